@@ -0,0 +1,178 @@
+//! Loading files once, and sharing the result.
+//!
+//! [`Assets`] memoizes everything by path and type, so asking for the same image twice decodes it
+//! once. Loading can also be started early on a background thread with [`Assets::prefetch`].
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::mpsc::{Receiver, Sender};
+
+use eyre::WrapErr as _;
+use wgpu::util::DeviceExt as _;
+
+use crate::renderer::Graphics;
+
+/// Something that can be loaded from a file.
+///
+/// Assets are `Send + Sync`, so they can be loaded on one thread and used on another.
+pub trait Asset: Any + Send + Sync + Sized {
+    fn load(path: &Path) -> eyre::Result<Self>;
+}
+
+/// The raw contents of a file.
+impl Asset for Vec<u8> {
+    fn load(path: &Path) -> eyre::Result<Self> {
+        Ok(std::fs::read(path)?)
+    }
+}
+
+/// A decoded image, in any format the `image` crate was built with.
+impl Asset for image::RgbaImage {
+    fn load(path: &Path) -> eyre::Result<Self> {
+        Ok(image::ImageReader::open(path)?
+            .with_guessed_format()?
+            .decode()?
+            .to_rgba8())
+    }
+}
+
+type Loaded = Arc<dyn Any + Send + Sync>;
+type Key = (PathBuf, TypeId);
+
+/// A cache of loaded assets, keyed by path.
+pub struct Assets {
+    loaded: HashMap<Key, Loaded>,
+    textures: HashMap<PathBuf, wgpu::Texture>,
+
+    /// Finished prefetches, picked up by [`Self::receive_prefetched`].
+    sender: Sender<(Key, eyre::Result<Loaded>)>,
+    receiver: Receiver<(Key, eyre::Result<Loaded>)>,
+}
+
+impl Default for Assets {
+    fn default() -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        Self {
+            loaded: HashMap::new(),
+            textures: HashMap::new(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl Assets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load `path` as a `T`, or return the copy loaded earlier.
+    pub fn get<T: Asset>(&mut self, path: impl AsRef<Path>) -> eyre::Result<Arc<T>> {
+        let path = path.as_ref();
+        let key = (path.to_owned(), TypeId::of::<T>());
+        if let Some(loaded) = self.loaded.get(&key) {
+            return Ok(downcast(loaded.clone()));
+        }
+
+        let asset = Arc::new(T::load(path).wrap_err_with(|| format!("Failed to load {path:?}"))?);
+        self.loaded.insert(key, asset.clone());
+        Ok(asset)
+    }
+
+    /// Load the image at `path` into an sRGB texture that can be sampled in shaders.
+    ///
+    /// The returned texture is a cheap handle to the cached one.
+    pub fn texture(
+        &mut self,
+        gfx: &Graphics,
+        path: impl AsRef<Path>,
+    ) -> eyre::Result<wgpu::Texture> {
+        let path = path.as_ref();
+        if let Some(texture) = self.textures.get(path) {
+            return Ok(texture.clone());
+        }
+
+        let image = self.get::<image::RgbaImage>(path)?;
+        let texture = gfx.device.create_texture_with_data(
+            &gfx.queue,
+            &wgpu::TextureDescriptor {
+                label: path.to_str(),
+                size: wgpu::Extent3d {
+                    width: image.width(),
+                    height: image.height(),
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            image.as_raw(),
+        );
+        self.textures.insert(path.to_owned(), texture.clone());
+        Ok(texture)
+    }
+
+    /// Start loading `path` as a `T` on a background thread.
+    ///
+    /// The result ends up in the cache once [`Self::receive_prefetched`] is called after it
+    /// finished. Without threads, as on the web, it is loaded right away instead.
+    pub fn prefetch<T: Asset>(&mut self, path: impl Into<PathBuf>) {
+        let key = (path.into(), TypeId::of::<T>());
+        if self.loaded.contains_key(&key) {
+            return;
+        }
+
+        let load = move || {
+            let result = T::load(&key.0).map(|asset| Arc::new(asset) as Loaded);
+            (key, result)
+        };
+        if cfg!(target_arch = "wasm32") {
+            let _ = self.sender.send(load());
+        } else {
+            let sender = self.sender.clone();
+            std::thread::spawn(move || {
+                let _ = sender.send(load());
+            });
+        }
+    }
+
+    /// Move finished prefetches into the cache. Call once per frame.
+    pub fn receive_prefetched(&mut self) {
+        while let Ok(((path, type_id), result)) = self.receiver.try_recv() {
+            match result {
+                Ok(asset) => {
+                    self.loaded.insert((path, type_id), asset);
+                }
+                Err(error) => log::warn!("Failed to prefetch {path:?}: {error:#}"),
+            }
+        }
+    }
+
+    /// Forget everything loaded from `path`, so it is loaded from disk again next time.
+    ///
+    /// Handles returned earlier keep the old contents.
+    pub fn reload(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        self.loaded
+            .retain(|(loaded_path, _), _| loaded_path != path);
+        self.textures.remove(path);
+    }
+
+    /// Whether anything has been loaded from `path`.
+    pub fn contains(&self, path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        self.textures.contains_key(path) || self.loaded.keys().any(|(loaded, _)| loaded == path)
+    }
+}
+
+fn downcast<T: Asset>(loaded: Loaded) -> Arc<T> {
+    loaded
+        .downcast()
+        .unwrap_or_else(|_| unreachable!("assets are keyed by their type"))
+}
@@ -0,0 +1,121 @@
+//! A window of internals for diagnosing the ui and renderer, toggled with [`TOGGLE_SHORTCUT`].
+//!
+//! It is never open on startup, and nothing about it is saved.
+
+use std::time::Duration;
+
+use crate::frame_time::FrameTime;
+use crate::renderer::ResourceStats;
+use crate::toolkit::UiExt as _;
+
+/// Opens and closes the debug overlay.
+pub const TOGGLE_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::F12);
+
+/// What the renderer measured last frame, for [`show`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RenderStats {
+    pub frame_time: FrameTime,
+    pub gpu_time: Option<Duration>,
+    pub resources: Option<ResourceStats>,
+}
+
+/// Show the debug overlay while `open`: frame timings, the renderer's resources, egui's own
+/// inspectors, and switches for egui's layout debugging.
+pub fn show(ctx: &egui::Context, open: &mut bool, stats: &RenderStats) {
+    egui::Window::new("Debug")
+        .id(egui::Id::new("debug_overlay"))
+        .open(open)
+        .default_width(360.0)
+        .vscroll(true)
+        .show(ctx, |ui| {
+            egui::CollapsingHeader::new("Frame")
+                .default_open(true)
+                .show(ui, |ui| frame_ui(ui, stats));
+
+            egui::CollapsingHeader::new("Layout")
+                .default_open(true)
+                .show(ui, layout_ui);
+
+            egui::CollapsingHeader::new("Textures").show(ui, |ui| ctx.texture_ui(ui));
+            egui::CollapsingHeader::new("Memory").show(ui, |ui| ctx.memory_ui(ui));
+            egui::CollapsingHeader::new("Inspection").show(ui, |ui| ctx.inspection_ui(ui));
+        });
+}
+
+fn frame_ui(ui: &mut egui::Ui, stats: &RenderStats) {
+    const MIB: f64 = 1024.0 * 1024.0;
+    let millis = |duration: Duration| format!("{:.2} ms", duration.as_secs_f64() * 1e3);
+
+    ui.property_grid("debug_frame", |grid| {
+        let raw = stats.frame_time.raw;
+        grid.row("FPS", |ui| {
+            ui.label(format!("{:.0}", 1.0 / raw.as_secs_f64().max(f64::EPSILON)))
+        });
+        grid.row("Frame", |ui| ui.label(millis(raw)));
+        grid.row("Smoothed", |ui| ui.label(millis(stats.frame_time.smoothed)));
+        grid.row("GPU", |ui| match stats.gpu_time {
+            Some(gpu_time) => ui.label(millis(gpu_time)),
+            None => ui.weak("Not measured"),
+        });
+
+        let Some(resources) = stats.resources else {
+            return;
+        };
+        grid.row("UI textures", |ui| {
+            ui.label(format!(
+                "{} ({:.1} MiB)",
+                resources.ui_textures,
+                resources.ui_texture_bytes as f64 / MIB
+            ))
+        });
+        if let Some(allocated) = resources.device_allocated_bytes {
+            grid.row("Device allocated", |ui| {
+                ui.label(format!("{:.1} MiB", allocated as f64 / MIB))
+            });
+        }
+        if let Some(reserved) = resources.device_reserved_bytes {
+            grid.row("Device reserved", |ui| {
+                ui.label(format!("{:.1} MiB", reserved as f64 / MIB))
+            });
+        }
+    });
+}
+
+fn layout_ui(ui: &mut egui::Ui) {
+    let ctx = ui.ctx().clone();
+
+    let mut debug_on_hover = ctx.debug_on_hover();
+    if ui
+        .checkbox(&mut debug_on_hover, "Debug on hover")
+        .on_hover_text("Show the widget under the pointer, and how it was laid out")
+        .changed()
+    {
+        ctx.set_debug_on_hover(debug_on_hover);
+    }
+
+    let debug = ctx.style().debug;
+    let mut show_widget_rects = debug.show_interactive_widgets;
+    let mut show_widget_hits = debug.show_widget_hits;
+    let mut show_unaligned = debug.show_unaligned;
+    let mut changed = false;
+    changed |= ui
+        .checkbox(&mut show_widget_rects, "Widget rects")
+        .on_hover_text("Outline every interactive widget")
+        .changed();
+    changed |= ui
+        .checkbox(&mut show_widget_hits, "Widget hits")
+        .on_hover_text("Show which widgets the pointer hits")
+        .changed();
+    changed |= ui
+        .checkbox(&mut show_unaligned, "Unaligned")
+        .on_hover_text("Mark shapes that aren't on whole pixels")
+        .changed();
+    if changed {
+        ctx.all_styles_mut(|style| {
+            style.debug.show_interactive_widgets = show_widget_rects;
+            style.debug.show_widget_hits = show_widget_hits;
+            style.debug.show_unaligned = show_unaligned;
+        });
+    }
+}
@@ -0,0 +1,61 @@
+//! Errors the user should see, collected from wherever they happen.
+
+use std::sync::Mutex;
+
+use crate::toolkit;
+use crate::toolkit::HasDesignTokens as _;
+
+/// Errors shown in a window until dismissed, see [`super::App::errors`].
+///
+/// Also a resource, so systems can report errors. [`Self::push`] only needs a shared reference for
+/// that.
+#[derive(Debug, Default)]
+pub struct ErrorSink {
+    errors: Mutex<Vec<eyre::Report>>,
+}
+
+impl ErrorSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show `report` to the user, and log it.
+    pub fn push(&self, report: eyre::Report) {
+        log::error!("{report:#}");
+        self.errors.lock().unwrap().push(report);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.lock().unwrap().is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.lock().unwrap().len()
+    }
+
+    pub fn clear(&mut self) {
+        self.errors.get_mut().unwrap().clear();
+    }
+
+    /// Each error with [`toolkit::error::show`], newest first, with buttons to dismiss them.
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        let tokens = ui.tokens();
+        let errors = self.errors.get_mut().unwrap();
+        let mut dismissed = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (index, report) in errors.iter().enumerate().rev() {
+                ui.push_id(index, |ui| {
+                    toolkit::error::show(ui, report);
+                    if ui.small_button("Dismiss").clicked() {
+                        dismissed = Some(index);
+                    }
+                });
+                ui.add_space(tokens.view_padding() as f32);
+            }
+        });
+        if let Some(index) = dismissed {
+            // Dismissed, so the report is of no further use
+            let _ = errors.remove(index);
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! Undo and redo.
+//!
+//! Edits can be recorded in one of two ways, which trade memory for effort:
+//!
+//! - As [`Command`]s in a [`History`]. Each entry only stores what it changed, so thousands of
+//!   them are cheap, but every kind of edit needs code to revert itself.
+//! - As [`Snapshots`] of the whole world, made with [`scene::save_to_string`] before each edit.
+//!   Any edit can be undone without extra code, but every entry costs as much memory as a saved
+//!   scene, taking one is proportional to the size of the world, and only components registered
+//!   with the [`SceneRegistry`] are restored. Best kept to coarse edits and small capacities.
+
+use std::collections::VecDeque;
+
+use hecs::World;
+
+use super::scene::{self, SceneRegistry};
+
+/// An edit to the world that can be reverted.
+pub trait Command {
+    /// Shown in the Edit menu, e.g. "Move Star".
+    fn name(&self) -> &str;
+    fn apply(&mut self, world: &mut World);
+    fn revert(&mut self, world: &mut World);
+}
+
+/// A bounded undo stack, with the entries that were undone kept around for redo.
+pub struct History<T> {
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    capacity: usize,
+}
+
+impl<T> History<T> {
+    /// Keep at most `capacity` entries to undo, forgetting the oldest past that.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Record a new entry. Anything that was undone can no longer be redone.
+    pub fn push(&mut self, entry: T) {
+        self.redo.clear();
+        self.push_undo(entry);
+    }
+
+    /// Move the newest entry over to the redo stack, and return it so it can be reverted.
+    pub fn undo(&mut self) -> Option<&mut T> {
+        let entry = self.undo.pop_back()?;
+        self.redo.push(entry);
+        self.redo.last_mut()
+    }
+
+    /// Move the most recently undone entry back, and return it so it can be re-applied.
+    pub fn redo(&mut self) -> Option<&mut T> {
+        let entry = self.redo.pop()?;
+        self.push_undo(entry);
+        self.undo.back_mut()
+    }
+
+    /// What [`Self::undo`] would return.
+    pub fn peek_undo(&self) -> Option<&T> {
+        self.undo.back()
+    }
+
+    /// What [`Self::redo`] would return.
+    pub fn peek_redo(&self) -> Option<&T> {
+        self.redo.last()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Keeps to the capacity, forgetting the oldest entry to make room.
+    fn push_undo(&mut self, entry: T) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.undo.len() == self.capacity {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(entry);
+    }
+}
+
+impl History<Box<dyn Command>> {
+    /// Apply `command` to `world`, and record it.
+    pub fn execute(&mut self, world: &mut World, mut command: Box<dyn Command>) {
+        command.apply(world);
+        self.push(command);
+    }
+
+    /// Revert the newest command. Returns `false` if there was nothing to undo.
+    pub fn undo_command(&mut self, world: &mut World) -> bool {
+        match self.undo() {
+            Some(command) => {
+                command.revert(world);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Re-apply the most recently undone command. Returns `false` if there was nothing to redo.
+    pub fn redo_command(&mut self, world: &mut World) -> bool {
+        match self.redo() {
+            Some(command) => {
+                command.apply(world);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Snapshot based undo, which restores the whole world at once.
+///
+/// Restoring replaces `world` with a new one, so components that aren't registered, like gpu
+/// resources, are lost. Entity ids are kept, so handles to entities stay valid.
+pub struct Snapshots {
+    past: VecDeque<String>,
+    future: Vec<String>,
+    capacity: usize,
+}
+
+impl Snapshots {
+    /// Keep at most `capacity` snapshots to undo, forgetting the oldest past that.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            past: VecDeque::new(),
+            future: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Snapshot `world`. Call right before an edit.
+    pub fn record(&mut self, world: &World, registry: &SceneRegistry) -> eyre::Result<()> {
+        let snapshot = scene::save_to_string(world, registry)?;
+        self.future.clear();
+        self.push_past(snapshot);
+        Ok(())
+    }
+
+    /// Restore the newest snapshot. Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self, world: &mut World, registry: &SceneRegistry) -> eyre::Result<bool> {
+        let Some(snapshot) = self.past.back() else {
+            return Ok(false);
+        };
+        let restored = scene::load_from_str(registry, snapshot)?;
+        self.future.push(scene::save_to_string(world, registry)?);
+        self.past.pop_back();
+        *world = restored;
+        Ok(true)
+    }
+
+    /// Restore the state from before the last undo. Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self, world: &mut World, registry: &SceneRegistry) -> eyre::Result<bool> {
+        let Some(snapshot) = self.future.last() else {
+            return Ok(false);
+        };
+        let restored = scene::load_from_str(registry, snapshot)?;
+        self.push_past(scene::save_to_string(world, registry)?);
+        self.future.pop();
+        *world = restored;
+        Ok(true)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.past.clear();
+        self.future.clear();
+    }
+
+    /// Keeps to the capacity, forgetting the oldest snapshot to make room.
+    fn push_past(&mut self, snapshot: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.past.len() == self.capacity {
+            self.past.pop_front();
+        }
+        self.past.push_back(snapshot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::scene_registry;
+    use crate::math::Transform;
+
+    /// The entries that can be undone, newest first.
+    fn undo_stack(history: &History<i32>) -> Vec<i32> {
+        history.undo.iter().rev().copied().collect()
+    }
+
+    #[test]
+    fn undo_and_redo_in_order() {
+        let mut history = History::new(8);
+        assert!(!history.can_undo() && !history.can_redo());
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.redo(), None);
+
+        for entry in 1..=3 {
+            history.push(entry);
+        }
+        assert_eq!(history.peek_undo(), Some(&3));
+        assert_eq!(history.undo().copied(), Some(3));
+        assert_eq!(history.undo().copied(), Some(2));
+        assert_eq!(history.peek_redo(), Some(&2));
+        assert_eq!(undo_stack(&history), [1]);
+
+        assert_eq!(history.redo().copied(), Some(2));
+        assert_eq!(undo_stack(&history), [2, 1]);
+        assert_eq!(history.peek_redo(), Some(&3));
+
+        // A new entry forks the history, so what was undone is gone
+        history.push(4);
+        assert!(!history.can_redo());
+        assert_eq!(undo_stack(&history), [4, 2, 1]);
+
+        history.clear();
+        assert!(!history.can_undo() && !history.can_redo());
+    }
+
+    #[test]
+    fn history_keeps_to_capacity() {
+        let mut history = History::new(3);
+        for entry in 1..=5 {
+            history.push(entry);
+        }
+        assert_eq!(undo_stack(&history), [5, 4, 3]);
+
+        // Undoing everything and redoing it again loses nothing
+        while history.undo().is_some() {}
+        assert_eq!(undo_stack(&history), []);
+        while history.redo().is_some() {}
+        assert_eq!(undo_stack(&history), [5, 4, 3]);
+
+        let mut history = History::new(0);
+        history.push(1);
+        assert!(!history.can_undo());
+        assert_eq!(history.undo(), None);
+    }
+
+    #[test]
+    fn snapshots_restore_and_keep_to_capacity() {
+        let registry = scene_registry();
+        let mut world = World::new();
+        let entity = world.spawn((Transform::from_xyz(0.0, 0.0, 0.0),));
+        let x = |world: &World| world.get::<&Transform>(entity).unwrap().translation.x;
+
+        let mut snapshots = Snapshots::new(2);
+        for step in 1..=3 {
+            snapshots.record(&world, &registry).unwrap();
+            world.get::<&mut Transform>(entity).unwrap().translation.x = step as f32;
+        }
+        assert_eq!(snapshots.past.len(), 2);
+
+        // The snapshot before the first edit was forgotten
+        assert!(snapshots.undo(&mut world, &registry).unwrap());
+        assert_eq!(x(&world), 2.0);
+        assert!(snapshots.undo(&mut world, &registry).unwrap());
+        assert_eq!(x(&world), 1.0);
+        assert!(!snapshots.undo(&mut world, &registry).unwrap());
+
+        assert!(snapshots.redo(&mut world, &registry).unwrap());
+        assert_eq!(x(&world), 2.0);
+        assert!(snapshots.redo(&mut world, &registry).unwrap());
+        assert_eq!(x(&world), 3.0);
+        assert!(!snapshots.redo(&mut world, &registry).unwrap());
+        assert_eq!(snapshots.past.len(), 2);
+
+        snapshots.record(&world, &registry).unwrap();
+        assert!(!snapshots.can_redo());
+        assert_eq!(snapshots.past.len(), 2);
+    }
+}
@@ -0,0 +1,153 @@
+//! Queryable keyboard and mouse state, for logic that polls input every frame.
+
+use std::collections::HashSet;
+
+use winit::event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+/// Approximate height of a line, for turning line based scrolling into pixels.
+const SCROLL_LINE_HEIGHT: f32 = 20.0;
+
+/// Keyboard and mouse state, built up from winit events over a frame.
+///
+/// Input that egui used (e.g. typing into a text field) is not seen as pressed, but releases
+/// always are, so keys never get stuck down.
+#[derive(Clone, Debug, Default)]
+pub struct InputState {
+    keys_down: HashSet<KeyCode>,
+    keys_pressed: HashSet<KeyCode>,
+    keys_released: HashSet<KeyCode>,
+
+    buttons_down: HashSet<MouseButton>,
+    buttons_pressed: HashSet<MouseButton>,
+    buttons_released: HashSet<MouseButton>,
+
+    /// In physical pixels, relative to the top left of the window.
+    mouse_position: Option<glam::Vec2>,
+    mouse_delta: glam::Vec2,
+    /// In physical pixels.
+    scroll_delta: glam::Vec2,
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update from a window event. `consumed` is whether egui used it.
+    pub fn handle_window_event(&mut self, event: &WindowEvent, consumed: bool) {
+        match event {
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(code),
+                        state,
+                        repeat,
+                        ..
+                    },
+                ..
+            } => match state {
+                ElementState::Pressed if !consumed && !repeat => {
+                    self.keys_down.insert(*code);
+                    self.keys_pressed.insert(*code);
+                }
+                ElementState::Released => {
+                    if self.keys_down.remove(code) {
+                        self.keys_released.insert(*code);
+                    }
+                }
+                ElementState::Pressed => {}
+            },
+            WindowEvent::MouseInput { state, button, .. } => match state {
+                ElementState::Pressed if !consumed => {
+                    self.buttons_down.insert(*button);
+                    self.buttons_pressed.insert(*button);
+                }
+                ElementState::Released => {
+                    if self.buttons_down.remove(button) {
+                        self.buttons_released.insert(*button);
+                    }
+                }
+                ElementState::Pressed => {}
+            },
+            WindowEvent::CursorMoved { position, .. } => {
+                let position = glam::vec2(position.x as f32, position.y as f32);
+                if let Some(previous) = self.mouse_position
+                    && !consumed
+                {
+                    self.mouse_delta += position - previous;
+                }
+                self.mouse_position = Some(position);
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.mouse_position = None;
+            }
+            WindowEvent::MouseWheel { delta, .. } if !consumed => {
+                self.scroll_delta += match delta {
+                    MouseScrollDelta::LineDelta(x, y) => glam::vec2(*x, *y) * SCROLL_LINE_HEIGHT,
+                    MouseScrollDelta::PixelDelta(delta) => {
+                        glam::vec2(delta.x as f32, delta.y as f32)
+                    }
+                };
+            }
+            // We won't hear about releases while unfocused:
+            WindowEvent::Focused(false) => {
+                self.keys_released.extend(self.keys_down.drain());
+                self.buttons_released.extend(self.buttons_down.drain());
+            }
+            _ => {}
+        }
+    }
+
+    /// Forget the per-frame state, like presses and deltas. Call once every frame has run.
+    pub fn end_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.buttons_pressed.clear();
+        self.buttons_released.clear();
+        self.mouse_delta = glam::Vec2::ZERO;
+        self.scroll_delta = glam::Vec2::ZERO;
+    }
+
+    /// Whether `key` is held down.
+    pub fn is_down(&self, key: KeyCode) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    /// Whether `key` went down this frame.
+    pub fn just_pressed(&self, key: KeyCode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    /// Whether `key` went up this frame.
+    pub fn just_released(&self, key: KeyCode) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    pub fn is_button_down(&self, button: MouseButton) -> bool {
+        self.buttons_down.contains(&button)
+    }
+
+    pub fn button_just_pressed(&self, button: MouseButton) -> bool {
+        self.buttons_pressed.contains(&button)
+    }
+
+    pub fn button_just_released(&self, button: MouseButton) -> bool {
+        self.buttons_released.contains(&button)
+    }
+
+    /// Position of the mouse in physical pixels, or `None` if it is outside the window.
+    pub fn mouse_position(&self) -> Option<glam::Vec2> {
+        self.mouse_position
+    }
+
+    /// How far the mouse moved this frame, in physical pixels.
+    pub fn mouse_delta(&self) -> glam::Vec2 {
+        self.mouse_delta
+    }
+
+    /// How far was scrolled this frame, in physical pixels.
+    pub fn scroll_delta(&self) -> glam::Vec2 {
+        self.scroll_delta
+    }
+}
@@ -0,0 +1,1227 @@
+use std::f32;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use egui::Color32;
+use egui::epaint::ViewportInPixels;
+use eyre::WrapErr as _;
+use hecs::World;
+use winit::dpi::LogicalSize;
+use winit::window::{Icon, Window, WindowAttributes};
+
+use crate::components::{
+    BloomCompositeMode, Camera, Global, PanOrbitController, Pipeline, Selected, Star,
+    inspector_registry, propagate_transforms, update_pan_orbit_camera,
+};
+use crate::frame_time::FrameTime;
+use crate::math::{Projection, Transform};
+use crate::renderer::{
+    DebugLines, DrawCameraCallback, GraphicsConfig, ReferenceGrid, ResourceStats, UiCallback,
+};
+use crate::state::{BlackHole2dState, BlackHole3dState, FractalState, SpaceState, State};
+use crate::toolkit;
+use crate::toolkit::HasDesignTokens as _;
+use crate::toolkit::UiExt as _;
+use crate::toolkit::console::{LogBuffer, console_ui};
+use crate::toolkit::drop_overlay::drop_overlay_ui;
+use crate::toolkit::inspector::{InspectorRegistry, world_ui};
+use crate::toolkit::shortcuts::{self, Shortcuts};
+
+/// How many edits can be undone.
+const UNDO_CAPACITY: usize = 256;
+
+pub const UNDO_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+pub const REDO_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(
+    egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+    egui::Key::Z,
+);
+/// Redoes too, as is usual on Windows.
+pub const REDO_ALT_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Y);
+
+pub const ZOOM_IN_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Equals);
+/// Zooms in too, from the numpad or on layouts where `=` needs shift.
+pub const ZOOM_IN_ALT_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Plus);
+pub const ZOOM_OUT_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Minus);
+pub const RESET_ZOOM_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Num0);
+
+pub const PAUSE_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::F8);
+pub const STEP_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::F10);
+
+/// How many times egui may lay out the ui in a single frame, when a widget asks it to with
+/// [`egui::Context::request_discard`] because it needs to measure itself first.
+const MAX_UI_PASSES: usize = 3;
+
+/// Size the window opens at, in points.
+pub const DEFAULT_WINDOW_SIZE: LogicalSize<f64> = LogicalSize::new(1280.0, 720.0);
+/// Smallest the window can be resized to, in points.
+pub const MIN_WINDOW_SIZE: LogicalSize<f64> = LogicalSize::new(480.0, 320.0);
+
+/// How far the ui can be zoomed out and in, on top of the monitor's own scale factor.
+pub const UI_ZOOM_RANGE: RangeInclusive<f32> = 0.5..=3.0;
+/// How much one notch of the mouse wheel or a zoom shortcut zooms the ui by.
+pub const UI_ZOOM_STEP: f32 = 1.1;
+
+/// How far the world advances on a single step while paused.
+pub const FIXED_STEP: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+pub mod assets;
+pub mod debug_overlay;
+pub mod error_sink;
+pub mod history;
+pub mod input;
+pub mod recent_files;
+pub mod recorder;
+pub mod scene;
+pub mod schedule;
+pub mod settings;
+pub mod tasks;
+
+use assets::Assets;
+use error_sink::ErrorSink;
+use history::{Command, History};
+use input::InputState;
+use recent_files::RecentFiles;
+use recorder::{Recorder, RecorderConfig};
+use schedule::{Resources, Schedule, Stage};
+use tasks::{TaskHandle, TaskPool};
+
+pub struct App {
+    global: hecs::Entity,
+
+    state: State,
+    prev_state: Option<State>,
+    black_hole_2d: BlackHole2dState,
+    black_hole_3d: BlackHole3dState,
+    fractal: FractalState,
+    space: SpaceState,
+
+    /// Per-frame logic that isn't tied to a particular simulation state.
+    schedule: Schedule,
+    resources: Resources,
+    history: History<Box<dyn Command>>,
+
+    inspector: InspectorRegistry,
+    log_buffer: LogBuffer,
+    /// Every shortcut the app handles, for the cheat sheet.
+    shortcuts: Shortcuts,
+
+    /// Redraw every frame, even when nothing in the UI changed.
+    continuous_rendering: bool,
+    /// Upper bound on the frame rate, if any.
+    max_fps: Option<f32>,
+    /// Resolution to render at, relative to the window
+    render_scale: f32,
+    /// Frames the gpu may queue up before presenting.
+    frame_latency: u32,
+    /// Physical pixels shape edges are faded out over, or `None` for hard edges.
+    ui_feathering: Option<f32>,
+    /// Size of the ui relative to the monitor's scale factor, see [`Self::set_ui_zoom`].
+    ui_zoom: f32,
+    /// Present nothing new when a frame would look the same as the last one.
+    skip_unchanged_frames: bool,
+    /// Stop advancing the world, while the ui keeps running.
+    paused: bool,
+    /// Advance the world by a single [`FIXED_STEP`] next frame, while paused.
+    step_requested: bool,
+    /// How long the last frame took on the gpu, if known.
+    gpu_time: Option<Duration>,
+    /// Gpu resources held by the renderer, while they are shown.
+    resource_stats: Option<ResourceStats>,
+    /// Light or dark, or following the OS.
+    theme_preference: egui::ThemePreference,
+
+    /// Files currently dragged over the window.
+    hovered_files: Vec<PathBuf>,
+    /// Files opened lately, for the File menu.
+    recent_files: RecentFiles,
+    /// The file dialog from File > Open, while it is showing.
+    open_dialog: Option<TaskHandle<Option<PathBuf>>>,
+    /// Captures frames while recording, see [`Self::toggle_recording`].
+    recorder: Recorder,
+    /// Recordings being encoded, and where they are saved to.
+    saving_recordings: Vec<TaskHandle<eyre::Result<PathBuf>>>,
+
+    /// Ask before quitting, instead of exiting as soon as the window is closed.
+    confirm_close: bool,
+    /// The quit prompt is showing.
+    close_requested: bool,
+    /// The user confirmed the quit prompt, so the event loop should exit.
+    close_confirmed: bool,
+
+    show_post_processing: bool,
+    show_inspector: bool,
+    show_console: bool,
+    show_settings: bool,
+    show_gizmos: bool,
+    show_grid: bool,
+    show_resource_stats: bool,
+    show_debug_overlay: bool,
+    show_shortcuts: bool,
+}
+
+/// What to do when the user asks to close the window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseAction {
+    /// Exit right away.
+    Exit,
+    /// Keep running, e.g. to ask the user first.
+    Cancel,
+}
+
+pub struct StarPhysics {
+    velocity: glam::Vec3,
+    mass: f32,
+}
+
+pub struct StarAcceleration(glam::Vec3);
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl App {
+    pub fn new() -> Self {
+        Self {
+            global: hecs::Entity::DANGLING,
+
+            state: State::BlackHole2d,
+            prev_state: None,
+            black_hole_2d: BlackHole2dState::new(),
+            black_hole_3d: BlackHole3dState::new(),
+            fractal: FractalState::new(),
+            space: SpaceState::new(),
+
+            schedule: default_schedule(),
+            resources: {
+                let mut resources = Resources::new();
+                resources.insert(InputState::new());
+                resources.insert(Assets::new());
+                resources.insert(ErrorSink::new());
+                resources.insert(TaskPool::new());
+                resources
+            },
+            history: History::new(UNDO_CAPACITY),
+
+            inspector: inspector_registry(),
+            log_buffer: LogBuffer::default(),
+            shortcuts: default_shortcuts(),
+
+            continuous_rendering: true,
+            max_fps: None,
+            render_scale: 1.0,
+            frame_latency: GraphicsConfig::default().frame_latency,
+            ui_feathering: ui_feathering_from_env(),
+            ui_zoom: 1.0,
+            skip_unchanged_frames: true,
+            paused: false,
+            step_requested: false,
+            gpu_time: None,
+            resource_stats: None,
+            theme_preference: egui::ThemePreference::System,
+
+            hovered_files: Vec::new(),
+            recent_files: RecentFiles::load_default(),
+            open_dialog: None,
+            recorder: Recorder::new(RecorderConfig::from_env()),
+            saving_recordings: Vec::new(),
+
+            confirm_close: false,
+            close_requested: false,
+            close_confirmed: false,
+
+            show_post_processing: false,
+            show_inspector: false,
+            show_console: false,
+            show_settings: false,
+            show_gizmos: false,
+            show_grid: false,
+            show_resource_stats: false,
+            show_debug_overlay: false,
+            show_shortcuts: false,
+        }
+    }
+
+    /// Show the records from `log_buffer` in the console window.
+    pub fn with_log_buffer(mut self, log_buffer: LogBuffer) -> Self {
+        self.log_buffer = log_buffer;
+        self
+    }
+
+    /// Ask the user to confirm before quitting.
+    pub fn with_close_confirmation(mut self, confirm_close: bool) -> Self {
+        self.confirm_close = confirm_close;
+        self
+    }
+
+    /// How the main window should be created: title, size limits and icon.
+    ///
+    /// Sizes are logical, in points, so the window comes up the same size on any monitor. The
+    /// event loop turns them into pixels for the monitor the window opens on.
+    pub fn window_attributes(&self) -> WindowAttributes {
+        let attributes = Window::default_attributes()
+            .with_title("Stellar")
+            .with_inner_size(DEFAULT_WINDOW_SIZE)
+            .with_min_inner_size(MIN_WINDOW_SIZE);
+
+        match window_icon() {
+            Ok(icon) => attributes.with_window_icon(Some(icon)),
+            Err(error) => {
+                log::warn!("Failed to load window icon: {error:#}");
+                attributes
+            }
+        }
+    }
+
+    pub fn ui_context(&self) -> egui::Context {
+        let ctx = egui::Context::default();
+        ctx.options_mut(|options| {
+            options.max_passes = MAX_UI_PASSES.try_into().expect("at least one pass");
+            // Zoom is kept in `ui_zoom` instead, with its own shortcuts
+            options.zoom_with_keyboard = false;
+            options.zoom_factor = self.ui_zoom;
+        });
+
+        toolkit::apply_style_and_install_loaders(&ctx);
+        // Replaces the toolkit's default
+        toolkit::set_feathering(&ctx, self.ui_feathering);
+
+        ctx
+    }
+
+    /// Whether the scene is animating, and so must be redrawn even when the UI is idle.
+    pub fn is_animating(&self) -> bool {
+        self.continuous_rendering
+    }
+
+    /// Redraw every frame, rather than only when the UI changes.
+    pub fn set_continuous_rendering(&mut self, continuous_rendering: bool) {
+        self.continuous_rendering = continuous_rendering;
+    }
+
+    /// The camera of the current simulation, if it has one.
+    pub fn active_camera(&self) -> Option<hecs::Entity> {
+        match self.state {
+            State::BlackHole2d => None,
+            State::BlackHole3d => Some(self.black_hole_3d.camera()),
+            State::Fractal => Some(self.fractal.camera()),
+            State::Space => Some(self.space.camera()),
+        }
+    }
+
+    /// The camera to draw orientation gizmos for, if they are enabled.
+    pub fn gizmo_camera(&self) -> Option<hecs::Entity> {
+        self.active_camera().filter(|_| self.show_gizmos)
+    }
+
+    /// The camera to draw a ground grid for, and how it looks, if the grid is enabled.
+    pub fn reference_grid(&self, ctx: &egui::Context) -> Option<(hecs::Entity, ReferenceGrid)> {
+        let camera = self.active_camera().filter(|_| self.show_grid)?;
+        let colors = toolkit::scene_colors(ctx.theme());
+        let grid = ReferenceGrid::default().with_colors(colors.grid_minor, colors.grid_major);
+        Some((camera, grid))
+    }
+
+    /// Queue this frame's world space debug lines: the world axes, while gizmos are enabled.
+    pub fn draw_debug_lines(&self, ctx: &egui::Context, lines: &mut DebugLines) {
+        if self.gizmo_camera().is_none() {
+            return;
+        }
+        let tokens = ctx.tokens();
+        let origin = glam::Vec3::ZERO;
+        lines.draw_line(origin, glam::Vec3::X, tokens.axis_color_x);
+        lines.draw_line(origin, glam::Vec3::Y, tokens.axis_color_y);
+        lines.draw_line(origin, glam::Vec3::Z, tokens.axis_color_z);
+    }
+
+    pub fn max_fps(&self) -> Option<f32> {
+        self.max_fps
+    }
+
+    /// Cap the frame rate, or pass `None` to draw as fast as vsync allows.
+    pub fn set_max_fps(&mut self, max_fps: Option<f32>) {
+        self.max_fps = max_fps.filter(|fps| *fps > 0.0);
+    }
+
+    /// See [`crate::renderer::Graphics::set_frame_latency`].
+    pub fn frame_latency(&self) -> u32 {
+        self.frame_latency
+    }
+
+    pub fn set_frame_latency(&mut self, frame_latency: u32) {
+        self.frame_latency = frame_latency;
+    }
+
+    /// See [`toolkit::set_feathering`].
+    pub fn ui_feathering(&self) -> Option<f32> {
+        self.ui_feathering
+    }
+
+    pub fn set_ui_feathering(&mut self, ui_feathering: Option<f32>) {
+        self.ui_feathering = ui_feathering;
+    }
+
+    /// See [`Self::set_ui_zoom`].
+    pub fn ui_zoom(&self) -> f32 {
+        self.ui_zoom
+    }
+
+    /// Make the whole ui larger or smaller, like zooming a web page, within [`UI_ZOOM_RANGE`].
+    ///
+    /// This multiplies the monitor's scale factor rather than replacing it, so a zoom of 1 is the
+    /// size the OS asks for on any monitor. Text is laid out at the combined scale, so it stays
+    /// sharp.
+    pub fn set_ui_zoom(&mut self, ui_zoom: f32) {
+        self.ui_zoom = ui_zoom.clamp(*UI_ZOOM_RANGE.start(), *UI_ZOOM_RANGE.end());
+    }
+
+    /// Zoom in by `factor`, or out when it is less than one, e.g. on Ctrl+scroll.
+    pub fn zoom_ui_by(&mut self, factor: f32) {
+        self.set_ui_zoom(self.ui_zoom * factor);
+    }
+
+    /// See [`crate::renderer::Renderer::set_render_scale`].
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale
+    }
+
+    pub fn set_render_scale(&mut self, render_scale: f32) {
+        self.render_scale = render_scale;
+    }
+
+    /// Whether frames that would look the same as the last one should be skipped, see
+    /// [`crate::renderer::Renderer::needs_redraw`].
+    pub fn skips_unchanged_frames(&self) -> bool {
+        self.skip_unchanged_frames && !self.is_animating()
+    }
+
+    /// Called whenever the set of files dragged over the window changes, with an empty slice once
+    /// they leave or are dropped.
+    pub fn on_file_hovered(&mut self, hovered: &[PathBuf]) {
+        self.hovered_files = hovered.to_vec();
+    }
+
+    /// Called for each file dropped on the window.
+    ///
+    /// All files from a single drop are delivered together, before the next frame.
+    pub fn on_file_dropped(&mut self, _world: &mut World, path: PathBuf) {
+        log::info!("Dropped file {}", path.display());
+        self.open_file(path);
+    }
+
+    fn open_file(&mut self, path: PathBuf) {
+        if let Err(error) =
+            std::fs::metadata(&path).wrap_err_with(|| format!("Failed to open {path:?}"))
+        {
+            self.errors_mut().push(error);
+            return;
+        }
+        // The file may have been edited since it was loaded
+        self.assets_mut().reload(&path);
+        self.recent_files.push(path);
+    }
+
+    /// Called when the user asks to close the window.
+    ///
+    /// Returning [`CloseAction::Cancel`] keeps the app running. Until [`Self::close_confirmed`]
+    /// returns `true`, that is.
+    pub fn on_close_requested(&mut self, _world: &mut World) -> CloseAction {
+        if !self.confirm_close || self.close_confirmed {
+            return CloseAction::Exit;
+        }
+
+        self.close_requested = true;
+        CloseAction::Cancel
+    }
+
+    /// The user confirmed that they want to quit, after a cancelled close request.
+    pub fn close_confirmed(&self) -> bool {
+        self.close_confirmed
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Stop or resume advancing the world. The ui and camera controls keep running.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.step_requested = false;
+    }
+
+    /// While paused, advance the world by exactly one [`FIXED_STEP`] next frame.
+    pub fn step(&mut self) {
+        if self.paused {
+            self.step_requested = true;
+        }
+    }
+
+    pub fn theme_preference(&self) -> egui::ThemePreference {
+        self.theme_preference
+    }
+
+    /// Force a light or dark theme, or follow the OS with [`egui::ThemePreference::System`].
+    pub fn set_theme_preference(&mut self, preference: egui::ThemePreference) {
+        self.theme_preference = preference;
+    }
+
+    /// Called when the OS switches between light and dark mode.
+    pub fn on_system_theme_changed(&mut self, ctx: &egui::Context, theme: egui::Theme) {
+        log::info!("System theme changed to {theme:?}");
+        if self.theme_preference == egui::ThemePreference::System {
+            toolkit::set_theme_preference(ctx, self.theme_preference);
+        }
+    }
+
+    /// Apply an edit to the world, so that it can be undone.
+    pub fn execute(&mut self, world: &mut World, command: impl Command + 'static) {
+        self.history.execute(world, Box::new(command));
+    }
+
+    /// Revert the last edit, e.g. on Ctrl+Z.
+    pub fn undo(&mut self, world: &mut World) {
+        self.history.undo_command(world);
+    }
+
+    /// Re-apply the last undone edit, e.g. on Ctrl+Shift+Z.
+    pub fn redo(&mut self, world: &mut World) {
+        self.history.redo_command(world);
+    }
+
+    pub fn history(&self) -> &History<Box<dyn Command>> {
+        &self.history
+    }
+
+    /// Show how long the renderer's last frame took on the gpu.
+    pub fn set_gpu_time(&mut self, gpu_time: Option<Duration>) {
+        self.gpu_time = gpu_time;
+    }
+
+    /// Whether the status bar or the debug overlay shows [`ResourceStats`], so they are worth
+    /// collecting.
+    pub fn shows_resource_stats(&self) -> bool {
+        self.show_resource_stats || self.show_debug_overlay
+    }
+
+    /// Every shortcut the app handles, as listed in the cheat sheet. Register more here when
+    /// adding them.
+    pub fn shortcuts_mut(&mut self) -> &mut Shortcuts {
+        &mut self.shortcuts
+    }
+
+    /// Every shortcut the app handles, for the event loop to dispatch keys through.
+    pub fn shortcuts(&self) -> &Shortcuts {
+        &self.shortcuts
+    }
+
+    /// Whether a modal is open that Escape should close, rather than ask to quit.
+    pub fn shows_modal(&self) -> bool {
+        self.show_shortcuts
+    }
+
+    /// Open or close the debug overlay, e.g. on [`debug_overlay::TOGGLE_SHORTCUT`].
+    pub fn toggle_debug_overlay(&mut self) {
+        self.show_debug_overlay = !self.show_debug_overlay;
+    }
+
+    /// Start recording, or stop and save what was recorded, e.g. on [`recorder::TOGGLE_SHORTCUT`].
+    ///
+    /// Recordings are encoded on the task pool, and saved to [`RecorderConfig::directory`].
+    pub fn toggle_recording(&mut self) {
+        if !self.recorder.is_recording() {
+            if cfg!(target_arch = "wasm32") {
+                log::warn!("Recording isn't available on the web, there is nowhere to save it");
+                return;
+            }
+            log::info!("Recording started");
+            self.recorder.start();
+            return;
+        }
+
+        let Some(recording) = self.recorder.stop() else {
+            // Also when the platform doesn't let frames be copied out of the window
+            log::warn!("Recording stopped before any frames were captured");
+            return;
+        };
+        let path = recording.default_path(&self.recorder.config().directory);
+        log::info!(
+            "Saving {} frames ({:.1} s) to {}",
+            recording.len(),
+            recording.duration().as_secs_f32(),
+            path.display()
+        );
+        let task = self
+            .tasks_mut()
+            .spawn(async move { recording.save(&path).map(|()| path) });
+        self.saving_recordings.push(task);
+    }
+
+    /// Takes frames from the render loop while recording.
+    pub fn recorder_mut(&mut self) -> &mut Recorder {
+        &mut self.recorder
+    }
+
+    /// Show what gpu resources the renderer holds.
+    pub fn set_resource_stats(&mut self, resource_stats: Option<ResourceStats>) {
+        self.resource_stats = resource_stats;
+    }
+
+    pub fn start(&mut self, world: &mut World) {
+        let mut global = Global::default();
+        global.bloom.composite_mode = BloomCompositeMode::Additive;
+        global.bloom.intensity = 0.3;
+        self.global = world.spawn(hecs::EntityBuilder::new().add(global).build());
+
+        // Initialize initial state
+        match self.state {
+            State::BlackHole2d => self.black_hole_2d.start(world),
+            State::Fractal => self.fractal.start(world),
+            State::Space => self.space.start(world),
+            State::BlackHole3d => self.black_hole_3d.start(world),
+        }
+    }
+
+    /// Step the simulation by a frame, before the ui is drawn. Returns how far the world advanced.
+    fn advance(&mut self, world: &mut World, ui: &egui::Ui, delta_time: Duration) -> Duration {
+        // How far the world advances, as opposed to the wall clock `delta_time` the ui uses
+        let world_delta_time = if !self.paused {
+            delta_time
+        } else if std::mem::take(&mut self.step_requested) {
+            FIXED_STEP
+        } else {
+            Duration::ZERO
+        };
+
+        self.assets_mut().receive_prefetched();
+
+        // Open whatever was picked once the file dialog closes
+        if let Some(dialog) = &mut self.open_dialog {
+            let picked = dialog.poll();
+            if !dialog.is_running() {
+                self.open_dialog = None;
+            }
+            if let Some(path) = picked.flatten() {
+                self.open_file(path);
+            }
+        }
+
+        // Report recordings once they are saved
+        let tasks: &TaskPool = self.resources.get_or_insert_default();
+        self.recorder.receive(tasks);
+        let mut saved = Vec::new();
+        self.saving_recordings.retain_mut(|task| {
+            saved.extend(task.poll());
+            task.is_running()
+        });
+        for result in saved {
+            match result {
+                Ok(path) => log::info!("Saved recording to {}", path.display()),
+                Err(error) => self.errors_mut().push(error),
+            }
+        }
+
+        // Pick up changes to the theme preference
+        if ui.ctx().options(|o| o.theme_preference) != self.theme_preference {
+            toolkit::set_theme_preference(ui.ctx(), self.theme_preference);
+        }
+        if toolkit::feathering(ui.ctx()) != self.ui_feathering {
+            toolkit::set_feathering(ui.ctx(), self.ui_feathering);
+        }
+        if ui.ctx().zoom_factor() != self.ui_zoom {
+            ui.ctx().set_zoom_factor(self.ui_zoom);
+        }
+
+        self.schedule
+            .run_stage(Stage::PreUpdate, world, &self.resources, world_delta_time);
+
+        // Update camera positions
+        for (transform, camera, controller) in
+            world.query_mut::<(&mut Transform, &mut Camera, &mut PanOrbitController)>()
+        {
+            ui.input(|input| {
+                update_pan_orbit_camera(input, delta_time, transform, camera, controller);
+            });
+        }
+
+        // Update individual state objects
+        match self.state {
+            State::BlackHole2d => self.black_hole_2d.update(world, world_delta_time),
+            State::BlackHole3d => self.black_hole_3d.update(world, world_delta_time),
+            State::Fractal => self.fractal.update(world, world_delta_time),
+            State::Space => self.space.update(world, world_delta_time),
+        }
+        self.schedule
+            .run_stage(Stage::Update, world, &self.resources, world_delta_time);
+
+        world_delta_time
+    }
+
+    pub fn update(
+        &mut self,
+        world: &mut World,
+        ui: &mut egui::Ui,
+        screen: [u32; 2],
+        frame_time: FrameTime,
+    ) {
+        // Animations and the simulation move by the smoothed time, readouts show the raw one
+        let delta_time = frame_time.smoothed;
+        // `Context::run_ui` runs this again if a pass was discarded, but the world only advances
+        // once per frame
+        let world_delta_time = if ui.ctx().current_pass_index() == 0 {
+            self.advance(world, ui, delta_time)
+        } else {
+            Duration::ZERO
+        };
+
+        // Draw Top Panel
+        egui::Panel::top("top").show_inside(ui, |ui| {
+            egui::containers::menu::MenuBar::new().ui(ui, |ui| {
+                ui.menu_button("File", |ui| {
+                    if ui
+                        .add_enabled(self.open_dialog.is_none(), egui::Button::new("Open…"))
+                        .clicked()
+                    {
+                        let mut dialog = toolkit::dialog::FileDialog::new().title("Open File");
+                        // Start next to the last file opened
+                        if let Some(directory) = self
+                            .recent_files
+                            .paths()
+                            .first()
+                            .and_then(|path| path.parent())
+                        {
+                            dialog = dialog.directory(directory);
+                        }
+                        self.open_dialog = Some(self.tasks_mut().spawn(dialog.open_file()));
+                    }
+                    ui.menu_button("Open Recent", |ui| {
+                        if let Some(path) = self.recent_files.recent_files_menu(ui) {
+                            self.open_file(path);
+                        }
+                    });
+                    ui.separator();
+                    if ui.button("Settings…").clicked() {
+                        self.show_settings = true;
+                    }
+                });
+                ui.menu_button("Edit", |ui| {
+                    let undo = self.history.peek_undo().map(|command| command.name());
+                    if ui
+                        .add_enabled(
+                            undo.is_some(),
+                            egui::Button::new(format!("Undo {}", undo.unwrap_or_default()))
+                                .shortcut_text(toolkit::format_shortcut(&UNDO_SHORTCUT)),
+                        )
+                        .clicked()
+                    {
+                        self.history.undo_command(world);
+                    }
+                    let redo = self.history.peek_redo().map(|command| command.name());
+                    if ui
+                        .add_enabled(
+                            redo.is_some(),
+                            egui::Button::new(format!("Redo {}", redo.unwrap_or_default()))
+                                .shortcut_text(toolkit::format_shortcut(&REDO_SHORTCUT)),
+                        )
+                        .clicked()
+                    {
+                        self.history.redo_command(world);
+                    }
+                });
+                ui.menu_button("Simulation", |ui| {
+                    ui.selectable_value(&mut self.state, State::BlackHole2d, "BlackHole2d");
+                    ui.selectable_value(&mut self.state, State::BlackHole3d, "BlackHole3d");
+                    ui.selectable_value(&mut self.state, State::Fractal, "Fractal");
+                    ui.selectable_value(&mut self.state, State::Space, "Space");
+                });
+                ui.menu_button("Graphics", |ui| {
+                    if ui.button("Post-Processing").clicked() {
+                        self.show_post_processing = true;
+                    }
+                    ui.checkbox(&mut self.continuous_rendering, "Continuous Rendering");
+                    ui.checkbox(&mut self.skip_unchanged_frames, "Skip Unchanged Frames");
+                    ui.horizontal(|ui| {
+                        let mut capped = self.max_fps.is_some();
+                        if ui.checkbox(&mut capped, "FPS Cap").changed() {
+                            self.set_max_fps(capped.then_some(60.0));
+                        }
+                        if let Some(max_fps) = &mut self.max_fps {
+                            ui.add(
+                                egui::DragValue::new(max_fps)
+                                    .range(1.0..=1000.0)
+                                    .suffix(" fps"),
+                            );
+                        }
+                    });
+                    ui.menu_button("Render Scale", |ui| {
+                        for render_scale in [0.5, 1.0, 2.0] {
+                            ui.radio_value(
+                                &mut self.render_scale,
+                                render_scale,
+                                format!("{render_scale}×"),
+                            );
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        let mut feathered = self.ui_feathering.is_some();
+                        if ui.checkbox(&mut feathered, "UI Anti-Aliasing").changed() {
+                            self.ui_feathering = feathered.then(toolkit::default_feathering);
+                        }
+                        if let Some(feathering) = &mut self.ui_feathering {
+                            ui.add(
+                                egui::DragValue::new(feathering)
+                                    .range(0.25..=2.0)
+                                    .speed(0.05)
+                                    .suffix(" px"),
+                            )
+                            .on_hover_text("Width of the fade at the edges of shapes");
+                        }
+                    });
+                    ui.menu_button("Frame Latency", |ui| {
+                        ui.radio_value(&mut self.frame_latency, 1, "1 (Lowest Latency)");
+                        ui.radio_value(&mut self.frame_latency, 2, "2");
+                        ui.radio_value(&mut self.frame_latency, 3, "3 (Triple Buffering)");
+                    });
+                    ui.menu_button("Theme", |ui| {
+                        ui.radio_value(
+                            &mut self.theme_preference,
+                            egui::ThemePreference::System,
+                            "System",
+                        );
+                        ui.radio_value(
+                            &mut self.theme_preference,
+                            egui::ThemePreference::Dark,
+                            "Dark",
+                        );
+                        ui.radio_value(
+                            &mut self.theme_preference,
+                            egui::ThemePreference::Light,
+                            "Light",
+                        );
+                    });
+                });
+                ui.menu_button("Help", |ui| {
+                    if ui
+                        .add(egui::Button::new("Keyboard Shortcuts").shortcut_text(
+                            toolkit::format_shortcut(&shortcuts::CHEAT_SHEET_SHORTCUT),
+                        ))
+                        .clicked()
+                    {
+                        self.show_shortcuts = true;
+                    }
+                });
+                ui.menu_button("Debug", |ui| {
+                    if ui.button("Inspector").clicked() {
+                        self.show_inspector = true;
+                    }
+                    if ui.button("Console").clicked() {
+                        self.show_console = true;
+                    }
+                    ui.checkbox(&mut self.show_gizmos, "Gizmos");
+                    ui.checkbox(&mut self.show_grid, "Grid");
+                    ui.checkbox(&mut self.show_resource_stats, "Resource Stats");
+                    ui.checkbox(
+                        &mut self.show_debug_overlay,
+                        format!(
+                            "Debug Overlay ({})",
+                            toolkit::format_shortcut(&debug_overlay::TOGGLE_SHORTCUT)
+                        ),
+                    );
+                    let recording = if self.recorder.is_recording() {
+                        "Stop Recording"
+                    } else {
+                        "Start Recording"
+                    };
+                    if ui
+                        .add(
+                            egui::Button::new(recording).shortcut_text(toolkit::format_shortcut(
+                                &recorder::TOGGLE_SHORTCUT,
+                            )),
+                        )
+                        .clicked()
+                    {
+                        self.toggle_recording();
+                    }
+                    ui.separator();
+                    let mut paused = self.paused;
+                    let pause = format!("Pause ({})", toolkit::format_shortcut(&PAUSE_SHORTCUT));
+                    if ui.checkbox(&mut paused, pause).changed() {
+                        self.set_paused(paused);
+                    }
+                    if ui
+                        .add_enabled(
+                            self.paused,
+                            egui::Button::new("Step")
+                                .shortcut_text(toolkit::format_shortcut(&STEP_SHORTCUT)),
+                        )
+                        .clicked()
+                    {
+                        self.step();
+                    }
+                });
+            });
+        });
+
+        // Draw Status Bar
+        ui.status_bar(|bar| {
+            bar.left(|ui| {
+                let selected = world
+                    .query::<(hecs::Entity, &Selected)>()
+                    .iter()
+                    .next()
+                    .map(|(entity, _)| entity);
+                match selected {
+                    Some(entity) => ui.truncated_label(&format!("Entity {}", entity.id())),
+                    None => ui.truncated_label("Nothing selected"),
+                };
+            });
+            if self.paused || self.recorder.is_recording() {
+                bar.center(|ui| {
+                    let tokens = ui.tokens();
+                    if self.recorder.is_recording() {
+                        ui.label(egui::RichText::new("Recording").color(tokens.error_fg_color));
+                    }
+                    if self.paused {
+                        ui.label(egui::RichText::new("Paused").color(tokens.warn_fg_color));
+                    }
+                });
+            }
+            bar.right(|ui| {
+                let fps = 1.0 / frame_time.raw.as_secs_f64().max(f64::EPSILON);
+                ui.status_value("FPS", fps, 0..=0);
+                ui.status_value("CPU ms", frame_time.raw.as_secs_f64() * 1e3, 1..=1);
+                if let Some(gpu_time) = self.gpu_time {
+                    ui.status_value("GPU ms", gpu_time.as_secs_f64() * 1e3, 2..=2);
+                }
+                if self.show_resource_stats
+                    && let Some(stats) = self.resource_stats
+                {
+                    const MIB: f64 = 1024.0 * 1024.0;
+                    ui.status_value("Textures", stats.ui_textures as f64, 0..=0);
+                    ui.status_value("Texture MiB", stats.ui_texture_bytes as f64 / MIB, 1..=1);
+                    if let Some(allocated) = stats.device_allocated_bytes {
+                        ui.status_value("Device MiB", allocated as f64 / MIB, 1..=1);
+                    }
+                }
+            });
+        });
+
+        // Handle any state changes
+        if let Some(prev) = self.prev_state
+            && prev != self.state
+        {
+            match prev {
+                State::Fractal => self.fractal.finish(world),
+                State::BlackHole2d => self.black_hole_2d.finish(world),
+                State::Space => self.space.finish(world),
+                State::BlackHole3d => self.black_hole_3d.finish(world),
+            }
+
+            match self.state {
+                State::BlackHole2d => self.black_hole_2d.start(world),
+                State::BlackHole3d => self.black_hole_3d.start(world),
+                State::Fractal => self.fractal.start(world),
+                State::Space => self.space.start(world),
+            }
+        }
+        // We are definiately in the correct state now
+        self.prev_state = Some(self.state);
+
+        // Draw individual state ui
+        match self.state {
+            State::BlackHole2d => self.black_hole_2d.ui(world, ui, screen, delta_time),
+            State::BlackHole3d => self.black_hole_3d.ui(world, ui, screen),
+            State::Fractal => self.fractal.ui(world, ui, screen),
+            State::Space => self.space.ui(world, ui, screen),
+        }
+
+        // Draw post-processing window
+        if self.show_post_processing {
+            egui::Window::new("Post-Processing")
+                .open(&mut self.show_post_processing)
+                .show(ui, |ui| {
+                    let mut global = world.get::<&mut Global>(self.global).unwrap();
+
+                    ui.label("Tonemapping");
+                    ui.add(
+                        egui::Slider::new(&mut global.tonemap.pre_saturation, 0.0..=10.0)
+                            .text("Pre-Saturation"),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut global.tonemap.post_saturation, 0.0..=10.0)
+                            .text("Post-Saturation"),
+                    );
+                    ui.add(egui::Slider::new(&mut global.tonemap.gamma, 0.0..=10.0).text("Gamma"));
+                    ui.add(
+                        egui::Slider::new(&mut global.tonemap.exposure, -10.0..=10.0)
+                            .text("Exposure"),
+                    );
+
+                    ui.label("Bloom");
+                    ui.add(
+                        egui::Slider::new(&mut global.bloom.intensity, 0.0..=5.0).text("Intensity"),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut global.bloom.low_frequency_boost, 0.0..=5.0)
+                            .text("Low Frequency Boost"),
+                    );
+                    ui.add(
+                        egui::Slider::new(
+                            &mut global.bloom.low_frequency_boost_curvature,
+                            0.0..=5.0,
+                        )
+                        .text("Low Frequency Boost Curvature"),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut global.bloom.high_pass_frequency, 0.0..=5.0)
+                            .text("High Pass Frequency"),
+                    );
+                    egui::ComboBox::from_label("Composite Mode")
+                        .selected_text(format!("{:?}", global.bloom.composite_mode))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(
+                                &mut global.bloom.composite_mode,
+                                BloomCompositeMode::Additive,
+                                "Additive",
+                            );
+                            ui.selectable_value(
+                                &mut global.bloom.composite_mode,
+                                BloomCompositeMode::EnergyConserving,
+                                "EnergyConserving",
+                            );
+                        });
+                    ui.add(
+                        egui::Slider::new(&mut global.bloom.prefilter.threshold, 0.0..=5.0)
+                            .text("Threshold"),
+                    );
+                    ui.add(
+                        egui::Slider::new(
+                            &mut global.bloom.prefilter.threshold_softness,
+                            0.0..=5.0,
+                        )
+                        .text("Threshold Softness"),
+                    );
+                });
+        }
+
+        // Draw entity inspector
+        if self.show_inspector {
+            egui::Window::new("Inspector")
+                .open(&mut self.show_inspector)
+                .vscroll(true)
+                .show(ui, |ui| {
+                    world_ui(ui, world, &self.inspector);
+                });
+        }
+
+        // Draw log console
+        if self.show_console {
+            egui::Window::new("Console")
+                .open(&mut self.show_console)
+                .default_size([600.0, 300.0])
+                .show(ui, |ui| {
+                    console_ui(ui, &self.log_buffer);
+                });
+        }
+
+        // Draw preferences
+        if self.show_settings {
+            let mut open = true;
+            toolkit::settings::settings_window(ui.ctx(), &mut open, self);
+            self.show_settings = open;
+        }
+
+        // Draw errors until they are dismissed
+        let errors = self.errors_mut();
+        if !errors.is_empty() {
+            let mut open = true;
+            egui::Window::new(format!("Errors ({})", errors.len()))
+                .id(egui::Id::new("errors"))
+                .open(&mut open)
+                .default_width(480.0)
+                .show(ui, |ui| {
+                    errors.ui(ui);
+                    ui.separator();
+                    if ui.button("Dismiss All").clicked() {
+                        errors.clear();
+                    }
+                });
+            if !open {
+                errors.clear();
+            }
+        }
+
+        // Draw shortcut cheat sheet
+        if !self.show_shortcuts
+            && !ui.ctx().wants_keyboard_input()
+            && ui.input_mut(|input| input.consume_shortcut(&shortcuts::CHEAT_SHEET_SHORTCUT))
+        {
+            self.show_shortcuts = true;
+        }
+        shortcuts::cheat_sheet(ui.ctx(), &mut self.show_shortcuts, &self.shortcuts);
+
+        // Draw debug overlay
+        if self.show_debug_overlay {
+            let stats = debug_overlay::RenderStats {
+                frame_time,
+                gpu_time: self.gpu_time,
+                resources: self.resource_stats,
+            };
+            debug_overlay::show(ui.ctx(), &mut self.show_debug_overlay, &stats);
+        }
+
+        // Nothing wakes the event loop when a task finishes, so check back until they all have
+        if self.tasks_mut().is_busy() {
+            ui.ctx().request_repaint_after(tasks::POLL_INTERVAL);
+        }
+
+        // Draw file drop feedback
+        drop_overlay_ui(ui.ctx(), &self.hovered_files);
+
+        // Draw quit prompt
+        if self.close_requested {
+            let modal = egui::Modal::new(egui::Id::new("close_requested")).show(ui.ctx(), |ui| {
+                ui.set_width(ui.tokens().default_modal_width);
+                ui.heading("Quit Stellar?");
+                ui.label("Any unsaved changes will be lost.");
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.primary_button("Quit").clicked() {
+                        self.close_confirmed = true;
+                    }
+                    ui.secondary_button("Cancel").clicked()
+                })
+                .inner
+            });
+            if self.close_confirmed || modal.inner || modal.should_close() {
+                self.close_requested = false;
+            }
+        }
+
+        // Now that the app is done moving things, e.g. resolve the transform hierarchy
+        self.schedule
+            .run_stage(Stage::Render, world, &self.resources, world_delta_time);
+
+        // Presses and deltas only last a single frame
+        self.input_mut().end_frame();
+    }
+
+    /// Keyboard and mouse state, also available to systems as a resource.
+    pub fn input(&self) -> Option<&InputState> {
+        self.resources.get()
+    }
+
+    /// Feed window events in here, and they'll show up in [`Self::input`].
+    pub fn input_mut(&mut self) -> &mut InputState {
+        self.resources.get_or_insert_default()
+    }
+
+    /// Files loaded so far, see [`Assets`].
+    pub fn assets_mut(&mut self) -> &mut Assets {
+        self.resources.get_or_insert_default()
+    }
+
+    /// Errors shown to the user, also available to systems as a resource.
+    pub fn errors_mut(&mut self) -> &mut ErrorSink {
+        self.resources.get_or_insert_default()
+    }
+
+    /// Background work, also available to systems as a resource.
+    pub fn tasks_mut(&mut self) -> &mut TaskPool {
+        self.resources.get_or_insert_default()
+    }
+
+    /// Systems that run every frame, whatever the simulation state.
+    pub fn schedule_mut(&mut self) -> &mut Schedule {
+        &mut self.schedule
+    }
+
+    /// Singletons shared with the systems in [`Self::schedule_mut`].
+    pub fn resources_mut(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+
+    pub fn cleanup(&mut self, world: &mut World) {
+        match self.state {
+            State::Fractal => self.fractal.finish(world),
+            State::BlackHole2d => self.black_hole_2d.finish(world),
+            State::BlackHole3d => self.black_hole_3d.finish(world),
+            State::Space => self.space.finish(world),
+        }
+    }
+}
+
+fn default_schedule() -> Schedule {
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system(Stage::PreUpdate, |world, _, delta_time| {
+            // Update timers
+            for timer in world.query_mut::<&mut Global>() {
+                timer.time += delta_time;
+            }
+        })
+        .add_system(Stage::Render, |world, _, _| propagate_transforms(world));
+    schedule
+}
+
+/// The shortcuts handled by the app and the event loop, see [`App::shortcuts_mut`].
+///
+/// The event loop only acts on keys registered here, see [`Shortcuts::pressed`].
+fn default_shortcuts() -> Shortcuts {
+    use egui::{Key, KeyboardShortcut, Modifiers};
+
+    let key = |key| KeyboardShortcut::new(Modifiers::NONE, key);
+    let mut shortcuts = Shortcuts::new();
+    shortcuts
+        .register(
+            "General",
+            "Keyboard shortcuts",
+            shortcuts::CHEAT_SHEET_SHORTCUT,
+        )
+        .register("General", "Quit", key(Key::Escape))
+        .register("Edit", "Undo", UNDO_SHORTCUT)
+        .register("Edit", "Redo", REDO_SHORTCUT)
+        .register("Edit", "Redo", REDO_ALT_SHORTCUT)
+        .register("View", "Zoom in", ZOOM_IN_SHORTCUT)
+        .register("View", "Zoom in", ZOOM_IN_ALT_SHORTCUT)
+        .register("View", "Zoom out", ZOOM_OUT_SHORTCUT)
+        .register("View", "Reset zoom", RESET_ZOOM_SHORTCUT)
+        .register("Simulation", "Pause", PAUSE_SHORTCUT)
+        .register("Simulation", "Step while paused", STEP_SHORTCUT)
+        .register("Debug", "Debug overlay", debug_overlay::TOGGLE_SHORTCUT)
+        .register(
+            "Debug",
+            "Start or stop recording",
+            recorder::TOGGLE_SHORTCUT,
+        );
+    shortcuts
+}
+
+/// `STELLAR_UI_FEATHERING=<pixels>` overrides the toolkit's feathering, and `0` turns it off, e.g.
+/// to compare `--screenshot`s with and without it.
+fn ui_feathering_from_env() -> Option<f32> {
+    match std::env::var("STELLAR_UI_FEATHERING")
+        .ok()
+        .and_then(|value| value.parse::<f32>().ok())
+    {
+        Some(size_in_pixels) if size_in_pixels <= 0.0 => None,
+        Some(size_in_pixels) => Some(size_in_pixels),
+        None => Some(toolkit::default_feathering()),
+    }
+}
+
+fn window_icon() -> eyre::Result<Icon> {
+    let image =
+        image::load_from_memory(include_bytes!("../toolkit/data/logo_dark_mode.png"))?.into_rgba8();
+    let (width, height) = image.dimensions();
+    Ok(Icon::from_rgba(image.into_raw(), width, height)?)
+}
@@ -0,0 +1,161 @@
+//! The most recently opened files, remembered across runs.
+
+use std::path::{Path, PathBuf};
+
+use eyre::WrapErr as _;
+
+use crate::toolkit::menu::{RecentFileAction, recent_files_menu};
+
+/// How many files are remembered by default.
+pub const DEFAULT_CAPACITY: usize = 10;
+
+/// Recently opened files, most recent first, without duplicates.
+///
+/// Saved as RON to [`Self::path`] whenever it changes, if it has one.
+#[derive(Debug)]
+pub struct RecentFiles {
+    paths: Vec<PathBuf>,
+    capacity: usize,
+    /// Where the list is saved, or `None` to keep it in memory.
+    path: Option<PathBuf>,
+}
+
+impl RecentFiles {
+    /// An empty list of up to `capacity` files, kept in memory.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            paths: Vec::new(),
+            capacity,
+            path: None,
+        }
+    }
+
+    /// The list saved at `path`, or an empty one if there is none yet, saving changes back to it.
+    pub fn load(path: impl Into<PathBuf>, capacity: usize) -> eyre::Result<Self> {
+        let path = path.into();
+        let mut paths: Vec<PathBuf> = match std::fs::read_to_string(&path) {
+            Ok(text) => ron::from_str(&text)
+                .wrap_err_with(|| format!("Failed to parse recent files {path:?}"))?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => {
+                return Err(error)
+                    .wrap_err_with(|| format!("Failed to read recent files {path:?}"));
+            }
+        };
+        paths.truncate(capacity);
+
+        Ok(Self {
+            paths,
+            capacity,
+            path: Some(path),
+        })
+    }
+
+    /// The list in the config directory, see [`config_dir`]. Kept in memory if there is none, or
+    /// it can't be read.
+    pub fn load_default() -> Self {
+        let Some(dir) = config_dir() else {
+            return Self::new(DEFAULT_CAPACITY);
+        };
+        Self::load(dir.join("recent_files.ron"), DEFAULT_CAPACITY).unwrap_or_else(|error| {
+            log::warn!("{error:#}");
+            Self::new(DEFAULT_CAPACITY)
+        })
+    }
+
+    /// Where the list is saved, if anywhere.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Most recent first.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Move `path` to the top, dropping the oldest file when full.
+    pub fn push(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        // Spelled differently, but the same file
+        let canonical = std::fs::canonicalize(&path).unwrap_or(path);
+        self.paths.retain(|existing| *existing != canonical);
+        self.paths.insert(0, canonical);
+        self.paths.truncate(self.capacity);
+        self.save();
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        let len = self.paths.len();
+        self.paths.retain(|existing| existing != path);
+        if self.paths.len() != len {
+            self.save();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.paths.clear();
+        self.save();
+    }
+
+    /// The files as menu items, see [`recent_files_menu`].
+    ///
+    /// Returns the file that was chosen, to [`Self::push`] once it was opened. Missing files are
+    /// removed when chosen instead.
+    pub fn recent_files_menu(&mut self, ui: &mut egui::Ui) -> Option<PathBuf> {
+        match recent_files_menu(ui, &self.paths)? {
+            RecentFileAction::Open(path) => Some(path),
+            RecentFileAction::Remove(path) => {
+                self.remove(&path);
+                None
+            }
+            RecentFileAction::Clear => {
+                self.clear();
+                None
+            }
+        }
+    }
+
+    /// Write the list to [`Self::path`], logging failures since a stale list is harmless.
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Err(error) = write(path, &self.paths) {
+            log::warn!("Failed to save recent files to {path:?}: {error:#}");
+        }
+    }
+}
+
+fn write(path: &Path, paths: &[PathBuf]) -> eyre::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let text = ron::ser::to_string_pretty(paths, Default::default())?;
+    std::fs::write(path, text)?;
+    Ok(())
+}
+
+/// Where settings are kept between runs: `STELLAR_CONFIG_DIR` if set, and otherwise the
+/// platform's config directory. `None` on the web, or when the directory can't be found.
+pub fn config_dir() -> Option<PathBuf> {
+    if cfg!(target_arch = "wasm32") {
+        return None;
+    }
+    if let Some(dir) = std::env::var_os("STELLAR_CONFIG_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+
+    let env_dir = |name: &str| std::env::var_os(name).map(PathBuf::from);
+    let base = if cfg!(target_os = "windows") {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        env_dir("XDG_CONFIG_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".config")))
+    };
+    Some(base?.join("stellar"))
+}
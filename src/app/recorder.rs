@@ -0,0 +1,341 @@
+//! Recording the last few seconds of the window to an animated GIF or PNG, for demos and bug
+//! reports, toggled with [`TOGGLE_SHORTCUT`].
+//!
+//! While recording, presented frames are copied back from the gpu at up to
+//! [`RecorderConfig::fps`], scaled down to [`RecorderConfig::max_width`] on the task pool, and
+//! kept in a ring of [`RecorderConfig::max_frames`], so memory stays bounded however long it runs.
+//! Encoding happens on the task pool too, once recording stops, see
+//! [`super::App::toggle_recording`].
+
+use std::collections::VecDeque;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use eyre::WrapErr as _;
+use web_time::Instant;
+
+use super::tasks::{TaskHandle, TaskPool};
+use crate::renderer::FrameCapture;
+
+/// Starts and stops recording.
+pub const TOGGLE_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::F7);
+
+pub const DEFAULT_FPS: f32 = 15.0;
+/// How much of the end of a recording is kept.
+pub const DEFAULT_SECONDS: f32 = 10.0;
+/// Wider frames are scaled down to this many pixels across.
+pub const DEFAULT_MAX_WIDTH: u32 = 640;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecordingFormat {
+    /// Plays everywhere, but with at most 256 colors per frame.
+    #[default]
+    Gif,
+    /// Animated PNG, in full color, at the cost of larger files.
+    Apng,
+}
+
+impl RecordingFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Gif => "gif",
+            Self::Apng => "png",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RecorderConfig {
+    /// Most frames captured per second. Fewer are when the window redraws less often.
+    pub fps: f32,
+    /// Frames kept, dropping the oldest first.
+    pub max_frames: usize,
+    pub max_width: u32,
+    pub format: RecordingFormat,
+    /// Where recordings are saved.
+    pub directory: PathBuf,
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        Self {
+            fps: DEFAULT_FPS,
+            max_frames: (DEFAULT_FPS * DEFAULT_SECONDS) as usize,
+            max_width: DEFAULT_MAX_WIDTH,
+            format: RecordingFormat::default(),
+            directory: PathBuf::from("."),
+        }
+    }
+}
+
+impl RecorderConfig {
+    /// The defaults, overridden by `STELLAR_RECORDING_FPS=<fps>`,
+    /// `STELLAR_RECORDING_SECONDS=<seconds>`, `STELLAR_RECORDING_WIDTH=<pixels>`,
+    /// `STELLAR_RECORDING_FORMAT=gif|apng` and `STELLAR_RECORDING_DIR=<path>`.
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok()?.parse().ok()
+        }
+
+        let mut config = Self::default();
+        if let Some(fps) = var::<f32>("STELLAR_RECORDING_FPS").filter(|fps| *fps > 0.0) {
+            config.fps = fps;
+        }
+        let seconds = var::<f32>("STELLAR_RECORDING_SECONDS")
+            .filter(|seconds| *seconds > 0.0)
+            .unwrap_or(DEFAULT_SECONDS);
+        config.max_frames = ((config.fps * seconds) as usize).max(1);
+        if let Some(max_width) = var::<u32>("STELLAR_RECORDING_WIDTH").filter(|width| *width > 0) {
+            config.max_width = max_width;
+        }
+        match std::env::var("STELLAR_RECORDING_FORMAT").as_deref() {
+            Ok("apng") => config.format = RecordingFormat::Apng,
+            Ok("gif") | Err(_) => {}
+            Ok(other) => log::warn!("Unknown STELLAR_RECORDING_FORMAT {other:?}, using gif"),
+        }
+        if let Some(directory) = std::env::var_os("STELLAR_RECORDING_DIR") {
+            config.directory = directory.into();
+        }
+        config
+    }
+}
+
+/// Keeps the latest frames while armed, see the [module docs](self).
+pub struct Recorder {
+    config: RecorderConfig,
+    recording: bool,
+    /// When the last frame was captured, to keep to the frame rate.
+    last_capture: Option<Instant>,
+    /// Frames copied out, waiting on the gpu.
+    pending: VecDeque<(Instant, FrameCapture)>,
+    /// Frames read back, being scaled down on the task pool.
+    scaling: VecDeque<(Instant, TaskHandle<image::RgbaImage>)>,
+    /// Frames read back, oldest first, with when they were presented.
+    frames: VecDeque<(Instant, image::RgbaImage)>,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new(RecorderConfig::default())
+    }
+}
+
+impl Recorder {
+    pub fn new(config: RecorderConfig) -> Self {
+        Self {
+            config,
+            recording: false,
+            last_capture: None,
+            pending: VecDeque::new(),
+            scaling: VecDeque::new(),
+            frames: VecDeque::new(),
+        }
+    }
+
+    pub fn config(&self) -> &RecorderConfig {
+        &self.config
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Start over with no frames.
+    pub fn start(&mut self) {
+        self.recording = true;
+        self.last_capture = None;
+        self.pending.clear();
+        self.scaling.clear();
+        self.frames.clear();
+    }
+
+    /// Stop, and take what was recorded, or `None` if no frames were.
+    ///
+    /// Frames still on their way back from the gpu, or still being scaled down, are left out.
+    pub fn stop(&mut self) -> Option<Recording> {
+        self.recording = false;
+        self.receive_scaled();
+        self.pending.clear();
+        self.scaling.clear();
+        if self.frames.is_empty() {
+            return None;
+        }
+
+        let frame_interval = Duration::from_secs_f32(1.0 / self.config.fps);
+        let times: Vec<Instant> = self.frames.iter().map(|(time, _)| *time).collect();
+        // The window may not have redrawn for a while, which the delays keep
+        let delays = times
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .chain(std::iter::once(frame_interval))
+            .collect();
+        Some(Recording {
+            frames: self.frames.drain(..).map(|(_, frame)| frame).collect(),
+            delays,
+            format: self.config.format,
+        })
+    }
+
+    /// Whether to capture the frame presented at `now`.
+    pub fn wants_frame(&self, now: Instant) -> bool {
+        self.recording
+            && self
+                .last_capture
+                .is_none_or(|last| now - last >= Duration::from_secs_f32(1.0 / self.config.fps))
+    }
+
+    /// Keep the frame presented at `now`, once the encoder `capture` was made with is submitted.
+    pub fn push(&mut self, now: Instant, capture: FrameCapture) {
+        capture.map();
+        self.pending.push_back((now, capture));
+        self.last_capture = Some(now);
+    }
+
+    /// Pick up the frames the gpu has finished with. Call once a frame while recording.
+    ///
+    /// Frames are scaled down on `tasks`, so only small frames are kept without the render loop
+    /// waiting on it. They are kept once scaled, still in the order they were presented.
+    pub fn receive(&mut self, tasks: &TaskPool) {
+        while let Some((time, capture)) = self.pending.front() {
+            let Some(frame) = capture.try_read() else {
+                // Mapped in order, so later ones aren't ready either
+                break;
+            };
+            let time = *time;
+            self.pending.pop_front();
+
+            let frame = match frame {
+                Ok(frame) => frame,
+                Err(error) => {
+                    log::warn!("Dropped a recorded frame: {error:#}");
+                    continue;
+                }
+            };
+            let max_width = self.config.max_width;
+            let scaled = tasks.spawn(async move { scale_down(frame, max_width) });
+            self.scaling.push_back((time, scaled));
+        }
+        self.receive_scaled();
+    }
+
+    /// Keep the frames that have been scaled down, up to the first that is still being scaled.
+    fn receive_scaled(&mut self) {
+        while let Some((time, scaled)) = self.scaling.front_mut() {
+            let frame = scaled.poll();
+            if scaled.is_running() {
+                break;
+            }
+            let time = *time;
+            self.scaling.pop_front();
+
+            let Some(frame) = frame else {
+                log::warn!("Dropped a recorded frame, scaling it down failed");
+                continue;
+            };
+            // Frames of an animation all have the same size
+            if let Some((_, last)) = self.frames.back()
+                && last.dimensions() != frame.dimensions()
+            {
+                log::info!("The window was resized, restarting the recording");
+                self.frames.clear();
+            }
+            self.frames.push_back((time, frame));
+            while self.frames.len() > self.config.max_frames {
+                self.frames.pop_front();
+            }
+        }
+    }
+}
+
+fn scale_down(frame: image::RgbaImage, max_width: u32) -> image::RgbaImage {
+    let (width, height) = frame.dimensions();
+    if width <= max_width {
+        return frame;
+    }
+    let scaled_height = ((height as u64 * max_width as u64) / width as u64).max(1) as u32;
+    image::imageops::thumbnail(&frame, max_width, scaled_height)
+}
+
+/// The frames of a stopped recording, ready to encode.
+pub struct Recording {
+    frames: Vec<image::RgbaImage>,
+    /// How long each frame is shown.
+    delays: Vec<Duration>,
+    format: RecordingFormat,
+}
+
+impl Recording {
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.delays.iter().sum()
+    }
+
+    /// A new file in `directory`, named after the current time.
+    pub fn default_path(&self, directory: &Path) -> PathBuf {
+        let seconds = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        directory.join(format!("recording-{seconds}.{}", self.format.extension()))
+    }
+
+    /// Encode the recording and write it to `path`. Slow, so run it on the task pool.
+    pub fn save(self, path: &Path) -> eyre::Result<()> {
+        let file = std::fs::File::create(path)
+            .wrap_err_with(|| format!("Failed to create {}", path.display()))?;
+        let writer = BufWriter::new(file);
+        match self.format {
+            RecordingFormat::Gif => self.encode_gif(writer),
+            RecordingFormat::Apng => self.encode_apng(writer),
+        }
+        .wrap_err_with(|| format!("Failed to save recording to {}", path.display()))
+    }
+
+    fn encode_gif(self, writer: impl std::io::Write) -> eyre::Result<()> {
+        use image::codecs::gif::{GifEncoder, Repeat};
+
+        // Trades a little quality for much faster color quantization
+        let mut encoder = GifEncoder::new_with_speed(writer, 10);
+        encoder.set_repeat(Repeat::Infinite)?;
+        encoder.encode_frames(
+            self.frames
+                .into_iter()
+                .zip(self.delays)
+                .map(|(frame, delay)| {
+                    image::Frame::from_parts(
+                        frame,
+                        0,
+                        0,
+                        image::Delay::from_saturating_duration(delay),
+                    )
+                }),
+        )?;
+        Ok(())
+    }
+
+    fn encode_apng(self, writer: impl std::io::Write) -> eyre::Result<()> {
+        let (width, height) = self.frames[0].dimensions();
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        // Loops forever
+        encoder.set_animated(self.frames.len() as u32, 0)?;
+        let mut writer = encoder.write_header()?;
+        for (frame, delay) in self.frames.iter().zip(&self.delays) {
+            let millis = delay.as_millis().min(u16::MAX as u128) as u16;
+            writer.set_frame_delay(millis, 1000)?;
+            writer.write_image_data(frame.as_raw())?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+//! Saving and loading a [`hecs::World`] as a RON scene file.
+//!
+//! hecs is type-erased, so only components registered with a [`SceneRegistry`] are persisted.
+//! Each component is keyed by a stable name, rather than its Rust type name, so renaming a type
+//! doesn't break existing scenes.
+
+use std::any::TypeId;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use eyre::WrapErr as _;
+use ron::value::RawValue;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+type SerializeFn = fn(hecs::EntityRef<'_>) -> Option<eyre::Result<Box<RawValue>>>;
+type DeserializeFn = fn(&RawValue, &mut hecs::EntityBuilder) -> eyre::Result<()>;
+
+struct RegisteredComponent {
+    serialize: SerializeFn,
+    deserialize: DeserializeFn,
+}
+
+/// Maps component types to the stable names they are stored under.
+#[derive(Default)]
+pub struct SceneRegistry {
+    components: HashMap<&'static str, RegisteredComponent>,
+    names: HashMap<TypeId, &'static str>,
+}
+
+impl SceneRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a component to be saved under `name`.
+    pub fn register<T>(&mut self, name: &'static str) -> &mut Self
+    where
+        T: hecs::Component + Serialize + DeserializeOwned,
+    {
+        let previous = self.names.insert(TypeId::of::<T>(), name);
+        debug_assert!(previous.is_none(), "Component registered twice");
+        debug_assert!(
+            !self.components.contains_key(name),
+            "Component name {name:?} is already in use"
+        );
+
+        self.components.insert(
+            name,
+            RegisteredComponent {
+                serialize: serialize_component::<T>,
+                deserialize: deserialize_component::<T>,
+            },
+        );
+        self
+    }
+}
+
+/// Components are written with their own [`Serialize`] impl, and embedded in the scene as is.
+fn serialize_component<T>(entity: hecs::EntityRef<'_>) -> Option<eyre::Result<Box<RawValue>>>
+where
+    T: hecs::Component + Serialize,
+{
+    let component = entity.get::<&T>()?;
+    Some(RawValue::from_rust(&*component).map_err(eyre::Report::from))
+}
+
+fn deserialize_component<T>(value: &RawValue, builder: &mut hecs::EntityBuilder) -> eyre::Result<()>
+where
+    T: hecs::Component + DeserializeOwned,
+{
+    let component: T = value.into_rust()?;
+    builder.add(component);
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
+struct SceneFile {
+    entities: Vec<SceneEntity>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SceneEntity {
+    /// The [`hecs::Entity::to_bits`] of the entity, so references between entities survive a
+    /// round-trip.
+    id: u64,
+    /// Components by their registered name, each in the format of its own [`Serialize`] impl.
+    components: BTreeMap<String, Box<RawValue>>,
+}
+
+/// Write every entity in `world`, along with its registered components, to `path`.
+///
+/// Entities without any registered components are skipped.
+pub fn save(
+    world: &hecs::World,
+    registry: &SceneRegistry,
+    path: impl AsRef<Path>,
+) -> eyre::Result<()> {
+    let path = path.as_ref();
+
+    let text = save_to_string(world, registry)?;
+    std::fs::write(path, text).wrap_err_with(|| format!("Failed to write scene to {path:?}"))?;
+
+    Ok(())
+}
+
+/// Like [`save`], but returns the scene instead of writing it to a file.
+pub fn save_to_string(world: &hecs::World, registry: &SceneRegistry) -> eyre::Result<String> {
+    let mut entities = Vec::new();
+    for entity in world.iter() {
+        let mut components = BTreeMap::new();
+        for type_id in entity.component_types() {
+            let Some(&name) = registry.names.get(&type_id) else {
+                continue;
+            };
+            let serialize = registry.components[name].serialize;
+            if let Some(value) = serialize(entity) {
+                let value = value.wrap_err_with(|| format!("Failed to serialize {name}"))?;
+                components.insert(name.to_owned(), value);
+            }
+        }
+
+        if !components.is_empty() {
+            entities.push(SceneEntity {
+                id: entity.entity().to_bits().get(),
+                components,
+            });
+        }
+    }
+
+    Ok(ron::ser::to_string_pretty(
+        &SceneFile { entities },
+        Default::default(),
+    )?)
+}
+
+/// Read a scene written by [`save`] into a new world.
+///
+/// Components that aren't in the `registry` are skipped with a warning.
+pub fn load(registry: &SceneRegistry, path: impl AsRef<Path>) -> eyre::Result<hecs::World> {
+    let path = path.as_ref();
+
+    let text =
+        std::fs::read_to_string(path).wrap_err_with(|| format!("Failed to read scene {path:?}"))?;
+    load_from_str(registry, &text).wrap_err_with(|| format!("Failed to load scene {path:?}"))
+}
+
+/// Like [`load`], but reads the scene from a string made by [`save_to_string`].
+pub fn load_from_str(registry: &SceneRegistry, text: &str) -> eyre::Result<hecs::World> {
+    let scene: SceneFile = ron::from_str(text).wrap_err("Failed to parse scene")?;
+
+    let mut world = hecs::World::new();
+    let mut builder = hecs::EntityBuilder::new();
+
+    for SceneEntity { id, components } in scene.entities {
+        for (name, value) in components {
+            let Some(component) = registry.components.get(name.as_str()) else {
+                log::warn!("Skipping unknown component {name:?} in scene");
+                continue;
+            };
+            (component.deserialize)(&value, &mut builder)
+                .wrap_err_with(|| format!("Failed to deserialize {name}"))?;
+        }
+
+        // Keep the same entity id if we can, so any stored references remain valid:
+        match hecs::Entity::from_bits(id) {
+            Some(entity) if !world.contains(entity) => world.spawn_at(entity, builder.build()),
+            _ => {
+                log::warn!("Invalid entity id {id} in scene, spawning a new entity");
+                world.spawn(builder.build());
+            }
+        }
+    }
+
+    Ok(world)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Camera, Parent, Star, scene_registry};
+    use crate::math::Transform;
+
+    #[test]
+    fn save_load_round_trip() {
+        let registry = scene_registry();
+        let mut world = hecs::World::new();
+        let parent = world.spawn((
+            Transform::from_xyz(1.0, 2.0, 3.0),
+            Camera::perspective(1.0, 0.1, 100.0),
+        ));
+        let child = world.spawn((
+            Transform {
+                scale: glam::Vec3::splat(2.0),
+                ..Transform::from_xyz(-4.0, 0.5, 0.0)
+            },
+            Parent(parent),
+            Star {
+                temperature: 5778.0,
+                ..Default::default()
+            },
+        ));
+        // Not registered, so not saved
+        let unsaved = world.spawn((42_u32,));
+
+        let text = save_to_string(&world, &registry).unwrap();
+        let loaded = load_from_str(&registry, &text).unwrap();
+
+        assert_eq!(loaded.len(), 2);
+        assert!(!loaded.contains(unsaved));
+        assert_eq!(
+            *loaded.get::<&Transform>(parent).unwrap(),
+            Transform::from_xyz(1.0, 2.0, 3.0)
+        );
+        assert_eq!(
+            *loaded.get::<&Transform>(child).unwrap(),
+            *world.get::<&Transform>(child).unwrap()
+        );
+        assert_eq!(*loaded.get::<&Parent>(child).unwrap(), Parent(parent));
+        assert_eq!(loaded.get::<&Star>(child).unwrap().temperature, 5778.0);
+
+        let camera = loaded.get::<&Camera>(parent).unwrap();
+        assert!(camera.projection.is_perspective());
+        assert!(!loaded.satisfies::<&Camera>(child));
+
+        // Saving what was loaded gives back the same scene
+        assert_eq!(save_to_string(&loaded, &registry).unwrap(), text);
+    }
+}
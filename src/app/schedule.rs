@@ -0,0 +1,116 @@
+//! Ordering per-frame logic over a [`hecs::World`] into systems.
+//!
+//! A [`Schedule`] runs its systems stage by stage, and in the order they were added within a
+//! stage. Singletons that aren't entities, like settings or input, live in [`Resources`].
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use hecs::World;
+
+/// When in the frame a system runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    /// Before the simulation states update, e.g. advancing timers.
+    PreUpdate,
+    /// Alongside the simulation states.
+    Update,
+    /// After the ui, right before the frame is rendered.
+    Render,
+}
+
+/// A piece of per-frame logic.
+pub type System = Box<dyn FnMut(&mut World, &Resources, Duration)>;
+
+/// A type map of singletons, at most one per type.
+#[derive(Default)]
+pub struct Resources {
+    resources: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert `resource`, returning the previous one of the same type.
+    pub fn insert<T: 'static>(&mut self, resource: T) -> Option<T> {
+        self.resources
+            .insert(TypeId::of::<T>(), Box::new(resource))
+            .and_then(|previous| previous.downcast().ok())
+            .map(|previous| *previous)
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.resources
+            .remove(&TypeId::of::<T>())
+            .and_then(|resource| resource.downcast().ok())
+            .map(|resource| *resource)
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.resources.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.resources.get_mut(&TypeId::of::<T>())?.downcast_mut()
+    }
+
+    /// Get the resource of type `T`, inserting its default first if there is none.
+    pub fn get_or_insert_default<T: Default + 'static>(&mut self) -> &mut T {
+        self.resources
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(T::default()))
+            .downcast_mut()
+            .expect("resources are keyed by their type")
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<T>())
+    }
+}
+
+/// Systems, grouped by [`Stage`].
+#[derive(Default)]
+pub struct Schedule {
+    systems: Vec<(Stage, System)>,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a system to the end of `stage`.
+    pub fn add_system(
+        &mut self,
+        stage: Stage,
+        system: impl FnMut(&mut World, &Resources, Duration) + 'static,
+    ) -> &mut Self {
+        // Keep sorted by stage. The sort is stable, so systems within a stage stay in order.
+        self.systems.push((stage, Box::new(system)));
+        self.systems.sort_by_key(|(stage, _)| *stage);
+        self
+    }
+
+    /// Run every system in `stage`.
+    pub fn run_stage(
+        &mut self,
+        stage: Stage,
+        world: &mut World,
+        resources: &Resources,
+        delta_time: Duration,
+    ) {
+        for (_, system) in self.systems.iter_mut().filter(|(s, _)| *s == stage) {
+            system(world, resources, delta_time);
+        }
+    }
+
+    /// Run every stage, in order.
+    pub fn run(&mut self, world: &mut World, resources: &Resources, delta_time: Duration) {
+        for (_, system) in &mut self.systems {
+            system(world, resources, delta_time);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! The app's preferences: shown in the settings window, and remembered across runs.
+
+use std::path::{Path, PathBuf};
+
+use eyre::WrapErr as _;
+use serde::{Deserialize, Serialize};
+
+use super::App;
+use super::recent_files::config_dir;
+use crate::renderer::{GraphicsConfig, RENDER_SCALE_RANGE};
+use crate::toolkit;
+use crate::toolkit::settings::{Settings, SettingsPage};
+
+/// [`egui::ThemePreference`], which egui only makes serializable with a feature we don't need.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum Theme {
+    System,
+    Dark,
+    Light,
+}
+
+impl From<egui::ThemePreference> for Theme {
+    fn from(preference: egui::ThemePreference) -> Self {
+        match preference {
+            egui::ThemePreference::System => Self::System,
+            egui::ThemePreference::Dark => Self::Dark,
+            egui::ThemePreference::Light => Self::Light,
+        }
+    }
+}
+
+impl From<Theme> for egui::ThemePreference {
+    fn from(theme: Theme) -> Self {
+        match theme {
+            Theme::System => Self::System,
+            Theme::Dark => Self::Dark,
+            Theme::Light => Self::Light,
+        }
+    }
+}
+
+/// The settings saved between runs, see [`App::preferences`].
+///
+/// Missing fields keep their defaults, so files from older versions still load.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    theme: Theme,
+    continuous_rendering: bool,
+    skip_unchanged_frames: bool,
+    max_fps: Option<f32>,
+    render_scale: f32,
+    frame_latency: u32,
+    ui_feathering: Option<f32>,
+    ui_zoom: f32,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            theme: Theme::System,
+            continuous_rendering: true,
+            skip_unchanged_frames: true,
+            max_fps: None,
+            render_scale: 1.0,
+            frame_latency: GraphicsConfig::default().frame_latency,
+            ui_feathering: Some(toolkit::default_feathering()),
+            ui_zoom: 1.0,
+        }
+    }
+}
+
+impl Preferences {
+    /// Where preferences are saved: next to the recent files, in [`config_dir`].
+    pub fn default_path() -> Option<PathBuf> {
+        Some(config_dir()?.join("preferences.ron"))
+    }
+
+    /// The preferences saved at `path`, or the defaults if there are none yet.
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => ron::from_str(&text)
+                .wrap_err_with(|| format!("Failed to parse preferences {path:?}")),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => {
+                Err(error).wrap_err_with(|| format!("Failed to read preferences {path:?}"))
+            }
+        }
+    }
+
+    /// The preferences at [`Self::default_path`], or the defaults if they can't be read.
+    pub fn load_default() -> Self {
+        let Some(path) = Self::default_path() else {
+            return Self::default();
+        };
+        Self::load(&path).unwrap_or_else(|error| {
+            log::warn!("{error:#}");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = ron::ser::to_string_pretty(self, Default::default())?;
+        std::fs::write(path, text).wrap_err_with(|| format!("Failed to write preferences {path:?}"))
+    }
+
+    /// Save to [`Self::default_path`], logging failures.
+    pub fn save_default(&self) {
+        let Some(path) = Self::default_path() else {
+            return;
+        };
+        if let Err(error) = self.save(&path) {
+            log::warn!("{error:#}");
+        }
+    }
+}
+
+impl App {
+    /// Start out with the `preferences` from a previous run.
+    pub fn with_preferences(mut self, preferences: Preferences) -> Self {
+        self.theme_preference = preferences.theme.into();
+        self.continuous_rendering = preferences.continuous_rendering;
+        self.skip_unchanged_frames = preferences.skip_unchanged_frames;
+        self.set_max_fps(preferences.max_fps);
+        self.render_scale = preferences.render_scale;
+        self.frame_latency = preferences.frame_latency;
+        self.ui_feathering = preferences.ui_feathering;
+        self.set_ui_zoom(preferences.ui_zoom);
+        self
+    }
+
+    /// The current settings, to save for the next run.
+    pub fn preferences(&self) -> Preferences {
+        Preferences {
+            theme: self.theme_preference.into(),
+            continuous_rendering: self.continuous_rendering,
+            skip_unchanged_frames: self.skip_unchanged_frames,
+            max_fps: self.max_fps,
+            render_scale: self.render_scale,
+            frame_latency: self.frame_latency,
+            ui_feathering: self.ui_feathering,
+            ui_zoom: self.ui_zoom,
+        }
+    }
+}
+
+impl Settings for App {
+    fn categories(&self) -> Vec<&'static str> {
+        vec!["Appearance", "Performance"]
+    }
+
+    fn category_ui(&mut self, category: &str, page: &mut SettingsPage<'_, '_>) {
+        match category {
+            "Appearance" => {
+                page.setting("Theme", |ui| {
+                    let mut theme = Theme::from(self.theme_preference);
+                    let changed = ui.horizontal(|ui| {
+                        ui.selectable_value(&mut theme, Theme::System, "System")
+                            | ui.selectable_value(&mut theme, Theme::Dark, "Dark")
+                            | ui.selectable_value(&mut theme, Theme::Light, "Light")
+                    });
+                    self.theme_preference = theme.into();
+                    changed.inner
+                });
+                page.setting("UI Zoom", |ui| {
+                    let mut ui_zoom = self.ui_zoom;
+                    let response = ui
+                        .add(
+                            egui::DragValue::new(&mut ui_zoom)
+                                .range(super::UI_ZOOM_RANGE)
+                                .speed(0.01)
+                                .custom_formatter(|zoom, _| format!("{:.0}%", zoom * 100.0))
+                                .custom_parser(|text| {
+                                    let percent = text.trim().trim_end_matches('%').trim();
+                                    percent.parse::<f64>().ok().map(|percent| percent / 100.0)
+                                }),
+                        )
+                        .on_hover_text("Ctrl+scroll to zoom, Ctrl+0 to reset");
+                    self.set_ui_zoom(ui_zoom);
+                    response
+                });
+                page.setting("UI Anti-Aliasing", |ui| {
+                    let mut feathered = self.ui_feathering.is_some();
+                    let mut response = ui.checkbox(&mut feathered, "");
+                    if response.changed() {
+                        self.ui_feathering = feathered.then(toolkit::default_feathering);
+                    }
+                    if let Some(feathering) = &mut self.ui_feathering {
+                        response |= ui.add(
+                            egui::DragValue::new(feathering)
+                                .range(0.25..=2.0)
+                                .speed(0.05)
+                                .suffix(" px"),
+                        );
+                    }
+                    response
+                });
+            }
+            "Performance" => {
+                page.setting("Continuous Rendering", |ui| {
+                    ui.checkbox(&mut self.continuous_rendering, "")
+                });
+                page.setting("Skip Unchanged Frames", |ui| {
+                    ui.checkbox(&mut self.skip_unchanged_frames, "")
+                });
+                page.setting("FPS Cap", |ui| {
+                    let mut capped = self.max_fps.is_some();
+                    let mut response = ui.checkbox(&mut capped, "");
+                    if response.changed() {
+                        self.set_max_fps(capped.then_some(60.0));
+                    }
+                    if let Some(max_fps) = &mut self.max_fps {
+                        response |= ui.add(
+                            egui::DragValue::new(max_fps)
+                                .range(1.0..=1000.0)
+                                .suffix(" fps"),
+                        );
+                    }
+                    response
+                });
+                page.setting("Render Scale", |ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut self.render_scale)
+                            .range(RENDER_SCALE_RANGE)
+                            .speed(0.05)
+                            .suffix("×"),
+                    )
+                });
+                page.setting("Frame Latency", |ui| {
+                    ui.add(egui::Slider::new(&mut self.frame_latency, 1..=3))
+                });
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! Work that runs off the render loop, like parsing a large file, with results picked up a frame
+//! at a time.
+//!
+//! ```ignore
+//! let handle = app.tasks_mut().spawn(async move { parse(&path) });
+//! // Later, every frame:
+//! if let Some(parsed) = handle.poll() {
+//!     // ...
+//! } else if handle.is_running() {
+//!     ui.spinner();
+//! }
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// How often the ui wakes up to check on tasks while any are running, when nothing else redraws
+/// it. See [`TaskPool::is_busy`].
+pub const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Most worker threads a pool starts, however many cores there are.
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_WORKERS: usize = 4;
+
+#[cfg(not(target_arch = "wasm32"))]
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Runs tasks in the background: on a few worker threads natively, and on the browser's event loop
+/// on the web.
+///
+/// Also a resource, see [`super::App::tasks_mut`]. Tasks still running when the pool is dropped
+/// finish on their own, but nobody gets their results.
+#[derive(Debug)]
+pub struct TaskPool {
+    #[cfg(not(target_arch = "wasm32"))]
+    jobs: mpsc::Sender<Job>,
+    /// Tasks spawned and not yet finished.
+    running: Arc<AtomicUsize>,
+}
+
+impl Default for TaskPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskPool {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new() -> Self {
+        let workers = std::thread::available_parallelism()
+            .map_or(1, usize::from)
+            .min(MAX_WORKERS);
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(std::sync::Mutex::new(receiver));
+        for index in 0..workers {
+            let receiver = receiver.clone();
+            let spawned = std::thread::Builder::new()
+                .name(format!("task-worker-{index}"))
+                .spawn(move || {
+                    loop {
+                        // Let go of the lock before running the job, so the others can take one
+                        let job = receiver.lock().unwrap().recv();
+                        let Ok(job) = job else {
+                            // The pool was dropped
+                            break;
+                        };
+                        if std::panic::catch_unwind(std::panic::AssertUnwindSafe(job)).is_err() {
+                            log::error!("A background task panicked");
+                        }
+                    }
+                });
+            if let Err(error) = spawned {
+                log::error!("Failed to start a task worker: {error}");
+            }
+        }
+
+        Self {
+            jobs,
+            running: Arc::default(),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn new() -> Self {
+        Self {
+            running: Arc::default(),
+        }
+    }
+
+    /// Run `future` to completion on a worker thread, blocking it while waiting.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn<T, F>(&self, future: F) -> TaskHandle<T>
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.spawn_blocking(move || pollster::block_on(future))
+    }
+
+    /// Run `future` on the browser's event loop, in between frames.
+    #[cfg(target_arch = "wasm32")]
+    pub fn spawn<T, F>(&self, future: F) -> TaskHandle<T>
+    where
+        T: 'static,
+        F: Future<Output = T> + 'static,
+    {
+        let (sender, handle) = self.start();
+        wasm_bindgen_futures::spawn_local(async move {
+            sender.send(future.await);
+        });
+        handle
+    }
+
+    /// Run `work` on a worker thread.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn_blocking<T, F>(&self, work: F) -> TaskHandle<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (sender, handle) = self.start();
+        if self
+            .jobs
+            .send(Box::new(move || sender.send(work())))
+            .is_err()
+        {
+            log::error!("No task workers are running");
+        }
+        handle
+    }
+
+    /// Whether any task is still running. The ui should keep checking on them until none are,
+    /// even when nothing else changes.
+    pub fn is_busy(&self) -> bool {
+        self.running.load(Ordering::Acquire) > 0
+    }
+
+    fn start<T>(&self) -> (ResultSender<T>, TaskHandle<T>) {
+        let (sender, receiver) = mpsc::channel();
+        self.running.fetch_add(1, Ordering::AcqRel);
+        let sender = ResultSender {
+            sender,
+            running: self.running.clone(),
+        };
+        let handle = TaskHandle {
+            receiver,
+            status: TaskStatus::Running,
+        };
+        (sender, handle)
+    }
+}
+
+/// Hands a task's result back to its [`TaskHandle`], and counts the task as finished when dropped,
+/// even if it panicked first.
+struct ResultSender<T> {
+    sender: mpsc::Sender<T>,
+    running: Arc<AtomicUsize>,
+}
+
+impl<T> ResultSender<T> {
+    fn send(self, result: T) {
+        // The handle may have been dropped, if nobody wants the result anymore
+        let _ = self.sender.send(result);
+    }
+}
+
+impl<T> Drop for ResultSender<T> {
+    fn drop(&mut self) {
+        self.running.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Where a task stood the last time its [`TaskHandle`] was polled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskStatus {
+    Running,
+    /// The result was handed out by [`TaskHandle::poll`].
+    Finished,
+    /// The task panicked, or the pool was gone before it could start.
+    Failed,
+}
+
+/// The result of a task spawned on a [`TaskPool`], once it is ready.
+///
+/// Dropping the handle doesn't cancel the task, only throws away its result.
+#[derive(Debug)]
+pub struct TaskHandle<T> {
+    receiver: mpsc::Receiver<T>,
+    status: TaskStatus,
+}
+
+impl<T> TaskHandle<T> {
+    /// The result, on the first poll after the task finished, and `None` otherwise. Doesn't wait.
+    pub fn poll(&mut self) -> Option<T> {
+        if self.status != TaskStatus::Running {
+            return None;
+        }
+        match self.receiver.try_recv() {
+            Ok(result) => {
+                self.status = TaskStatus::Finished;
+                Some(result)
+            }
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.status = TaskStatus::Failed;
+                None
+            }
+        }
+    }
+
+    pub fn status(&self) -> TaskStatus {
+        self.status
+    }
+
+    /// Whether the result is still to come, as of the last [`Self::poll`]. Show a spinner while it
+    /// is.
+    pub fn is_running(&self) -> bool {
+        self.status == TaskStatus::Running
+    }
+}
@@ -0,0 +1,117 @@
+//! Measuring frame times with rendering uncapped, for `--benchmark <seconds>` and
+//! `--benchmark-frames <count>`.
+
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use web_time::Instant;
+
+use crate::misc::format_duration;
+
+/// When a [`Benchmark`] is done.
+#[derive(Clone, Copy, Debug)]
+pub enum BenchmarkLimit {
+    Duration(Duration),
+    Frames(usize),
+}
+
+/// Number of buckets in the histogram printed by [`Benchmark::report`].
+const HISTOGRAM_BUCKETS: usize = 12;
+/// Width of the longest histogram bar, in characters.
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// Records how long every frame took, until its [`BenchmarkLimit`] is reached.
+#[derive(Debug)]
+pub struct Benchmark {
+    limit: BenchmarkLimit,
+    /// When the first frame finished. Frames are timed from there, to leave out startup.
+    start: Option<Instant>,
+    frame_times: Vec<Duration>,
+}
+
+impl Benchmark {
+    pub fn new(limit: BenchmarkLimit) -> Self {
+        Self {
+            limit,
+            start: None,
+            frame_times: Vec::new(),
+        }
+    }
+
+    /// Record a frame that took `frame_time`, ending at `now`.
+    pub fn record(&mut self, now: Instant, frame_time: Duration) {
+        if self.start.is_none() {
+            self.start = Some(now);
+            return;
+        }
+        self.frame_times.push(frame_time);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        match self.limit {
+            BenchmarkLimit::Duration(duration) => {
+                self.start.is_some_and(|start| start.elapsed() >= duration)
+            }
+            BenchmarkLimit::Frames(frames) => self.frame_times.len() >= frames,
+        }
+    }
+
+    /// The mean, percentiles and a histogram of the recorded frame times.
+    pub fn report(&self) -> String {
+        let mut sorted = self.frame_times.clone();
+        sorted.sort_unstable();
+        let (Some(&min), Some(&max)) = (sorted.first(), sorted.last()) else {
+            return "Benchmark recorded no frames".to_owned();
+        };
+
+        let total: Duration = sorted.iter().sum();
+        let mean = total / sorted.len() as u32;
+        let percentile = |p: f64| {
+            let index = ((p / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+            sorted[index]
+        };
+
+        let mut report = String::new();
+        let _ = writeln!(
+            report,
+            "Benchmark: {} frames in {} ({:.1} fps)",
+            sorted.len(),
+            format_duration(total),
+            sorted.len() as f64 / total.as_secs_f64().max(f64::EPSILON),
+        );
+        for (name, value) in [
+            ("mean", mean),
+            ("min", min),
+            ("p50", percentile(50.0)),
+            ("p95", percentile(95.0)),
+            ("p99", percentile(99.0)),
+            ("max", max),
+        ] {
+            let _ = writeln!(report, "  {name:>4}: {}", format_duration(value));
+        }
+
+        // Evenly sized buckets between the fastest and slowest frame
+        let bucket_size = (max - min).as_secs_f64() / HISTOGRAM_BUCKETS as f64;
+        let mut counts = [0usize; HISTOGRAM_BUCKETS];
+        for frame_time in &sorted {
+            let bucket = if bucket_size > 0.0 {
+                ((*frame_time - min).as_secs_f64() / bucket_size) as usize
+            } else {
+                0
+            };
+            counts[bucket.min(HISTOGRAM_BUCKETS - 1)] += 1;
+        }
+        let most = counts.iter().copied().max().unwrap_or(1).max(1);
+        let _ = writeln!(report, "Frame times:");
+        for (bucket, count) in counts.iter().enumerate() {
+            let from = min + Duration::from_secs_f64(bucket as f64 * bucket_size);
+            let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(most));
+            let _ = writeln!(
+                report,
+                "  {:>10} | {bar:<HISTOGRAM_WIDTH$} {count}",
+                format_duration(from),
+            );
+        }
+        report
+    }
+}
@@ -2,10 +2,11 @@
 
 use std::{
     f32::consts::{FRAC_PI_2, PI},
-    time::{Duration, Instant},
+    time::Duration,
 };
 
 use glam::{DMat4, DQuat, DVec2, DVec3, Vec2, Vec3};
+use web_time::Instant;
 
 use crate::{
     components::Camera,
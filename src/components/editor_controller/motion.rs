@@ -1,6 +1,8 @@
 use super::{Velocity, inputs::MotionInputs};
 use glam::DVec3;
-use std::time::{Duration, Instant};
+use std::time::Duration;
+
+use web_time::Instant;
 
 /// The current motion state of the camera.
 #[derive(Debug, Clone, Default)]
@@ -1,9 +1,11 @@
 use std::{
     collections::VecDeque,
     ops::{Add, AddAssign, Mul},
-    time::{Duration, Instant},
+    time::Duration,
 };
 
+use web_time::Instant;
+
 /// How smooth should inputs be? Over what tine window should they be averaged.
 #[derive(Debug, Clone, Copy)]
 pub struct Smoothing {
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use serde::de::Error as _;
+
+use crate::math::{GlobalTransform, Transform};
+
+/// Attaches an entity to a parent, so its [`Transform`] is relative to that of the parent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Parent(pub hecs::Entity);
+
+impl serde::Serialize for Parent {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.to_bits().get().serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Parent {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = u64::deserialize(deserializer)?;
+        hecs::Entity::from_bits(bits)
+            .map(Parent)
+            .ok_or_else(|| D::Error::custom(format!("invalid entity id {bits}")))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    InProgress,
+    Done,
+    Cyclic,
+}
+
+/// Compute the [`GlobalTransform`] of every entity with a [`Transform`], taking its ancestors
+/// into account.
+///
+/// Entities whose [`Parent`] has no [`Transform`] are treated as roots. Entities that are part
+/// of (or descend from) a cycle are logged and left without an updated [`GlobalTransform`].
+///
+/// Should be run once per frame, after gameplay code has updated the transforms but before
+/// rendering.
+pub fn propagate_transforms(world: &mut hecs::World) {
+    let locals: HashMap<hecs::Entity, (Transform, Option<hecs::Entity>)> = world
+        .query_mut::<(hecs::Entity, &Transform, Option<&Parent>)>()
+        .into_iter()
+        .map(|(entity, transform, parent)| (entity, (*transform, parent.map(|p| p.0))))
+        .collect();
+
+    // Visit entities in a stable order, so that cycles are always broken in the same place:
+    let mut entities: Vec<hecs::Entity> = locals.keys().copied().collect();
+    entities.sort_unstable_by_key(|entity| entity.to_bits());
+
+    let mut globals: HashMap<hecs::Entity, GlobalTransform> =
+        HashMap::with_capacity(entities.len());
+    let mut visits: HashMap<hecs::Entity, Visit> = HashMap::with_capacity(entities.len());
+
+    for &root in &entities {
+        // Walk up to the first ancestor that is already resolved (or a root) ...
+        let mut chain = Vec::new();
+        let mut current = Some(root);
+        let mut base = GlobalTransform::IDENTITY;
+        while let Some(entity) = current {
+            match visits.get(&entity) {
+                Some(Visit::Done) => {
+                    base = globals[&entity];
+                    break;
+                }
+                Some(Visit::InProgress) => {
+                    log::error!(
+                        "Transform hierarchy contains a cycle through {entity:?}, skipping"
+                    );
+                    for &member in &chain {
+                        visits.insert(member, Visit::Cyclic);
+                    }
+                    chain.clear();
+                    break;
+                }
+                Some(Visit::Cyclic) => {
+                    // Descends from a cycle that was already reported.
+                    for &member in &chain {
+                        visits.insert(member, Visit::Cyclic);
+                    }
+                    chain.clear();
+                    break;
+                }
+                None => {}
+            }
+
+            let Some(&(_, parent)) = locals.get(&entity) else {
+                // The parent doesn't have a transform, treat the child as a root.
+                break;
+            };
+            visits.insert(entity, Visit::InProgress);
+            chain.push(entity);
+            current = parent;
+        }
+
+        // ... then resolve the chain back down.
+        for &entity in chain.iter().rev() {
+            let (local, _) = locals[&entity];
+            base = base.mul_transform(local);
+            globals.insert(entity, base);
+            visits.insert(entity, Visit::Done);
+        }
+    }
+
+    let mut missing = Vec::new();
+    for (entity, global) in globals {
+        if let Ok(mut existing) = world.get::<&mut GlobalTransform>(entity) {
+            *existing = global;
+        } else {
+            missing.push((entity, global));
+        }
+    }
+
+    for (entity, global) in missing {
+        // We just queried the entity, so it must exist.
+        world.insert_one(entity, global).unwrap();
+    }
+}
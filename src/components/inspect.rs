@@ -0,0 +1,137 @@
+use crate::components::{
+    Camera, Global, PanOrbitController, Parent, PickBounds, SchwarschildBlackHole, Selected,
+    Sprite, Star, WorldLabel,
+};
+use crate::math::{GlobalTransform, Projection, Transform};
+use crate::toolkit::UiExt as _;
+use crate::toolkit::inspector::{Inspect, InspectorRegistry};
+use crate::toolkit::vector_edit::Vec3Edit;
+
+/// The registry of every component the app knows how to inspect.
+pub fn inspector_registry() -> InspectorRegistry {
+    let mut registry = InspectorRegistry::new();
+    registry
+        .register::<Transform>()
+        .register::<Camera>()
+        .register::<Star>()
+        .register::<SchwarschildBlackHole>()
+        .register_opaque::<GlobalTransform>()
+        .register_opaque::<Parent>()
+        .register_opaque::<Sprite>()
+        .register_opaque::<WorldLabel>()
+        .register_opaque::<Global>()
+        .register_opaque::<PanOrbitController>()
+        .register_opaque::<PickBounds>()
+        .register_opaque::<Selected>();
+    registry
+}
+
+impl Inspect for Transform {
+    fn inspect(&mut self, ui: &mut egui::Ui) {
+        // Not part of the transform, so kept with the ui, per entity
+        let uniform_scale_id = ui.id().with("uniform_scale");
+        let mut uniform_scale = ui.data(|data| data.get_temp(uniform_scale_id).unwrap_or(false));
+
+        ui.property_grid("transform", |grid| {
+            grid.row("Translation", |ui| {
+                let mut translation = self.translation.to_array();
+                if ui.vec3_edit(&mut translation).changed() {
+                    self.translation = translation.into();
+                }
+            });
+
+            grid.row("Rotation", |ui| {
+                ui.quat_edit(&mut self.rotation);
+            });
+
+            grid.row("Scale", |ui| {
+                let mut scale = self.scale.to_array();
+                if Vec3Edit::new(&mut scale)
+                    .default([1.0; 3])
+                    .speed(0.01)
+                    .linked(&mut uniform_scale)
+                    .show(ui)
+                    .changed()
+                {
+                    self.scale = scale.into();
+                }
+            });
+        });
+
+        ui.data_mut(|data| data.insert_temp(uniform_scale_id, uniform_scale));
+    }
+}
+
+impl Inspect for Camera {
+    fn inspect(&mut self, ui: &mut egui::Ui) {
+        let [width, height] = self.physical_size();
+        ui.property_grid("camera", |grid| {
+            grid.row("Physical size", |ui| ui.label(format!("{width}×{height}")));
+
+            match &mut self.projection {
+                Projection::Perspective(perspective) => {
+                    let mut fov = perspective.fov.to_degrees();
+                    grid.row("FOV", |ui| {
+                        if ui
+                            .add(egui::Slider::new(&mut fov, 1.0..=179.0).suffix("°"))
+                            .changed()
+                        {
+                            perspective.fov = fov.to_radians();
+                        }
+                    });
+                    let far = perspective.far;
+                    grid.row("Near", |ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut perspective.near)
+                                .speed(0.01)
+                                .range(0.0..=far),
+                        )
+                    });
+                    let near = perspective.near;
+                    grid.row("Far", |ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut perspective.far)
+                                .speed(1.0)
+                                .range(near..=f32::INFINITY),
+                        )
+                    });
+                }
+                Projection::Orthographic(orthographic) => {
+                    grid.row("Scale", |ui| {
+                        ui.add(egui::DragValue::new(&mut orthographic.scale).speed(0.01))
+                    });
+                    grid.row("Near", |ui| {
+                        ui.add(egui::DragValue::new(&mut orthographic.near).speed(0.01))
+                    });
+                    grid.row("Far", |ui| {
+                        ui.add(egui::DragValue::new(&mut orthographic.far).speed(1.0))
+                    });
+                }
+            }
+        });
+    }
+}
+
+impl Inspect for Star {
+    fn inspect(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::Slider::new(&mut self.temperature, 1000.0..=40000.0).text("Temperature"));
+        ui.add(egui::Slider::new(&mut self.sunspot_threshold, 0.0..=1.0).text("Sunspot Threshold"));
+        ui.add(
+            egui::Slider::new(&mut self.sunspot_frequency, 0.0..=20.0).text("Sunspot Frequency"),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.granule_frequency, 0.0..=100.0).text("Granule Frequency"),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.granule_persistence, 0.0..=1.0).text("Granule Persistence"),
+        );
+        ui.checkbox(&mut self.color_shift, "Color Shift");
+        ui.add(egui::Slider::new(&mut self.time_scale, 0.0..=1000.0).text("Time Scale"));
+    }
+}
+
+impl Inspect for SchwarschildBlackHole {
+    fn inspect(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::Slider::new(&mut self.mass, 0.0..=10.0).text("Mass"));
+    }
+}
@@ -0,0 +1,94 @@
+use crate::components::Camera;
+use crate::math::{GlobalTransform, Transform};
+
+/// Text anchored to a point in the world, drawn on top of the camera viewport.
+#[derive(Clone, Debug)]
+pub struct WorldLabel {
+    pub text: String,
+    /// Offset from the entity's transform, or a world-space position if it has none.
+    pub position: glam::Vec3,
+    pub color: egui::Color32,
+}
+
+impl WorldLabel {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            position: glam::Vec3::ZERO,
+            color: egui::Color32::WHITE,
+        }
+    }
+
+    pub fn with_position(self, position: glam::Vec3) -> Self {
+        Self { position, ..self }
+    }
+
+    pub fn with_color(self, color: egui::Color32) -> Self {
+        Self { color, ..self }
+    }
+}
+
+/// Draw every [`WorldLabel`] as seen by `camera`, whose image covers `rect`.
+///
+/// Labels behind the camera (or in front of the near plane) are hidden. Labels are drawn from far
+/// to near, so closer labels end up on top.
+pub fn draw_world_labels(
+    world: &mut hecs::World,
+    ui: &egui::Ui,
+    camera: hecs::Entity,
+    rect: egui::Rect,
+) {
+    let Ok(clip_from_view) = world
+        .get::<&Camera>(camera)
+        .map(|camera| camera.projection.get_clip_from_view())
+    else {
+        return;
+    };
+    let world_from_view = match world.get::<&GlobalTransform>(camera) {
+        Ok(global) => global.to_matrix(),
+        Err(_) => match world.get::<&Transform>(camera) {
+            Ok(transform) => transform.to_matrix(),
+            Err(_) => return,
+        },
+    };
+    let clip_from_world = clip_from_view * world_from_view.inverse();
+
+    let mut labels: Vec<(f32, egui::Pos2, &WorldLabel)> = Vec::new();
+    for (label, transform, global) in world
+        .query_mut::<(&WorldLabel, Option<&Transform>, Option<&GlobalTransform>)>()
+        .into_iter()
+    {
+        let position = match (global, transform) {
+            (Some(global), _) => global.transform_point(label.position),
+            (None, Some(transform)) => transform.transform_point(label.position),
+            (None, None) => label.position,
+        };
+
+        let clip = clip_from_world * position.extend(1.0);
+        // Reverse-z: anything with z > w is closer than the near plane.
+        if clip.w <= 0.0 || clip.z > clip.w {
+            continue;
+        }
+
+        let ndc = clip.truncate() / clip.w;
+        let screen = egui::pos2(
+            rect.left() + (ndc.x + 1.0) * 0.5 * rect.width(),
+            rect.top() + (1.0 - ndc.y) * 0.5 * rect.height(),
+        );
+        labels.push((clip.w, screen, label));
+    }
+
+    labels.sort_by(|(a, _, _), (b, _, _)| b.total_cmp(a));
+
+    let painter = ui.painter_at(rect);
+    let font_id = egui::TextStyle::Body.resolve(ui.style());
+    for (_, position, label) in labels {
+        painter.text(
+            position,
+            egui::Align2::CENTER_BOTTOM,
+            &label.text,
+            font_id.clone(),
+            label.color,
+        );
+    }
+}
@@ -1,18 +1,30 @@
 use std::time::Duration;
 
 use crate::math::{PerspectiveProjection, Projection};
+use crate::renderer::SpriteTexture;
 
 // mod editor_controller;
+mod hierarchy;
+mod inspect;
+mod label;
 mod panorbit_controller;
+mod picking;
+mod scene;
 
 use glam::Vec2;
+pub use hierarchy::{Parent, propagate_transforms};
+pub use inspect::inspector_registry;
+pub use label::{WorldLabel, draw_world_labels};
 pub use panorbit_controller::{PanOrbitController, update_pan_orbit_camera};
+pub use picking::{PickBounds, Selected, pick, select};
+pub use scene::scene_registry;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Camera {
     /// Projection matrix for camera
     pub projection: Projection,
-    /// Current physical size of the camera render target
+    /// Current physical size of the camera render target, set by [`Self::update`] every frame
+    #[serde(skip, default = "Camera::placeholder_physical_size")]
     physical_size: [u32; 2],
 }
 
@@ -32,10 +44,15 @@ impl Camera {
 
         Camera {
             projection: proj.into(),
-            physical_size: [16, 16],
+            physical_size: Self::placeholder_physical_size(),
         }
     }
 
+    /// Until the camera is first updated with the size of its target.
+    fn placeholder_physical_size() -> [u32; 2] {
+        [16, 16]
+    }
+
     pub fn physical_size(&self) -> [u32; 2] {
         self.physical_size
     }
@@ -298,7 +315,7 @@ impl Default for Global {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct SchwarschildBlackHole {
     pub mass: f32,
 }
@@ -309,7 +326,7 @@ impl Default for SchwarschildBlackHole {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Star {
     pub temperature: f32,
     pub sunspot_threshold: f32,
@@ -350,3 +367,35 @@ impl Default for Star {
         }
     }
 }
+
+/// A textured quad drawn in screen space, centered on the translation of the entity's transform.
+///
+/// Positions and sizes are in physical pixels, with the origin in the top left of the window.
+#[derive(Clone, Debug)]
+pub struct Sprite {
+    pub texture: SpriteTexture,
+    pub size: Vec2,
+    /// Multiplied with the texture color. Like egui colors, this is in gamma space.
+    pub tint: glam::Vec4,
+    /// Sprites with a larger `z` are drawn on top.
+    pub z: f32,
+}
+
+impl Sprite {
+    pub fn new(texture: SpriteTexture, size: Vec2) -> Self {
+        Self {
+            texture,
+            size,
+            tint: glam::Vec4::ONE,
+            z: 0.0,
+        }
+    }
+
+    pub fn with_tint(self, tint: glam::Vec4) -> Self {
+        Self { tint, ..self }
+    }
+
+    pub fn with_z(self, z: f32) -> Self {
+        Self { z, ..self }
+    }
+}
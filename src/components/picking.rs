@@ -0,0 +1,108 @@
+use crate::components::Camera;
+use crate::math::{GlobalTransform, Transform};
+
+/// A sphere around the entity's origin that can be clicked on in the viewport.
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub struct PickBounds {
+    /// Radius in local space, so it scales with the entity.
+    pub radius: f32,
+}
+
+impl PickBounds {
+    pub fn sphere(radius: f32) -> Self {
+        Self { radius }
+    }
+}
+
+/// Marks the entity the user picked last. At most one entity is selected at a time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Selected;
+
+/// Find the closest entity with [`PickBounds`] under `pointer`, as seen by `camera`, whose image
+/// covers `rect`.
+///
+/// Returns `None` if the ray through `pointer` hits nothing.
+pub fn pick(
+    world: &hecs::World,
+    camera: hecs::Entity,
+    rect: egui::Rect,
+    pointer: egui::Pos2,
+) -> Option<hecs::Entity> {
+    let view_from_clip = world
+        .get::<&Camera>(camera)
+        .ok()?
+        .projection
+        .get_clip_from_view()
+        .inverse();
+    let world_from_view = match world.get::<&GlobalTransform>(camera) {
+        Ok(global) => global.to_matrix(),
+        Err(_) => world.get::<&Transform>(camera).ok()?.to_matrix(),
+    };
+    let world_from_clip = world_from_view * view_from_clip;
+
+    let ndc = glam::Vec2::new(
+        2.0 * (pointer.x - rect.left()) / rect.width() - 1.0,
+        1.0 - 2.0 * (pointer.y - rect.top()) / rect.height(),
+    );
+    // Reverse-z: the near plane is at 1, and 0.5 is still finite for an infinite far plane.
+    let near = world_from_clip.project_point3(ndc.extend(1.0));
+    let further = world_from_clip.project_point3(ndc.extend(0.5));
+    let direction = (further - near).try_normalize()?;
+
+    let mut query = world.query::<(
+        hecs::Entity,
+        &PickBounds,
+        Option<&Transform>,
+        Option<&GlobalTransform>,
+    )>();
+
+    query
+        .iter()
+        .filter_map(|(entity, bounds, transform, global)| {
+            let (center, scale) = match (global, transform) {
+                (Some(global), _) => (global.translation(), global.scale()),
+                (None, Some(transform)) => (transform.translation, transform.scale),
+                (None, None) => return None,
+            };
+            let radius = bounds.radius * scale.abs().max_element();
+            ray_sphere(near, direction, center, radius).map(|t| (t, entity))
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, entity)| entity)
+}
+
+/// Distance along the ray to the first intersection with the sphere, if any.
+fn ray_sphere(
+    origin: glam::Vec3,
+    direction: glam::Vec3,
+    center: glam::Vec3,
+    radius: f32,
+) -> Option<f32> {
+    let offset = origin - center;
+    let b = offset.dot(direction);
+    let c = offset.length_squared() - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let sqrt = discriminant.sqrt();
+    // Use the far intersection if the ray starts inside the sphere.
+    [-b - sqrt, -b + sqrt].into_iter().find(|t| *t >= 0.0)
+}
+
+/// Move the [`Selected`] marker to `entity`, or clear it.
+pub fn select(world: &mut hecs::World, entity: Option<hecs::Entity>) {
+    let previous: Vec<hecs::Entity> = world
+        .query_mut::<(hecs::Entity, &Selected)>()
+        .into_iter()
+        .map(|(entity, _)| entity)
+        .collect();
+    for previous in previous {
+        let _ = world.remove_one::<Selected>(previous);
+    }
+
+    if let Some(entity) = entity {
+        let _ = world.insert_one(entity, Selected);
+    }
+}
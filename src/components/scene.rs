@@ -0,0 +1,18 @@
+use crate::app::scene::SceneRegistry;
+use crate::components::{Camera, Parent, PickBounds, SchwarschildBlackHole, Star};
+use crate::math::Transform;
+
+/// The registry of every component that is persisted in scene files.
+///
+/// The names are part of the file format, so don't change them.
+pub fn scene_registry() -> SceneRegistry {
+    let mut registry = SceneRegistry::new();
+    registry
+        .register::<Transform>("transform")
+        .register::<Parent>("parent")
+        .register::<Star>("star")
+        .register::<SchwarschildBlackHole>("schwarschild_black_hole")
+        .register::<PickBounds>("pick_bounds")
+        .register::<Camera>("camera");
+    registry
+}
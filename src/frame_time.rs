@@ -0,0 +1,92 @@
+//! Time between frames, smoothed so uneven frame pacing doesn't make animations jitter.
+
+use std::time::Duration;
+
+use web_time::Instant;
+
+/// Longest step a single frame advances animations and the simulation by.
+///
+/// Frames after a stall, like a debugger pause or the window waking up from idle, would otherwise
+/// make everything jump ahead.
+pub const MAX_DELTA_TIME: Duration = Duration::from_millis(100);
+
+/// How much of the previous average is kept each frame, by default. See [`FrameClock::new`].
+pub const DEFAULT_SMOOTHING: f64 = 0.8;
+
+/// How long the last frame took, as handed to [`crate::app::App::update`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameTime {
+    /// Wall clock time since the previous frame, exactly as measured. For frame time readouts.
+    pub raw: Duration,
+    /// [`Self::raw`] clamped to [`MAX_DELTA_TIME`] and smoothed over recent frames. For anything
+    /// that moves.
+    pub smoothed: Duration,
+}
+
+impl FrameTime {
+    /// The same `delta_time` for both, e.g. for a fixed step.
+    pub fn fixed(delta_time: Duration) -> Self {
+        Self {
+            raw: delta_time,
+            smoothed: delta_time,
+        }
+    }
+}
+
+/// Measures the [`FrameTime`] of each frame.
+#[derive(Clone, Debug)]
+pub struct FrameClock {
+    last_tick: Instant,
+    /// Share of the previous average kept each frame, in `0..1`. Zero passes frames through raw.
+    smoothing: f64,
+    /// Exponential moving average of the clamped frame time, in seconds.
+    average: Option<f64>,
+}
+
+impl FrameClock {
+    /// Start timing now, keeping `smoothing` of the average each frame and blending in the rest
+    /// from the newest frame. Higher is smoother but slower to follow real changes in frame rate.
+    pub fn new(smoothing: f64) -> Self {
+        Self {
+            last_tick: Instant::now(),
+            smoothing: smoothing.clamp(0.0, 0.99),
+            average: None,
+        }
+    }
+
+    /// [`DEFAULT_SMOOTHING`], unless `STELLAR_FRAME_SMOOTHING` is set. Set it to 0 to animate with
+    /// the raw frame times.
+    pub fn from_env() -> Self {
+        let smoothing = std::env::var("STELLAR_FRAME_SMOOTHING")
+            .ok()
+            .and_then(|smoothing| smoothing.parse::<f64>().ok())
+            .unwrap_or(DEFAULT_SMOOTHING);
+        Self::new(smoothing)
+    }
+
+    /// When the current frame started.
+    pub fn last_tick(&self) -> Instant {
+        self.last_tick
+    }
+
+    /// Start a new frame at `now`, and measure the previous one.
+    pub fn tick(&mut self, now: Instant) -> FrameTime {
+        let raw = now - self.last_tick;
+        self.last_tick = now;
+
+        let clamped = raw.min(MAX_DELTA_TIME).as_secs_f64();
+        let average = match self.average {
+            // After a stall, start over rather than averaging it in for the next few frames
+            Some(average) if raw <= MAX_DELTA_TIME => {
+                self.smoothing * average + (1.0 - self.smoothing) * clamped
+            }
+            _ => clamped,
+        };
+        self.average = Some(average);
+
+        FrameTime {
+            raw,
+            smoothed: Duration::from_secs_f64(average),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Rendering a single frame without a window, e.g. for documentation and golden images.
+
+use std::path::Path;
+
+use eyre::WrapErr as _;
+
+use crate::app::App;
+use crate::frame_time::FrameTime;
+use crate::renderer::{FrameCapture, Graphics, GraphicsConfig, Renderer, UiScreen, scale_ui_input};
+
+/// Run the app for one frame at `width`×`height` physical pixels, and save it as a PNG.
+///
+/// The frame is rendered at `render_scale` times that size and filtered down, so `2.0` gives an
+/// antialiased image.
+pub fn screenshot(path: &Path, width: u32, height: u32, render_scale: f32) -> eyre::Result<()> {
+    eyre::ensure!(width > 0 && height > 0, "Screenshot size must not be zero");
+
+    let gfx = Graphics::new_headless_blocking(width, height, GraphicsConfig::from_env())?;
+    let mut renderer = Renderer::new(&gfx);
+    renderer.set_render_scale(render_scale);
+
+    let mut app = App::new();
+    let mut world = hecs::World::new();
+    app.start(&mut world);
+
+    // Run a single frame of ui
+    let pixels_per_point = 1.0;
+    let ctx = app.ui_context();
+    let mut raw_input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(width as f32, height as f32) / pixels_per_point,
+        )),
+        ..Default::default()
+    };
+    scale_ui_input(&mut raw_input, renderer.render_scale());
+    let egui::FullOutput {
+        textures_delta,
+        shapes,
+        pixels_per_point,
+        ..
+    } = ctx.run_ui(raw_input, |ui| {
+        app.update(&mut world, ui, [width, height], FrameTime::default());
+    });
+    let screen = UiScreen {
+        size_in_pixels: [width, height],
+        pixels_per_point,
+    }
+    .scaled(renderer.render_scale());
+    let paint_jobs = ctx.tessellate(shapes, screen.pixels_per_point);
+
+    // Render into an offscreen texture
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let target = gfx.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("screenshot"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: gfx.surface_format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let mut encoder = gfx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    renderer.prepare(&gfx, &mut world, &mut encoder);
+    renderer.prepare_ui(&gfx, screen, &textures_delta, &paint_jobs, &mut encoder);
+    renderer.render(&gfx, &target_view, &mut world, &mut encoder);
+
+    let capture = FrameCapture::new(&gfx, &mut encoder, &target);
+    gfx.queue.submit(std::iter::once(encoder.finish()));
+    renderer.recall(&gfx, &mut world);
+
+    // Read the pixels back
+    let image = capture.read_blocking(&gfx)?;
+
+    app.cleanup(&mut world);
+
+    image
+        .save(path)
+        .wrap_err_with(|| format!("Failed to save screenshot to {}", path.display()))?;
+
+    log::info!("Saved {width}×{height} screenshot to {}", path.display());
+    Ok(())
+}
@@ -1,55 +1,190 @@
-use std::io::Write;
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::Duration;
+use web_time::Instant;
 use winit::application::ApplicationHandler;
 use winit::dpi::PhysicalSize;
-use winit::event::{KeyEvent, WindowEvent};
-use winit::event_loop::EventLoop;
+use winit::event::{ElementState, KeyEvent, MouseScrollDelta, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoop};
 use winit::keyboard::{KeyCode, PhysicalKey};
 use winit::window::{Theme, Window, WindowId};
 
 pub mod app;
+pub mod benchmark;
 pub mod components;
+pub mod frame_time;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod headless;
 pub mod math;
 pub mod misc;
+pub mod profiling;
 pub mod renderer;
 pub mod state;
 pub mod toolkit;
 
-use crate::app::App;
-use crate::renderer::{Graphics, Renderer, UiScreen};
+use crate::app::settings::Preferences;
+use crate::app::{App, CloseAction};
+use crate::benchmark::Benchmark;
+use crate::frame_time::FrameClock;
+use crate::profiling::Profiler;
+use crate::renderer::{FrameCapture, Graphics, GraphicsConfig, Renderer, UiScreen, scale_ui_input};
+use crate::toolkit::console::LogBuffer;
 
+/// Touchpads scroll by pixels rather than notches, this many to a notch of the mouse wheel.
+const PIXELS_PER_SCROLL_LINE: f64 = 50.0;
+
+#[cfg(not(target_arch = "wasm32"))]
 fn main() -> eyre::Result<()> {
     // Initialize pretty error handling
     color_eyre::install()?;
-    // Initialize logger
-    env_logger::builder()
-        .filter_level(log::LevelFilter::Info)
-        .write_style(env_logger::WriteStyle::Always)
-        .format(move |buf, record| {
-            let level_style = buf.default_level_style(record.level());
-
-            writeln!(
-                buf,
-                "{level_style}[{}]{level_style:#}: {}",
-                record.level(),
-                record.args()
-            )
-        })
-        .write_style(env_logger::WriteStyle::Always)
-        .init();
+    // Initialize logger, keeping recent records around for the in-app console
+    let log_buffer = misc::init_logging()?;
+    // `--screenshot <path> [--size <width>x<height>] [--scale <factor>]` renders a single frame
+    // without a window
+    if let Some((path, width, height, render_scale)) = screenshot_args()? {
+        return headless::screenshot(&path, width, height, render_scale);
+    }
     // Create the event loop and run the app
-    let event_loop = EventLoop::builder().build()?;
-    event_loop.set_control_flow(winit::event_loop::ControlFlow::Poll);
-    let mut framework = Framework::default();
+    let event_loop = EventLoop::with_user_event().build()?;
+    event_loop.set_control_flow(ControlFlow::Wait);
+    // `--benchmark <seconds>` or `--benchmark-frames <count>` draws uncapped and prints frame times
+    let benchmark = benchmark_args()?.map(Benchmark::new);
+    let mut framework = Framework::Init {
+        log_buffer,
+        benchmark,
+    };
     event_loop.run_app(&mut framework)?;
+    // Surface startup failures through color-eyre rather than exiting quietly
+    if let Framework::Failed(error) = framework {
+        return Err(error);
+    }
     Ok(())
 }
 
-#[derive(Default)]
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use winit::platform::web::EventLoopExtWebSys as _;
+
+    // Log panics and records to the browser console
+    std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+    console_log::init_with_level(log::Level::Info).expect("Failed to initialize logger");
+    // Create the event loop and hand it to the browser
+    let event_loop = EventLoop::with_user_event()
+        .build()
+        .expect("Failed to create event loop");
+    event_loop.set_control_flow(ControlFlow::Wait);
+    let proxy = event_loop.create_proxy();
+    event_loop.spawn_app(Framework::Init {
+        log_buffer: LogBuffer::default(),
+        benchmark: None,
+        proxy,
+    });
+}
+
+/// Parse `--screenshot <path>`, the optional `--size <width>x<height>` and `--scale <factor>`.
+#[cfg(not(target_arch = "wasm32"))]
+fn screenshot_args() -> eyre::Result<Option<(PathBuf, u32, u32, f32)>> {
+    let mut path = None;
+    let mut size = (1280, 720);
+    let mut render_scale = 1.0;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--screenshot" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("--screenshot expects a path"))?;
+                path = Some(PathBuf::from(value));
+            }
+            "--size" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("--size expects <width>x<height>"))?;
+                size = value
+                    .split_once('x')
+                    .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+                    .ok_or_else(|| {
+                        eyre::eyre!("Invalid --size {value:?}, expected e.g. 1280x720")
+                    })?;
+            }
+            "--scale" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("--scale expects a factor, e.g. 2"))?;
+                render_scale = value
+                    .parse()
+                    .map_err(|_| eyre::eyre!("Invalid --scale {value:?}, expected e.g. 2"))?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(path.map(|path| (path, size.0, size.1, render_scale)))
+}
+
+/// Parse `--benchmark <seconds>` or `--benchmark-frames <count>`.
+#[cfg(not(target_arch = "wasm32"))]
+fn benchmark_args() -> eyre::Result<Option<benchmark::BenchmarkLimit>> {
+    let mut limit = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--benchmark" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("--benchmark expects a number of seconds"))?;
+                let seconds = value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|seconds| seconds.is_finite() && *seconds > 0.0)
+                    .ok_or_else(|| {
+                        eyre::eyre!("Invalid --benchmark {value:?}, expected e.g. 10")
+                    })?;
+                limit = Some(benchmark::BenchmarkLimit::Duration(
+                    Duration::from_secs_f32(seconds),
+                ));
+            }
+            "--benchmark-frames" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("--benchmark-frames expects a number of frames"))?;
+                let frames = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|frames| *frames > 0)
+                    .ok_or_else(|| {
+                        eyre::eyre!("Invalid --benchmark-frames {value:?}, expected e.g. 1000")
+                    })?;
+                limit = Some(benchmark::BenchmarkLimit::Frames(frames));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(limit)
+}
+
+/// Sent to the event loop by work that finishes asynchronously.
+pub enum FrameworkEvent {
+    /// [`Graphics::new`] finished, on the web where it can't be blocked on.
+    GraphicsReady(eyre::Result<Graphics>),
+}
+
 pub enum Framework {
-    #[default]
-    Init,
+    Init {
+        log_buffer: LogBuffer,
+        benchmark: Option<Benchmark>,
+        #[cfg(target_arch = "wasm32")]
+        proxy: winit::event_loop::EventLoopProxy<FrameworkEvent>,
+    },
+    /// The window exists, and graphics are being created.
+    Pending {
+        window: Arc<Window>,
+        app: App,
+        benchmark: Option<Benchmark>,
+    },
     State {
         window: Arc<Window>,
         gfx: Graphics,
@@ -57,40 +192,71 @@ pub enum Framework {
         world: hecs::World,
         ui_state: egui_winit::State,
         last_size: (u32, u32),
-        last_render_time: Instant,
+        /// Times frames, for animations and the frame rate cap.
+        frame_clock: FrameClock,
+        /// When the next frame should be drawn, or `None` to wait for input.
+        repaint_at: Option<Instant>,
+        /// Files currently dragged over the window.
+        hovered_files: Vec<PathBuf>,
+        /// Files dropped since the last time the app was told about them.
+        dropped_files: Vec<PathBuf>,
+        /// Per-frame CPU timings, if enabled.
+        profiler: Profiler,
+        /// Frame times being measured, exiting once done.
+        benchmark: Option<Benchmark>,
         app: App,
     },
+    /// Initialization failed, and the event loop is shutting down.
+    Failed(eyre::Report),
 }
 
-impl ApplicationHandler for Framework {
-    fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
-        let attributes = Window::default_attributes().with_title("Stellar");
-
-        let Ok(new_window) = event_loop.create_window(attributes) else {
-            return;
+impl Framework {
+    /// Finish initialization once graphics have been created.
+    fn init_with_graphics(
+        &mut self,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        window: Arc<Window>,
+        mut app: App,
+        benchmark: Option<Benchmark>,
+        gfx: eyre::Result<Graphics>,
+    ) {
+        let mut gfx = match gfx {
+            Ok(gfx) => gfx,
+            Err(error) => {
+                log::error!("Unable to initialize graphics: {error:#}");
+                log::error!(
+                    "Make sure your gpu drivers are up to date and support Vulkan, Metal or DirectX 12."
+                );
+                *self = Self::Failed(error);
+                event_loop.exit();
+                return;
+            }
         };
-
-        if let Framework::State { window, .. } = self {
-            *window = Arc::new(new_window);
-            return;
+        let renderer = Renderer::new(&gfx);
+        // The environment overrides the saved preference
+        if std::env::var_os("STELLAR_FRAME_LATENCY").is_some() {
+            app.set_frame_latency(gfx.frame_latency());
+        }
+        // Draw as fast as the gpu allows, rather than when vsync or the UI says to
+        if benchmark.is_some() {
+            log::info!("Benchmarking with vsync and the frame rate cap disabled");
+            gfx.set_present_mode(wgpu::PresentMode::AutoNoVsync);
+            app.set_max_fps(None);
+            app.set_continuous_rendering(true);
         }
 
-        let mut app = App::new();
-
-        let window_handle = Arc::new(new_window);
-        let window = window_handle.clone();
-
-        let (width, height) = (
-            window_handle.inner_size().width,
-            window_handle.inner_size().height,
-        );
-        // Initialize graphics
-        let gfx =
-            pollster::block_on(
-                async move { Graphics::new(window_handle.clone(), width, height).await },
-            );
-        let renderer = Renderer::new(&gfx);
+        // The window may have come up at a different size than graphics were created for, e.g.
+        // when the window manager tiles it, and no `Resized` event is guaranteed to follow
+        let PhysicalSize { width, height } = window.inner_size();
+        if width > 0
+            && height > 0
+            && (width, height) != (gfx.surface_config.width, gfx.surface_config.height)
+        {
+            gfx.resize(width, height);
+        }
 
+        // The window's scale factor goes to egui below, and scales the very first frame along
+        // with the app's own zoom
         let egui_context = app.ui_context();
         let viewport_id = egui_context.viewport_id();
         let ui_state = egui_winit::State::new(
@@ -98,7 +264,8 @@ impl ApplicationHandler for Framework {
             viewport_id,
             &window,
             Some(window.scale_factor() as _),
-            Some(Theme::Dark),
+            // Start out matching the OS, if it says
+            window.theme(),
             None,
         );
 
@@ -113,10 +280,135 @@ impl ApplicationHandler for Framework {
             world,
             ui_state,
             last_size: (width, height),
-            last_render_time: Instant::now(),
+            frame_clock: FrameClock::from_env(),
+            repaint_at: Some(Instant::now()),
+            hovered_files: Vec::new(),
+            dropped_files: Vec::new(),
+            profiler: Profiler::from_env(),
+            benchmark,
             app,
         };
     }
+}
+
+/// Turn the logical sizes of `attributes` into physical ones, using the scale factor of the monitor
+/// the window will most likely open on.
+///
+/// winit otherwise converts them with a scale factor of one on some platforms, before it knows the
+/// monitor, so the window comes up tiny or huge on HiDPI displays. The initial size is kept
+/// within the monitor, too.
+fn with_physical_sizes(
+    mut attributes: winit::window::WindowAttributes,
+    event_loop: &winit::event_loop::ActiveEventLoop,
+) -> winit::window::WindowAttributes {
+    let Some(monitor) = event_loop
+        .primary_monitor()
+        .or_else(|| event_loop.available_monitors().next())
+    else {
+        return attributes;
+    };
+    let scale_factor = monitor.scale_factor();
+
+    if let Some(size) = attributes.inner_size {
+        let mut size: PhysicalSize<u32> = size.to_physical(scale_factor);
+        // Leave room for the taskbar and window decorations
+        let monitor_size = monitor.size();
+        if monitor_size.width > 0 && monitor_size.height > 0 {
+            size.width = size.width.min(monitor_size.width * 9 / 10);
+            size.height = size.height.min(monitor_size.height * 9 / 10);
+        }
+        attributes.inner_size = Some(size.into());
+    }
+    if let Some(size) = attributes.min_inner_size {
+        let size: PhysicalSize<u32> = size.to_physical(scale_factor);
+        attributes.min_inner_size = Some(size.into());
+    }
+    attributes
+}
+
+impl ApplicationHandler<FrameworkEvent> for Framework {
+    fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        let (log_buffer, benchmark) = match self {
+            Framework::Init {
+                log_buffer,
+                benchmark,
+                ..
+            } => (log_buffer.clone(), benchmark.take()),
+            Framework::State { window, app, .. } => {
+                let attributes = with_physical_sizes(app.window_attributes(), event_loop);
+                if let Ok(new_window) = event_loop.create_window(attributes) {
+                    *window = Arc::new(new_window);
+                }
+                return;
+            }
+            Framework::Pending { .. } | Framework::Failed(_) => return,
+        };
+
+        let app = App::new()
+            .with_log_buffer(log_buffer)
+            .with_preferences(Preferences::load_default());
+
+        let attributes = with_physical_sizes(app.window_attributes(), event_loop);
+        // Add the canvas to the page
+        #[cfg(target_arch = "wasm32")]
+        let attributes = {
+            use winit::platform::web::WindowAttributesExtWebSys as _;
+            attributes.with_append(true)
+        };
+
+        let Ok(new_window) = event_loop.create_window(attributes) else {
+            return;
+        };
+        let window = Arc::new(new_window);
+
+        let PhysicalSize { width, height } = window.inner_size();
+        let config = GraphicsConfig::from_env();
+
+        // Initialize graphics
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let gfx = Graphics::new_blocking(window.clone(), width, height, config);
+            self.init_with_graphics(event_loop, window, app, benchmark, gfx);
+        }
+        // The browser can't be blocked, so finish initializing in `user_event`
+        #[cfg(target_arch = "wasm32")]
+        {
+            let Framework::Init { proxy, .. } = self else {
+                unreachable!()
+            };
+            let proxy = proxy.clone();
+            let graphics = Graphics::new(window.clone(), width, height, config);
+            wasm_bindgen_futures::spawn_local(async move {
+                let gfx = graphics.await;
+                let _ = proxy.send_event(FrameworkEvent::GraphicsReady(gfx));
+            });
+            *self = Self::Pending {
+                window,
+                app,
+                benchmark,
+            };
+        }
+    }
+
+    fn user_event(
+        &mut self,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        event: FrameworkEvent,
+    ) {
+        match event {
+            FrameworkEvent::GraphicsReady(gfx) => {
+                let Framework::Pending {
+                    window,
+                    app,
+                    benchmark,
+                } = std::mem::replace(self, Framework::Failed(eyre::eyre!("Initializing")))
+                else {
+                    return;
+                };
+                self.init_with_graphics(event_loop, window, app, benchmark, gfx);
+            }
+        }
+    }
 
     fn suspended(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {}
 
@@ -131,7 +423,12 @@ impl ApplicationHandler for Framework {
             gfx,
             renderer,
             last_size,
-            last_render_time,
+            frame_clock,
+            repaint_at,
+            hovered_files,
+            dropped_files,
+            profiler,
+            benchmark,
             ui_state,
             world,
             app,
@@ -140,7 +437,63 @@ impl ApplicationHandler for Framework {
             return;
         };
 
-        if ui_state.on_window_event(window, &event).consumed {
+        // Ctrl+scroll zooms the whole ui, so widgets that zoom on their own never see it
+        if let WindowEvent::MouseWheel { delta, .. } = &event
+            && ui_state.egui_input().modifiers.command
+        {
+            let notches = match delta {
+                MouseScrollDelta::LineDelta(_, y) => *y,
+                MouseScrollDelta::PixelDelta(position) => {
+                    (position.y / PIXELS_PER_SCROLL_LINE) as f32
+                }
+            };
+            app.zoom_ui_by(app::UI_ZOOM_STEP.powf(notches));
+            window.request_redraw();
+            return;
+        }
+
+        let first_new_event = ui_state.egui_input().events.len();
+        let response = ui_state.on_window_event(window, &event);
+        app.input_mut()
+            .handle_window_event(&event, response.consumed);
+
+        // The app's shortcuts act even while egui wants the keyboard, and before it gets to
+        // consume them. Only the ones registered with it do, so the cheat sheet lists them all.
+        let shortcut = ui_state.egui_input().events[first_new_event..]
+            .iter()
+            .find_map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    repeat: false,
+                    modifiers,
+                    ..
+                } => app.shortcuts().pressed(*modifiers, *key),
+                _ => None,
+            });
+        if let Some(shortcut) = shortcut {
+            // Text fields have their own undo
+            let editing_text = ui_state.egui_ctx().wants_keyboard_input();
+            match shortcut {
+                app::PAUSE_SHORTCUT => app.set_paused(!app.is_paused()),
+                app::STEP_SHORTCUT => app.step(),
+                app::RESET_ZOOM_SHORTCUT => app.set_ui_zoom(1.0),
+                app::ZOOM_IN_SHORTCUT | app::ZOOM_IN_ALT_SHORTCUT => {
+                    app.zoom_ui_by(app::UI_ZOOM_STEP);
+                }
+                app::ZOOM_OUT_SHORTCUT => app.zoom_ui_by(1.0 / app::UI_ZOOM_STEP),
+                app::debug_overlay::TOGGLE_SHORTCUT => app.toggle_debug_overlay(),
+                app::recorder::TOGGLE_SHORTCUT => app.toggle_recording(),
+                app::UNDO_SHORTCUT if !editing_text => app.undo(world),
+                app::REDO_SHORTCUT | app::REDO_ALT_SHORTCUT if !editing_text => app.redo(world),
+                _ => {}
+            }
+            window.request_redraw();
+        }
+        if response.repaint {
+            window.request_redraw();
+        }
+        if response.consumed {
             return;
         }
 
@@ -149,15 +502,49 @@ impl ApplicationHandler for Framework {
                 event:
                     KeyEvent {
                         physical_key: PhysicalKey::Code(KeyCode::Escape),
+                        state: ElementState::Pressed,
                         ..
                     },
                 ..
             } => {
-                event_loop.exit();
+                // The modal sees the same press and closes itself
+                if app.shows_modal() {
+                    window.request_redraw();
+                } else if app.on_close_requested(world) == CloseAction::Exit {
+                    event_loop.exit();
+                } else {
+                    window.request_redraw();
+                }
+            }
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(KeyCode::F9),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } if profiler.is_enabled() => {
+                if let Err(error) = profiler.flush() {
+                    log::error!("Failed to write trace: {error}");
+                }
             }
             WindowEvent::ScaleFactorChanged { .. } => {
-                let scale_factor = window.scale_factor() as f32;
-                ui_state.egui_ctx().set_pixels_per_point(scale_factor);
+                // egui-winit passes the new scale factor on to egui, which keeps the app's zoom
+                // on top of it.
+                //
+                // Moving between monitors changes the physical size too, and the matching
+                // `Resized` event may only arrive after the next frame has been drawn.
+                let PhysicalSize { width, height } = window.inner_size();
+                if width == 0 || height == 0 || (width, height) == *last_size {
+                    return;
+                }
+
+                log::info!("Resizing renderer surface to ({width}, {height})");
+                gfx.resize(width, height);
+                renderer.resize(gfx, width, height);
+                *last_size = (width, height);
             }
             WindowEvent::Resized(PhysicalSize { width, height }) => {
                 if width == 0 || height == 0 {
@@ -166,26 +553,74 @@ impl ApplicationHandler for Framework {
 
                 log::info!("Resizing renderer surface to ({width}, {height})");
                 gfx.resize(width, height);
+                renderer.resize(gfx, width, height);
                 *last_size = (width, height);
 
-                let scale_factor = window.scale_factor() as f32;
-                ui_state.egui_ctx().set_pixels_per_point(scale_factor);
+                // The reconfigured surface has nothing on it until the next frame, draw that
+                // right away rather than showing the bare surface while the edge is dragged
+                window.request_redraw();
+            }
+            WindowEvent::ThemeChanged(theme) => {
+                let theme = match theme {
+                    Theme::Dark => egui::Theme::Dark,
+                    Theme::Light => egui::Theme::Light,
+                };
+                app.on_system_theme_changed(ui_state.egui_ctx(), theme);
+                renderer.invalidate();
+                window.request_redraw();
             }
             WindowEvent::CloseRequested => {
-                log::info!("Close requested. Exiting...");
-                event_loop.exit();
+                if app.on_close_requested(world) == CloseAction::Exit {
+                    log::info!("Close requested. Exiting...");
+                    event_loop.exit();
+                } else {
+                    // Give the app a frame to ask the user
+                    window.request_redraw();
+                }
+            }
+            // Dragging several files produces one event per file
+            WindowEvent::HoveredFile(path) => {
+                hovered_files.push(path);
+                app.on_file_hovered(hovered_files);
+                window.request_redraw();
+            }
+            WindowEvent::HoveredFileCancelled => {
+                hovered_files.clear();
+                app.on_file_hovered(hovered_files);
+                window.request_redraw();
+            }
+            WindowEvent::DroppedFile(path) => {
+                // Handed to the app in `about_to_wait`, once the whole drop has arrived
+                dropped_files.push(path);
+                window.request_redraw();
             }
             WindowEvent::RedrawRequested => {
                 // Track delta time
                 let now = Instant::now();
-                let delta_time = now - *last_render_time;
-                *last_render_time = now;
+                let frame_time = frame_clock.tick(now);
+
+                if let Some(benchmark) = benchmark {
+                    benchmark.record(now, frame_time.raw);
+                    if benchmark.is_finished() {
+                        println!("{}", benchmark.report());
+                        event_loop.exit();
+                        return;
+                    }
+                }
 
                 // Get size of available screen
                 let (width, height) = *last_size;
 
-                // Handle Ui Events
-                let ui_input = ui_state.take_egui_input(window);
+                app.set_gpu_time(renderer.last_gpu_time());
+                app.set_resource_stats(
+                    app.shows_resource_stats()
+                        .then(|| renderer.resource_stats(gfx)),
+                );
+
+                // Handle Ui Events, laid out for the resolution they will be rendered at
+                renderer.set_render_scale(app.render_scale());
+                let mut ui_input = ui_state.take_egui_input(window);
+                scale_ui_input(&mut ui_input, renderer.render_scale());
                 // Run Ui
                 let ctx = ui_state.egui_ctx();
                 let egui::FullOutput {
@@ -193,30 +628,59 @@ impl ApplicationHandler for Framework {
                     pixels_per_point,
                     textures_delta,
                     shapes,
+                    viewport_output,
                     ..
-                } = ctx.run_ui(ui_input, |ui| {
-                    // Run App logic and create UI
-                    app.update(world, ui, [width, height], delta_time);
+                } = profiler.scope("update", || {
+                    ctx.run_ui(ui_input, |ui| {
+                        // Run App logic and create UI
+                        app.update(world, ui, [width, height], frame_time);
+                    })
                 });
                 ui_state.handle_platform_output(window, platform_output);
-                // Generate paint job
-                let paint_jobs = ui_state.egui_ctx().tessellate(shapes, pixels_per_point);
-
-                // Perform rendering
+                if app.close_confirmed() {
+                    log::info!("Close confirmed. Exiting...");
+                    event_loop.exit();
+                }
+                // Schedule the next frame. A static scene with an idle UI waits for input instead.
+                let repaint_delay = viewport_output
+                    .get(&egui::ViewportId::ROOT)
+                    .map_or(Duration::ZERO, |viewport| viewport.repaint_delay);
+                *repaint_at = if app.is_animating() {
+                    Some(now)
+                } else {
+                    now.checked_add(repaint_delay)
+                };
+                // Whatever the ui doesn't cover blends in with it, rather than flashing
+                renderer
+                    .set_clear_color(toolkit::scene_colors(ui_state.egui_ctx().theme()).background);
+                gfx.set_frame_latency(app.frame_latency());
+                gfx.set_surface_copies(app.recorder_mut().is_recording());
+                let screen = UiScreen {
+                    size_in_pixels: [width, height],
+                    pixels_per_point,
+                }
+                .scaled(renderer.render_scale());
 
                 if width == 0 || height == 0 {
                     // Short circuit if surface is minimized
                     return;
                 }
 
-                let surface_texture = match gfx.surface.get_current_texture() {
+                // Leave the last frame on screen if this one would be identical, which lets a
+                // static window idle without touching the gpu
+                let needs_redraw = renderer.needs_redraw(screen, &shapes, &textures_delta);
+                if app.skips_unchanged_frames() && !needs_redraw {
+                    return;
+                }
+
+                let paint_jobs = ui_state
+                    .egui_ctx()
+                    .tessellate(shapes, screen.pixels_per_point);
+
+                // Perform rendering
+
+                let surface_texture = match gfx.get_current_texture() {
                     Ok(texture) => texture,
-                    Err(wgpu::SurfaceError::Outdated) => {
-                        gfx.surface.configure(&gfx.device, &gfx.surface_config);
-                        gfx.surface
-                            .get_current_texture()
-                            .expect("Failed to get surface texture after reconfiguration!")
-                    }
                     Err(error) => panic!("Failed to get surface texture {:?}", error),
                 };
                 let surface_view =
@@ -239,42 +703,107 @@ impl ApplicationHandler for Framework {
                     .device
                     .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
                 // Prepare renderer
-                renderer.prepare(gfx, world, &mut encoder);
+                renderer.reload_shaders(gfx);
+                app.draw_debug_lines(ui_state.egui_ctx(), renderer.debug_lines_mut());
+                profiler.scope("prepare", || renderer.prepare(gfx, world, &mut encoder));
+                if let Some((camera, grid)) = app.reference_grid(ui_state.egui_ctx()) {
+                    renderer.draw_reference_grid(gfx, camera, &grid);
+                }
                 // Prepare UI
-                renderer.prepare_ui(
-                    gfx,
-                    UiScreen {
-                        size_in_pixels: [width, height],
-                        pixels_per_point,
-                    },
-                    &textures_delta,
-                    &paint_jobs,
-                    &mut encoder,
-                );
+                profiler.scope("prepare_ui", || {
+                    renderer.prepare_ui(gfx, screen, &textures_delta, &paint_jobs, &mut encoder)
+                });
                 // Render
-                renderer.render(gfx, &surface_view, world, &mut encoder);
-                gfx.queue.submit(std::iter::once(encoder.finish()));
+                profiler.scope("render", || {
+                    renderer.render(gfx, &surface_view, world, &mut encoder);
+                    // Debug overlays
+                    if let Some(camera) = app.gizmo_camera() {
+                        let axis_colors = toolkit::scene_colors(ui_state.egui_ctx().theme()).axes;
+                        renderer.draw_gizmos(
+                            gfx,
+                            world,
+                            camera,
+                            axis_colors,
+                            &surface_view,
+                            &mut encoder,
+                        );
+                    }
+                });
+                // Copy the frame out for the recording, as it will be presented
+                let capture = (app.recorder_mut().wants_frame(now) && gfx.can_capture_surface())
+                    .then(|| FrameCapture::new(gfx, &mut encoder, &surface_texture.texture));
+                profiler.scope("submit", || {
+                    gfx.queue.submit(std::iter::once(encoder.finish()))
+                });
+                if let Some(capture) = capture {
+                    app.recorder_mut().push(now, capture);
+                }
                 // Free Resources
                 renderer.recall(gfx, world);
                 // Present
-                surface_texture.present();
+                profiler.scope("present", || surface_texture.present());
             }
             _ => {}
         }
     }
 
-    fn about_to_wait(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
-        let Self::State { window, .. } = self else {
+    fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        let Self::State {
+            window,
+            frame_clock,
+            repaint_at,
+            hovered_files,
+            dropped_files,
+            world,
+            app,
+            ..
+        } = self
+        else {
+            return;
+        };
+
+        if !dropped_files.is_empty() {
+            hovered_files.clear();
+            app.on_file_hovered(hovered_files);
+            for path in dropped_files.drain(..) {
+                app.on_file_dropped(world, path);
+            }
+        }
+
+        let Some(mut repaint_at) = *repaint_at else {
+            event_loop.set_control_flow(ControlFlow::Wait);
             return;
         };
-        window.request_redraw();
+
+        if let Some(max_fps) = app.max_fps() {
+            repaint_at =
+                repaint_at.max(frame_clock.last_tick() + Duration::from_secs_f32(1.0 / max_fps));
+        }
+
+        if repaint_at <= Instant::now() {
+            window.request_redraw();
+            event_loop.set_control_flow(ControlFlow::Wait);
+        } else {
+            event_loop.set_control_flow(ControlFlow::WaitUntil(repaint_at));
+        }
     }
 
     fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
-        let Self::State { world, app, .. } = self else {
+        let Self::State {
+            world,
+            profiler,
+            app,
+            ..
+        } = self
+        else {
             return;
         };
 
         app.cleanup(world);
+        app.preferences().save_default();
+
+        if let Err(error) = profiler.flush() {
+            log::error!("Failed to write trace: {error}");
+        }
     }
 }
@@ -0,0 +1,127 @@
+use std::ops::Mul;
+
+use glam::{Affine3A, Mat4, Quat, Vec3};
+
+use super::Transform;
+
+/// The world-space transform of an entity, i.e. its local [`Transform`] combined with those of
+/// all its ancestors.
+///
+/// This is computed by [`crate::components::propagate_transforms`] and should not be modified
+/// directly.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlobalTransform(Affine3A);
+
+impl GlobalTransform {
+    pub const IDENTITY: Self = Self(Affine3A::IDENTITY);
+
+    #[inline]
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self(Affine3A::from_translation(translation))
+    }
+
+    /// Returns the 3d affine transformation matrix as a [`Mat4`].
+    #[inline]
+    pub fn to_matrix(&self) -> Mat4 {
+        Mat4::from(self.0)
+    }
+
+    /// Returns the 3d affine transformation matrix as an [`Affine3A`].
+    #[inline]
+    pub fn affine(&self) -> Affine3A {
+        self.0
+    }
+
+    /// Returns the transformation as a [`Transform`].
+    ///
+    /// The transform is expected to be non-degenerate and without shearing, or the output
+    /// will be invalid.
+    #[inline]
+    pub fn compute_transform(&self) -> Transform {
+        let (scale, rotation, translation) = self.0.to_scale_rotation_translation();
+        Transform {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    /// Get the translation as a [`Vec3`].
+    #[inline]
+    pub fn translation(&self) -> Vec3 {
+        self.0.translation.into()
+    }
+
+    /// Get the rotation as a [`Quat`].
+    ///
+    /// The transform is expected to be non-degenerate and without shearing, or the output
+    /// will be invalid.
+    #[inline]
+    pub fn rotation(&self) -> Quat {
+        self.compute_transform().rotation
+    }
+
+    /// Get the scale as a [`Vec3`].
+    ///
+    /// The transform is expected to be non-degenerate and without shearing, or the output
+    /// will be invalid.
+    #[inline]
+    pub fn scale(&self) -> Vec3 {
+        self.compute_transform().scale
+    }
+
+    /// Transforms the given point from local space to global space, applying shear, scale,
+    /// rotation and translation.
+    #[inline]
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.0.transform_point3(point)
+    }
+
+    /// Multiplies `self` with `transform` component by component, returning the
+    /// resulting [`GlobalTransform`]
+    #[inline]
+    #[must_use]
+    pub fn mul_transform(&self, transform: Transform) -> Self {
+        Self(self.0 * Affine3A::from(transform))
+    }
+}
+
+impl Default for GlobalTransform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl From<Transform> for GlobalTransform {
+    fn from(transform: Transform) -> Self {
+        Self(transform.into())
+    }
+}
+
+impl From<Transform> for Affine3A {
+    fn from(transform: Transform) -> Self {
+        Affine3A::from_scale_rotation_translation(
+            transform.scale,
+            transform.rotation,
+            transform.translation,
+        )
+    }
+}
+
+impl Mul<Transform> for GlobalTransform {
+    type Output = GlobalTransform;
+
+    #[inline]
+    fn mul(self, transform: Transform) -> Self::Output {
+        self.mul_transform(transform)
+    }
+}
+
+impl Mul<Vec3> for GlobalTransform {
+    type Output = Vec3;
+
+    #[inline]
+    fn mul(self, value: Vec3) -> Self::Output {
+        self.transform_point(value)
+    }
+}
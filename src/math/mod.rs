@@ -1,6 +1,8 @@
+mod global_transform;
 mod projection;
 mod transform;
 
+pub use global_transform::GlobalTransform;
 pub use projection::{CameraProjection, OrthographicProjection, PerspectiveProjection, Projection};
 pub use transform::Transform;
 
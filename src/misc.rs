@@ -41,9 +41,44 @@ pub fn format_with_decimals_in_range(
     format_with_decimals(value, max_decimals)
 }
 
+/// How far one press of an arrow key moves a number edited to at most `decimals` decimals.
+///
+/// The step is one unit in the last of those decimals, so `0.01` for two, whatever the value
+/// happens to show. It is scaled by the held modifiers:
+///
+/// | Modifiers                        | Step |
+/// |----------------------------------|------|
+/// | None                             | 1×   |
+/// | Shift                            | 10×  |
+/// | Ctrl (⌘ on Mac) or Alt           | 0.1× |
+/// | Shift and Ctrl, or Shift and Alt | 1×   |
+pub fn nudge_step(decimals: usize, modifiers: egui::Modifiers) -> f64 {
+    let mut step = 10f64.powi(-(decimals as i32));
+    if modifiers.shift {
+        step *= 10.0;
+    }
+    if modifiers.command || modifiers.alt {
+        step *= 0.1;
+    }
+    step
+}
+
+/// How the digits of the integer part are grouped by the thousands separator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DigitGrouping {
+    /// Every three digits, like `1 234 567`.
+    #[default]
+    Western,
+
+    /// The last three digits, then every two, like `12 34 567` (lakh and crore).
+    ///
+    /// The fractional part is never grouped.
+    Indian,
+}
+
 /// Options for how to format a floating point number, e.g. an [`f64`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-struct FloatFormatOptions {
+pub struct FloatFormatOptions {
     /// Always show the sign, even if it is positive (`+`).
     pub always_sign: bool,
 
@@ -61,6 +96,15 @@ struct FloatFormatOptions {
 
     /// Only add thousands separators to decimals if there are at least this many decimals.
     pub min_decimals_for_thousands_separators: usize,
+
+    /// Put between every three digits, [`THIN_SPACE`] by default.
+    pub thousands_separator: char,
+
+    /// Where [`Self::thousands_separator`] goes, [`DigitGrouping::Western`] by default.
+    pub grouping: DigitGrouping,
+
+    /// Separates the integer part from the fractional part, `.` by default.
+    pub decimal_point: char,
 }
 
 impl FloatFormatOptions {
@@ -72,6 +116,9 @@ impl FloatFormatOptions {
         num_decimals: None,
         strip_trailing_zeros: true,
         min_decimals_for_thousands_separators: 6,
+        thousands_separator: THIN_SPACE,
+        grouping: DigitGrouping::Western,
+        decimal_point: '.',
     };
 
     /// Default options for formatting an [`f32`].
@@ -82,6 +129,9 @@ impl FloatFormatOptions {
         num_decimals: None,
         strip_trailing_zeros: true,
         min_decimals_for_thousands_separators: 6,
+        thousands_separator: THIN_SPACE,
+        grouping: DigitGrouping::Western,
+        decimal_point: '.',
     };
 
     /// Default options for formatting an [`f64`].
@@ -92,6 +142,9 @@ impl FloatFormatOptions {
         num_decimals: None,
         strip_trailing_zeros: true,
         min_decimals_for_thousands_separators: 6,
+        thousands_separator: THIN_SPACE,
+        grouping: DigitGrouping::Western,
+        decimal_point: '.',
     };
 
     /// Always show the sign, even if it is positive (`+`).
@@ -125,6 +178,37 @@ impl FloatFormatOptions {
         self
     }
 
+    /// Only add thousands separators to decimals if there are at least this many decimals.
+    #[inline]
+    pub fn with_min_decimals_for_thousands_separators(
+        mut self,
+        min_decimals_for_thousands_separators: usize,
+    ) -> Self {
+        self.min_decimals_for_thousands_separators = min_decimals_for_thousands_separators;
+        self
+    }
+
+    /// Use e.g. `'.'` for European style `1.234,56`.
+    #[inline]
+    pub fn with_thousands_separator(mut self, thousands_separator: char) -> Self {
+        self.thousands_separator = thousands_separator;
+        self
+    }
+
+    /// Use [`DigitGrouping::Indian`] for `12,34,567`.
+    #[inline]
+    pub fn with_grouping(mut self, grouping: DigitGrouping) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// Use e.g. `','` for European style `1.234,56`.
+    #[inline]
+    pub fn with_decimal_point(mut self, decimal_point: char) -> Self {
+        self.decimal_point = decimal_point;
+        self
+    }
+
     /// The returned value is for human eyes only, and can not be parsed
     /// by the normal `f64::from_str` function.
     pub fn format(&self, value: impl Into<f64>) -> String {
@@ -142,6 +226,9 @@ impl FloatFormatOptions {
             num_decimals,
             strip_trailing_zeros,
             min_decimals_for_thousands_separators,
+            thousands_separator,
+            grouping,
+            decimal_point,
         } = *self;
 
         if value.is_nan() {
@@ -168,6 +255,7 @@ impl FloatFormatOptions {
                 // so use scientific notation.
                 // TODO(emilk): nice formatting of scientific notation with thousands separators
                 format!("{:.*e}", precision.saturating_sub(1), value)
+                    .replace('.', decimal_point.encode_utf8(&mut [0; 4]))
             } else {
                 let max_decimals = max_decimals as usize;
 
@@ -193,18 +281,23 @@ impl FloatFormatOptions {
                     let fractional_part = &formatted[dot + 1..];
                     // let fractional_part = &fractional_part[..num_decimals.min(fractional_part.len())];
 
-                    let integer_part = add_thousands_separators(integer_part);
+                    let integer_part =
+                        add_digit_separators(integer_part, thousands_separator, grouping);
 
-                    if fractional_part.len() < min_decimals_for_thousands_separators {
-                        format!("{integer_part}.{fractional_part}")
+                    if fractional_part.len() < min_decimals_for_thousands_separators
+                        || grouping == DigitGrouping::Indian
+                    {
+                        format!("{integer_part}{decimal_point}{fractional_part}")
                     } else {
                         // For the fractional part we should start counting thousand separators from the _front_, so we reverse:
-                        let fractional_part =
-                            reverse(&add_thousands_separators(&reverse(fractional_part)));
-                        format!("{integer_part}.{fractional_part}")
+                        let fractional_part = reverse(&add_thousands_separators(
+                            &reverse(fractional_part),
+                            thousands_separator,
+                        ));
+                        format!("{integer_part}{decimal_point}{fractional_part}")
                     }
                 } else {
-                    add_thousands_separators(&formatted) // it's an integer
+                    add_digit_separators(&formatted, thousands_separator, grouping) // it's an integer
                 }
             }
         };
@@ -233,19 +326,165 @@ pub fn format_f32(value: f32) -> String {
 ///
 /// For human eyes only.
 pub fn format_lat_lon(value: f64) -> String {
+    format_lat_lon_with_decimals(value, 6)
+}
+
+/// Format a latitude or longitude value with exactly `num_decimals` decimals, like `+12.34°`.
+///
+/// For human eyes only.
+pub fn format_lat_lon_with_decimals(value: f64, num_decimals: usize) -> String {
     format!(
         "{}°",
         FloatFormatOptions {
             always_sign: true,
-            precision: 10,
-            num_decimals: Some(6),
+            // Up to three digits before the decimal point (180°), plus some margin:
+            precision: num_decimals + 4,
+            num_decimals: Some(num_decimals),
             strip_trailing_zeros: false,
-            min_decimals_for_thousands_separators: 10,
+            // Never group the decimals:
+            min_decimals_for_thousands_separators: num_decimals + 1,
+            ..FloatFormatOptions::DEFAULT_f64
         }
         .format_f64(value)
     )
 }
 
+/// Which axis a coordinate is on, which decides its hemisphere letters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LatLon {
+    /// `N` or `S`
+    Latitude,
+    /// `E` or `W`
+    Longitude,
+}
+
+impl LatLon {
+    /// The letter of the hemisphere a positive or `negative` coordinate is in, like `S` for a
+    /// negative latitude.
+    pub fn hemisphere(self, negative: bool) -> char {
+        match (self, negative) {
+            (Self::Latitude, false) => 'N',
+            (Self::Latitude, true) => 'S',
+            (Self::Longitude, false) => 'E',
+            (Self::Longitude, true) => 'W',
+        }
+    }
+}
+
+/// Format a latitude or longitude in degrees, minutes and seconds, like `40°26′46″N`.
+///
+/// Rounds to the nearest second. Zero has no hemisphere letter.
+pub fn format_lat_lon_dms(value: f64, axis: LatLon) -> String {
+    if !value.is_finite() {
+        return format_lat_lon(value);
+    }
+
+    // Round the total, so 59.9″ carries into the minutes instead of showing as 60″:
+    let total_seconds = (value.abs() * 3600.0).round() as u64;
+    let degrees = total_seconds / 3600;
+    let minutes = total_seconds / 60 % 60;
+    let seconds = total_seconds % 60;
+
+    if total_seconds == 0 {
+        return format!("{degrees}°{minutes}′{seconds}″");
+    }
+    let hemisphere = axis.hemisphere(value < 0.0);
+    format!("{degrees}°{minutes}′{seconds}″{hemisphere}")
+}
+
+/// Format a latitude or longitude in degrees with six decimals and a hemisphere letter instead of
+/// a sign, like `40.446111°N`.
+///
+/// Values that round to zero have no hemisphere letter.
+pub fn format_lat_lon_with_hemisphere(value: f64, axis: LatLon) -> String {
+    const NUM_DECIMALS: usize = 6;
+
+    if !value.is_finite() {
+        return format_lat_lon(value);
+    }
+
+    let degrees = format_lat_lon_with_decimals(value.abs(), NUM_DECIMALS);
+    let degrees = degrees.trim_start_matches('+');
+    if value.abs() < 0.5 * 10f64.powi(-(NUM_DECIMALS as i32)) {
+        return degrees.to_owned();
+    }
+    format!("{degrees}{}", axis.hemisphere(value < 0.0))
+}
+
+/// Parse a latitude or longitude, e.g. as formatted by [`format_lat_lon`],
+/// [`format_lat_lon_with_hemisphere`] or [`format_lat_lon_dms`].
+///
+/// Accepts decimal degrees with an optional `°`, or degrees, minutes and seconds like `40°26′46″`
+/// (also with `'` and `"`). Either a sign or an `N`/`S`/`E`/`W` suffix may be given, where `S` and
+/// `W` negate, but not both.
+pub fn parse_lat_lon(text: &str) -> Option<f64> {
+    parse_degrees(text, None)
+}
+
+/// Like [`parse_lat_lon`], but only accepts the hemisphere letters of `axis`.
+pub fn parse_lat_lon_on_axis(text: &str, axis: LatLon) -> Option<f64> {
+    parse_degrees(text, Some(axis))
+}
+
+fn parse_degrees(text: &str, axis: Option<LatLon>) -> Option<f64> {
+    let text = strip_whitespace_and_normalize(text);
+
+    let hemisphere_sign = match text.chars().last()?.to_ascii_uppercase() {
+        'N' if axis != Some(LatLon::Longitude) => Some(1.0),
+        'S' if axis != Some(LatLon::Longitude) => Some(-1.0),
+        'E' if axis != Some(LatLon::Latitude) => Some(1.0),
+        'W' if axis != Some(LatLon::Latitude) => Some(-1.0),
+        _ => None,
+    };
+    let text = if hemisphere_sign.is_some() {
+        &text[..text.len() - 1]
+    } else {
+        text.as_str()
+    };
+
+    let (sign, text) = if let Some(text) = text.strip_prefix('-') {
+        (Some(-1.0), text)
+    } else if let Some(text) = text.strip_prefix('+') {
+        (Some(1.0), text)
+    } else {
+        (None, text)
+    };
+    let sign = match (sign, hemisphere_sign) {
+        // Like `−12°S`, which is ambiguous
+        (Some(_), Some(_)) => return None,
+        (sign, hemisphere_sign) => sign.or(hemisphere_sign).unwrap_or(1.0),
+    };
+
+    // Minutes and seconds are each less than 60
+    let sexagesimal = |text: &str| -> Option<f64> {
+        let value: f64 = text.parse().ok()?;
+        (0.0..60.0).contains(&value).then_some(value)
+    };
+    let (degrees, mut rest) = text.split_once('°').unwrap_or((text, ""));
+    let mut value: f64 = degrees.parse().ok()?;
+    if value.is_sign_negative() {
+        return None;
+    }
+    if let Some((minutes, after)) = rest.split_once(['′', '\'']) {
+        value += sexagesimal(minutes)? / 60.0;
+        rest = after;
+    }
+    if let Some(seconds) = rest.strip_suffix(['″', '"']) {
+        value += sexagesimal(seconds)? / 3600.0;
+        rest = "";
+    }
+    if !rest.is_empty() {
+        return None;
+    }
+
+    value.is_finite().then_some(sign * value)
+}
+
+/// Wrap a longitude in degrees to (−180°, 180°], so both `−180°` and `180°` become `180°`.
+pub fn wrap_longitude(lon: f64) -> f64 {
+    180.0 - (180.0 - lon).rem_euclid(360.0)
+}
+
 // --- Numbers ---
 
 /// The minus character: <https://www.compart.com/en/unicode/U+2212>
@@ -261,42 +500,226 @@ pub const THIN_SPACE: char = '\u{2009}';
 /// Prepare a string containing a number for parsing
 pub fn strip_whitespace_and_normalize(text: &str) -> String {
     text.chars()
-        // Ignore whitespace (trailing, leading, and thousands separators):
-        .filter(|c| !c.is_whitespace())
+        // Ignore whitespace (trailing, leading, and thousands separators).
+        // `THIN_SPACE` is whitespace too, but be explicit since the formatting depends on it:
+        .filter(|&c| !c.is_whitespace() && c != THIN_SPACE)
         // Replace special minus character with normal minus (hyphen):
         .map(|c| if c == MINUS { '-' } else { c })
         .collect()
 }
 
+/// Format an integer with thousands separators, like `−1 234 567`.
+pub fn format_int(value: i64) -> String {
+    // `unsigned_abs` handles `i64::MIN`, which `abs` would overflow on:
+    let abs = add_thousands_separators(&value.unsigned_abs().to_string(), THIN_SPACE);
+    if value < 0 {
+        format!("{MINUS}{abs}")
+    } else {
+        abs
+    }
+}
+
+/// Format an unsigned integer with thousands separators, like `1 234 567`.
+pub fn format_uint(value: u64) -> String {
+    add_thousands_separators(&value.to_string(), THIN_SPACE)
+}
+
+/// Format an ordinal number, like `1st`, `2nd`, `23rd` or `111th`.
+pub fn format_ordinal(value: u64) -> String {
+    let suffix = match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{value}{suffix}")
+}
+
+/// Format a fraction as a percentage, like `0.125` as `12.5%`.
+///
+/// Uses at most one decimal, and none for whole percentages.
+pub fn format_percent(fraction: f64) -> String {
+    format!(
+        "{}%",
+        format_with_decimals_in_range(100.0 * fraction, 0..=1)
+    )
+}
+
+/// Format a duration in the largest unit that keeps it above one, like `16.7 ms` or `850 µs`.
+///
+/// Uses at most two decimals.
+pub fn format_duration(duration: std::time::Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    let (value, unit) = if seconds >= 1.0 {
+        (seconds, "s")
+    } else if seconds >= 1e-3 {
+        (seconds * 1e3, "ms")
+    } else if seconds >= 1e-6 {
+        (seconds * 1e6, "µs")
+    } else {
+        (seconds * 1e9, "ns")
+    };
+    format!("{} {unit}", format_with_decimals_in_range(value, 0..=2))
+}
+
+/// SI prefixes from `10^-15` to `10^15`, in steps of a thousand.
+const SI_PREFIXES: [&str; 11] = ["f", "p", "n", "µ", "m", "", "k", "M", "G", "T", "P"];
+/// Index of the empty prefix in [`SI_PREFIXES`].
+const SI_UNPREFIXED: usize = 5;
+
+/// Format a physical quantity with an SI prefix that keeps the number between one and a thousand,
+/// like `1500.0, "Hz"` as `1.5 kHz`.
+///
+/// `options` formats the scaled number, so e.g. [`FloatFormatOptions::with_decimals`] counts
+/// decimals of the `1.5`. The unit follows a [`THIN_SPACE`]. Values outside the prefixes, zero and
+/// non-finite values keep the unprefixed unit. For units that don't take prefixes, like degrees
+/// or bytes in powers of two, use [`format_with_fixed_unit`] instead.
+pub fn format_with_unit(value: f64, unit: &str, options: FloatFormatOptions) -> String {
+    let mut index = if value == 0.0 || !value.is_finite() {
+        SI_UNPREFIXED
+    } else {
+        let thousands = (value.abs().log10() / 3.0).floor() as isize;
+        (SI_UNPREFIXED as isize + thousands).clamp(0, SI_PREFIXES.len() as isize - 1) as usize
+    };
+    let scale = |index: usize| {
+        // Scale by exact powers of ten, rather than inexact ones like `1e-3`, to keep rounding
+        // errors out of the last digits
+        let exponent = 3 * (index as i32 - SI_UNPREFIXED as i32);
+        if exponent >= 0 {
+            value / 10f64.powi(exponent)
+        } else {
+            value * 10f64.powi(-exponent)
+        }
+    };
+
+    // Rounding can carry over into the next prefix, like `999.96` to `1000 Hz` with one decimal
+    let mut scaled = scale(index);
+    if let Some(num_decimals) = options.num_decimals {
+        let factor = 10f64.powi(num_decimals as i32);
+        if scaled.is_finite()
+            && (scaled.abs() * factor).round() / factor >= 1000.0
+            && index + 1 < SI_PREFIXES.len()
+        {
+            index += 1;
+            scaled = scale(index);
+        }
+    }
+
+    format!(
+        "{}{THIN_SPACE}{}{unit}",
+        options.format(scaled),
+        SI_PREFIXES[index]
+    )
+}
+
+/// Like [`format_with_unit`], but always in `unit` itself, without an SI prefix.
+pub fn format_with_fixed_unit(value: f64, unit: &str, options: FloatFormatOptions) -> String {
+    format!("{}{THIN_SPACE}{unit}", options.format(value))
+}
+
 /// Add thousands separators to a number, every three steps,
 /// counting from the last character.
-fn add_thousands_separators(number: &str) -> String {
+fn add_thousands_separators(number: &str, separator: char) -> String {
+    add_digit_separators(number, separator, DigitGrouping::Western)
+}
+
+/// Add separators to a number as described by `grouping`, counting from the last character.
+fn add_digit_separators(number: &str, separator: char, grouping: DigitGrouping) -> String {
     let mut chars = number.chars().rev().peekable();
 
     let mut result = vec![];
+    let mut group_size = 3;
     while chars.peek().is_some() {
         if !result.is_empty() {
             // thousands-deliminator:
-            result.push(THIN_SPACE);
+            result.push(separator);
         }
-        for _ in 0..3 {
+        for _ in 0..group_size {
             if let Some(c) = chars.next() {
                 result.push(c);
             }
         }
+        // Only the first group has three digits in the Indian system:
+        if grouping == DigitGrouping::Indian {
+            group_size = 2;
+        }
     }
 
     result.reverse();
     result.into_iter().collect()
 }
-/// Parse
-/// s a number, ignoring whitespace (e.g. thousand separators),
+/// Parses a number, ignoring whitespace (e.g. thousand separators),
 /// and treating the special minus character `MINUS` (−) as a minus sign.
+///
+/// Accepts everything [`format_f64`] produces, including `∞`.
 pub fn parse_f64(text: &str) -> Option<f64> {
     let text = strip_whitespace_and_normalize(text);
+    // `format_f64` writes infinity as `∞`, which `f64::from_str` doesn't understand:
+    let text = text.replace('∞', "inf");
     text.parse().ok()
 }
 
+/// Clamp `value` to `range`, mapping NaN to the start of the range.
+///
+/// Infinities end up at the nearest bound, like any other out of range value.
+pub fn sanitize_f64(value: f64, range: std::ops::RangeInclusive<f64>) -> f64 {
+    let (min, max) = range.into_inner();
+    debug_assert!(min <= max);
+    if value.is_nan() {
+        min
+    } else {
+        value.clamp(min, max)
+    }
+}
+
+/// Whether `value` is a whole number, so the decimal point can be left out.
+///
+/// NaN and infinities are not.
+pub fn is_effectively_integer(value: f64) -> bool {
+    value.is_finite() && value.fract() == 0.0
+}
+
+// --- Colors ---
+
+/// Format a color as `#RRGGBB`, or `#RRGGBBAA` if it is not opaque.
+pub fn format_hex_color(color: egui::Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    if a == u8::MAX {
+        format!("#{r:02X}{g:02X}{b:02X}")
+    } else {
+        format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+    }
+}
+
+/// Parse a `#RGB`, `#RRGGBB` or `#RRGGBBAA` color, with or without the leading `#`.
+///
+/// The alpha is unmultiplied, like in CSS.
+pub fn parse_hex_color(text: &str) -> Option<egui::Color32> {
+    let text = text.trim();
+    let hex = text.strip_prefix('#').unwrap_or(text);
+    // Also rules out the `+` that `from_str_radix` would accept:
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let byte = |i: usize| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok();
+    // Short form digits are repeated, so `#F80` is `#FF8800`:
+    let nibble = |i: usize| u8::from_str_radix(&hex[i..=i], 16).ok().map(|d| d * 0x11);
+
+    match hex.len() {
+        3 => Some(egui::Color32::from_rgb(nibble(0)?, nibble(1)?, nibble(2)?)),
+        6 => Some(egui::Color32::from_rgb(byte(0)?, byte(1)?, byte(2)?)),
+        8 => Some(egui::Color32::from_rgba_unmultiplied(
+            byte(0)?,
+            byte(1)?,
+            byte(2)?,
+            byte(3)?,
+        )),
+        _ => None,
+    }
+}
+
 // Linear interpolation
 
 /// Performs linear interpolation.
@@ -402,3 +825,661 @@ impl_lerp_for_array!(2; 0, 1);
 impl_lerp_for_array!(3; 0, 1, 2);
 impl_lerp_for_array!(4; 0, 1, 2, 3);
 impl_lerp_for_array!(5; 0, 1, 2, 3, 4);
+
+// Logging
+
+/// How large [`init_logging`]'s log file grows before it is rotated.
+#[cfg(not(target_arch = "wasm32"))]
+pub const LOG_FILE_MAX_SIZE: u64 = 8 * 1024 * 1024;
+/// How many rotated log files [`init_logging`] keeps, besides the one being written.
+#[cfg(not(target_arch = "wasm32"))]
+pub const LOG_FILE_BACKUPS: usize = 3;
+
+/// Set up logging to stderr, the in-app console, and optionally a file, all filtered the same.
+///
+/// The level comes from `RUST_LOG`, `info` if it isn't set, and `RUST_LOG_STYLE=never` turns off
+/// colors. `STELLAR_LOG_FILE=<path>` also writes plain text logs to `path`, rotated once they
+/// reach [`LOG_FILE_MAX_SIZE`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn init_logging() -> eyre::Result<crate::toolkit::console::LogBuffer> {
+    use eyre::WrapErr as _;
+    use std::io::Write as _;
+
+    let mut builder = env_logger::Builder::from_env(
+        env_logger::Env::default()
+            .default_filter_or("info")
+            .default_write_style_or("always"),
+    );
+    builder.format(move |buf, record| {
+        let level_style = buf.default_level_style(record.level());
+
+        writeln!(
+            buf,
+            "{level_style}[{}]{level_style:#}: {}",
+            record.level(),
+            record.args()
+        )
+    });
+
+    let file = match std::env::var_os("STELLAR_LOG_FILE") {
+        Some(path) => {
+            let file = RotatingFile::open(&path, LOG_FILE_MAX_SIZE, LOG_FILE_BACKUPS)
+                .wrap_err_with(|| format!("Failed to open log file {path:?}"))?;
+            Some(Box::new(file) as Box<dyn std::io::Write + Send>)
+        }
+        None => None,
+    };
+
+    Ok(crate::toolkit::console::install(&mut builder, file)?)
+}
+
+/// A file that is appended to until it reaches `max_size`, then moved aside to `<path>.1`, with
+/// older files moved up to `<path>.2` and so on, keeping at most `backups` of them.
+///
+/// Rotation only happens between writes, so write whole lines at a time to keep them together.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct RotatingFile {
+    path: std::path::PathBuf,
+    max_size: u64,
+    backups: usize,
+    file: std::fs::File,
+    size: u64,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RotatingFile {
+    /// Append to `path`, creating it and its parent directories if needed.
+    pub fn open(
+        path: impl Into<std::path::PathBuf>,
+        max_size: u64,
+        backups: usize,
+    ) -> std::io::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = Self::append(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_size,
+            backups,
+            file,
+            size,
+        })
+    }
+
+    fn append(path: &std::path::Path) -> std::io::Result<std::fs::File> {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+    }
+
+    fn backup_path(&self, index: usize) -> std::path::PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        std::io::Write::flush(&mut self.file)?;
+        if self.backups == 0 {
+            // Nowhere to move it to, start over
+            self.file.set_len(0)?;
+        } else {
+            // The oldest is overwritten by the rename
+            for index in (1..self.backups).rev() {
+                let from = self.backup_path(index);
+                if from.exists() {
+                    std::fs::rename(from, self.backup_path(index + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, self.backup_path(1))?;
+            self.file = Self::append(&self.path)?;
+        }
+        self.size = 0;
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl std::io::Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // A single write larger than the limit still goes into a file of its own
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        let written = std::io::Write::write(&mut self.file, buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::Write::flush(&mut self.file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colors() {
+        use egui::Color32;
+        for (text, color) in [
+            ("#F80", Color32::from_rgb(0xFF, 0x88, 0x00)),
+            ("f80", Color32::from_rgb(0xFF, 0x88, 0x00)),
+            ("#12AbEf", Color32::from_rgb(0x12, 0xAB, 0xEF)),
+            ("  #000000 ", Color32::BLACK),
+            (
+                "#FF000080",
+                Color32::from_rgba_unmultiplied(0xFF, 0x00, 0x00, 0x80),
+            ),
+        ] {
+            assert_eq!(parse_hex_color(text), Some(color), "parsing {text:?}");
+        }
+
+        for text in [
+            "",
+            "#",
+            "#F",
+            "#FF",
+            "#FFFF",
+            "#FFFFF",
+            "#FFFFFFF",
+            "#FFFFFFFFF",
+            "#GGG",
+            "+FF",
+            "#+F0F0F",
+            "##FFF",
+            "# FFF",
+            "#ＦＦＦ",
+        ] {
+            assert_eq!(parse_hex_color(text), None, "parsing {text:?}");
+        }
+
+        for color in [
+            Color32::BLACK,
+            Color32::from_rgb(0x12, 0xAB, 0xEF),
+            Color32::from_rgba_unmultiplied(0x10, 0x20, 0x30, 0x40),
+        ] {
+            assert_eq!(parse_hex_color(&format_hex_color(color)), Some(color));
+        }
+        assert_eq!(
+            format_hex_color(Color32::from_rgb(0x12, 0xAB, 0xEF)),
+            "#12ABEF"
+        );
+    }
+
+    #[test]
+    fn lat_lon_round_trip() {
+        // Decimal degrees keep their six decimals
+        for value in [0.0, 12.345_678, -45.000_001, 179.999_999, -89.5] {
+            let parsed = parse_lat_lon(&format_lat_lon(value)).unwrap();
+            assert!((parsed - value).abs() <= 0.5e-6);
+            let parsed = parse_lat_lon_on_axis(
+                &format_lat_lon_with_hemisphere(value, LatLon::Latitude),
+                LatLon::Latitude,
+            )
+            .unwrap();
+            assert!((parsed - value).abs() <= 0.5e-6);
+        }
+    }
+
+    #[test]
+    fn lat_lon_hemispheres() {
+        assert_eq!(
+            format_lat_lon_with_hemisphere(40.446_111, LatLon::Latitude),
+            "40.446111°N"
+        );
+        assert_eq!(
+            format_lat_lon_with_hemisphere(-79.982_222, LatLon::Longitude),
+            "79.982222°W"
+        );
+        // Zero, even after rounding, has no hemisphere
+        assert_eq!(
+            format_lat_lon_with_hemisphere(-0.0, LatLon::Latitude),
+            "0.000000°"
+        );
+        assert_eq!(
+            format_lat_lon_with_hemisphere(0.000_000_1, LatLon::Longitude),
+            "0.000000°"
+        );
+
+        for (text, value) in [
+            ("12.5N", 12.5),
+            ("12.5 s", -12.5),
+            ("12.5°E", 12.5),
+            ("12.5° W", -12.5),
+            ("−12.5°", -12.5),
+            ("+12.5", 12.5),
+        ] {
+            assert_eq!(parse_lat_lon(text), Some(value), "parsing {text:?}");
+        }
+
+        // Only the letters of the axis
+        assert_eq!(parse_lat_lon_on_axis("12N", LatLon::Latitude), Some(12.0));
+        assert_eq!(parse_lat_lon_on_axis("12E", LatLon::Latitude), None);
+        assert_eq!(parse_lat_lon_on_axis("12W", LatLon::Longitude), Some(-12.0));
+        assert_eq!(parse_lat_lon_on_axis("12S", LatLon::Longitude), None);
+    }
+
+    #[test]
+    fn wrap_longitude_keeps_the_antimeridian() {
+        for (lon, wrapped) in [
+            (0.0, 0.0),
+            (179.5, 179.5),
+            (-179.5, -179.5),
+            (180.0, 180.0),
+            // The antimeridian is always 180°, never −180°
+            (-180.0, 180.0),
+            (180.5, -179.5),
+            (-180.5, 179.5),
+            (360.0, 0.0),
+            (540.0, 180.0),
+            (-720.0, 0.0),
+        ] {
+            assert_eq!(wrap_longitude(lon), wrapped, "wrapping {lon}");
+        }
+    }
+
+    #[test]
+    fn lat_lon_rejects_malformed_input() {
+        for text in [
+            "",
+            "°",
+            "N",
+            "abc",
+            "12X",
+            "12°°",
+            "12NN",
+            "--12",
+            // A sign and a hemisphere contradict or repeat each other
+            "−12°S",
+            "+12N",
+            // Minutes and seconds are less than 60, and not negative
+            "40°60′",
+            "40°26′60″",
+            "40°−5′",
+            // Seconds without minutes, or minutes without degrees
+            "40°26″′",
+            "26′",
+            "inf",
+            "NaN",
+        ] {
+            assert_eq!(parse_lat_lon(text), None, "parsing {text:?}");
+        }
+    }
+
+    #[test]
+    fn format_parse_f64_round_trip() {
+        for exponent in -12..=18 {
+            for mantissa in [1.0, 1.5, std::f64::consts::E, 9.876_543_21, 1.0 / 3.0] {
+                for value in [
+                    mantissa * 10f64.powi(exponent),
+                    -mantissa * 10f64.powi(exponent),
+                ] {
+                    let text = format_f64(value);
+                    let parsed = parse_f64(&text).unwrap_or_else(|| {
+                        panic!("Failed to parse {text:?}, formatted from {value}")
+                    });
+                    // 15 digits of precision, but at most 15 decimals for small numbers
+                    let tolerance = (value.abs() * 1e-14).max(1e-15);
+                    assert!(
+                        (parsed - value).abs() <= tolerance,
+                        "{value} was formatted as {text:?} and parsed as {parsed}"
+                    );
+                }
+            }
+        }
+
+        for value in [0.0, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(parse_f64(&format_f64(value)), Some(value));
+        }
+        assert!(parse_f64(&format_f64(f64::NAN)).unwrap().is_nan());
+    }
+
+    #[test]
+    fn thousands_separators_on_both_sides_of_the_decimal_point() {
+        for (value, formatted) in [
+            (1_234_567.0, "1 234 567"),
+            (1_234_567.125, "1 234 567.125"),
+            (-1_234_567.123_456_79, "−1 234 567.123 456 79"),
+            (0.123_456_789, "0.123 456 789"),
+            // The fractional part is grouped from the decimal point, so the short group is last
+            (0.123_456_7, "0.123 456 7"),
+            (0.000_001, "0.000 001"),
+            // Fewer than six decimals are not grouped
+            (0.123_45, "0.12345"),
+        ] {
+            let formatted = formatted.replace(' ', &THIN_SPACE.to_string());
+            assert_eq!(format_f64(value), formatted);
+            assert_eq!(parse_f64(&formatted), Some(value), "parsing {formatted:?}");
+        }
+    }
+
+    #[test]
+    fn parse_f64_ignores_separators_and_accepts_minus() {
+        assert_eq!(
+            parse_f64("1\u{2009}234\u{2009}567.123\u{2009}4"),
+            Some(1_234_567.123_4)
+        );
+        assert_eq!(parse_f64(" 1 234\u{a0}567 "), Some(1_234_567.0));
+        assert_eq!(parse_f64("−0.5"), Some(-0.5));
+        assert_eq!(parse_f64("−∞"), Some(f64::NEG_INFINITY));
+        assert_eq!(parse_f64("1.2.3"), None);
+        assert_eq!(parse_f64(""), None);
+        assert_eq!(
+            strip_whitespace_and_normalize("−1\u{2009}234\u{a0}5 6\t7"),
+            "-1234567"
+        );
+    }
+
+    #[test]
+    fn integers_and_ordinals() {
+        assert_eq!(format_int(0), "0");
+        assert_eq!(format_int(999), "999");
+        assert_eq!(format_int(-1_234_567), "−1\u{2009}234\u{2009}567");
+        assert_eq!(
+            format_int(i64::MIN),
+            "−9\u{2009}223\u{2009}372\u{2009}036\u{2009}854\u{2009}775\u{2009}808"
+        );
+        assert_eq!(format_uint(1000), "1\u{2009}000");
+        assert_eq!(
+            format_uint(u64::MAX),
+            "18\u{2009}446\u{2009}744\u{2009}073\u{2009}709\u{2009}551\u{2009}615"
+        );
+
+        for (value, ordinal) in [
+            (0, "0th"),
+            (1, "1st"),
+            (2, "2nd"),
+            (3, "3rd"),
+            (4, "4th"),
+            (11, "11th"),
+            (12, "12th"),
+            (13, "13th"),
+            (21, "21st"),
+            (22, "22nd"),
+            (23, "23rd"),
+            (101, "101st"),
+            (111, "111th"),
+            (112, "112th"),
+            (113, "113th"),
+            (1_002, "1002nd"),
+        ] {
+            assert_eq!(format_ordinal(value), ordinal);
+        }
+    }
+
+    #[test]
+    fn lat_lon_dms_round_trip() {
+        for axis in [LatLon::Latitude, LatLon::Longitude] {
+            let max = if axis == LatLon::Latitude { 90 } else { 180 };
+            for step in -max * 8..=max * 8 {
+                let value = step as f64 / 8.0 + 0.000_123;
+                let text = format_lat_lon_dms(value, axis);
+                let parsed = parse_lat_lon_on_axis(&text, axis)
+                    .unwrap_or_else(|| panic!("Failed to parse {text:?}, formatted from {value}"));
+                // Rounded to the nearest second
+                assert!(
+                    (parsed - value).abs() <= 0.5 / 3600.0 + 1e-9,
+                    "{value} was formatted as {text:?} and parsed as {parsed}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn lat_lon_dms() {
+        assert_eq!(
+            format_lat_lon_dms(40.446_111, LatLon::Latitude),
+            "40°26′46″N"
+        );
+        assert_eq!(
+            format_lat_lon_dms(-40.446_111, LatLon::Latitude),
+            "40°26′46″S"
+        );
+        assert_eq!(
+            format_lat_lon_dms(79.982_222, LatLon::Longitude),
+            "79°58′56″E"
+        );
+        assert_eq!(
+            format_lat_lon_dms(-79.982_222, LatLon::Longitude),
+            "79°58′56″W"
+        );
+        // 59.9″ carries over instead of showing as 60″
+        assert_eq!(
+            format_lat_lon_dms(-(10.0 + 59.9 / 3600.0), LatLon::Latitude),
+            "10°1′0″S"
+        );
+        // Zero, even after rounding, has no hemisphere
+        assert_eq!(format_lat_lon_dms(-0.000_01, LatLon::Longitude), "0°0′0″");
+
+        assert_eq!(format_lat_lon_dms(90.0, LatLon::Latitude), "90°0′0″N");
+        assert_eq!(format_lat_lon_dms(-90.0, LatLon::Latitude), "90°0′0″S");
+        assert_eq!(format_lat_lon_dms(180.0, LatLon::Longitude), "180°0′0″E");
+        assert_eq!(format_lat_lon_dms(-180.0, LatLon::Longitude), "180°0′0″W");
+
+        for (text, value) in [
+            ("40°26'46\"N", 40.0 + 26.0 / 60.0 + 46.0 / 3600.0),
+            ("40°30′S", -40.5),
+            ("90°0′0″S", -90.0),
+            ("180°0′0″E", 180.0),
+        ] {
+            assert_eq!(parse_lat_lon(text), Some(value), "parsing {text:?}");
+        }
+    }
+
+    #[test]
+    fn custom_separators() {
+        let european = FloatFormatOptions::DEFAULT_f64
+            .with_thousands_separator('.')
+            .with_decimal_point(',');
+        let comma = FloatFormatOptions::DEFAULT_f64.with_thousands_separator(',');
+
+        for (options, value, formatted) in [
+            (european, 1_234_567.25, "1.234.567,25"),
+            (european, -1_234_567.25, "−1.234.567,25"),
+            (european, 0.5, "0,5"),
+            (european, 1234.0, "1.234"),
+            (comma, 1_234_567.0, "1,234,567"),
+            (comma, -9_876_543.21, "−9,876,543.21"),
+            (comma, 0.123_456_789, "0.123,456,789"),
+        ] {
+            assert_eq!(options.format(value), formatted);
+        }
+
+        // Scientific notation uses the decimal point too
+        assert_eq!(european.format(1.5e20), "1,50000000000000e20");
+    }
+
+    #[test]
+    fn percentages() {
+        assert_eq!(format_percent(0.0), "0%");
+        assert_eq!(format_percent(0.125), "12.5%");
+        assert_eq!(format_percent(0.5), "50%");
+        assert_eq!(format_percent(1.0), "100%");
+        assert_eq!(format_percent(0.3333), "33.3%");
+        assert_eq!(format_percent(-0.25), "−25%");
+    }
+
+    #[test]
+    fn digit_grouping() {
+        for (number, western, indian) in [
+            ("", "", ""),
+            ("1", "1", "1"),
+            ("123", "123", "123"),
+            ("1234", "1,234", "1,234"),
+            ("12345", "12,345", "12,345"),
+            ("123456", "123,456", "1,23,456"),
+            ("1234567", "1,234,567", "12,34,567"),
+            ("123456789", "123,456,789", "12,34,56,789"),
+            ("1234567890", "1,234,567,890", "1,23,45,67,890"),
+        ] {
+            assert_eq!(
+                add_digit_separators(number, ',', DigitGrouping::Western),
+                western
+            );
+            assert_eq!(
+                add_digit_separators(number, ',', DigitGrouping::Indian),
+                indian
+            );
+        }
+    }
+
+    #[test]
+    fn indian_grouping() {
+        let indian = FloatFormatOptions::DEFAULT_f64
+            .with_thousands_separator(',')
+            .with_grouping(DigitGrouping::Indian);
+        for (value, formatted) in [
+            (123.0, "123"),
+            (1234.0, "1,234"),
+            (1_234_567.0, "12,34,567"),
+            (12_345_678.0, "1,23,45,678"),
+            (-123.0, "−123"),
+            (-1234.0, "−1,234"),
+            (-1_234_567.5, "−12,34,567.5"),
+            // The fractional part is never grouped the Indian way
+            (0.123_456_789, "0.123456789"),
+        ] {
+            assert_eq!(indian.format(value), formatted);
+        }
+    }
+
+    #[test]
+    fn sanitize_and_integers() {
+        assert_eq!(sanitize_f64(0.5, 0.0..=1.0), 0.5);
+        assert_eq!(sanitize_f64(-3.0, 0.0..=1.0), 0.0);
+        assert_eq!(sanitize_f64(3.0, 0.0..=1.0), 1.0);
+        assert_eq!(sanitize_f64(f64::NAN, -1.0..=1.0), -1.0);
+        assert_eq!(sanitize_f64(f64::INFINITY, -1.0..=1.0), 1.0);
+        assert_eq!(sanitize_f64(f64::NEG_INFINITY, -1.0..=1.0), -1.0);
+        assert_eq!(
+            sanitize_f64(f64::INFINITY, 0.0..=f64::INFINITY),
+            f64::INFINITY
+        );
+
+        for value in [0.0, -0.0, 1.0, -42.0, 1e300] {
+            assert!(is_effectively_integer(value), "{value}");
+        }
+        for value in [0.5, -1e-9, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(!is_effectively_integer(value), "{value}");
+        }
+    }
+
+    #[test]
+    fn durations() {
+        use std::time::Duration;
+        for (duration, formatted) in [
+            (Duration::ZERO, "0 ns"),
+            (Duration::from_nanos(999), "999 ns"),
+            (Duration::from_nanos(1_000), "1 µs"),
+            (Duration::from_micros(850), "850 µs"),
+            (Duration::from_millis(1), "1 ms"),
+            (Duration::from_nanos(16_666_667), "16.67 ms"),
+            (Duration::from_millis(1_500), "1.5 s"),
+            (Duration::from_secs(90), "90 s"),
+        ] {
+            assert_eq!(format_duration(duration), formatted);
+        }
+    }
+
+    #[test]
+    fn si_prefixes() {
+        let options = FloatFormatOptions::DEFAULT_f64;
+        for (value, formatted) in [
+            (0.0, "0\u{2009}Hz"),
+            (1.0, "1\u{2009}Hz"),
+            (999.0, "999\u{2009}Hz"),
+            (1000.0, "1\u{2009}kHz"),
+            (1500.0, "1.5\u{2009}kHz"),
+            (-2500.0, "−2.5\u{2009}kHz"),
+            (0.001, "1\u{2009}mHz"),
+            (0.000_999, "999\u{2009}µHz"),
+            (1e-15, "1\u{2009}fHz"),
+            (1e15, "1\u{2009}PHz"),
+            // Past the ends, the outermost prefix is kept
+            (1e-16, "0.1\u{2009}fHz"),
+            (1e18, "1\u{2009}000\u{2009}PHz"),
+            (f64::INFINITY, "∞\u{2009}Hz"),
+            (f64::NAN, "NaN\u{2009}Hz"),
+        ] {
+            assert_eq!(format_with_unit(value, "Hz", options), formatted);
+        }
+
+        // Rounding to the decimals shown carries over into the next prefix
+        let one_decimal = options.with_decimals(1);
+        assert_eq!(format_with_unit(999.96, "Hz", options), "999.96\u{2009}Hz");
+        assert_eq!(format_with_unit(999.96, "Hz", one_decimal), "1\u{2009}kHz");
+        assert_eq!(
+            format_with_unit(f64::INFINITY, "Hz", one_decimal),
+            "∞\u{2009}Hz"
+        );
+
+        assert_eq!(
+            format_with_fixed_unit(1500.0, "°", options),
+            "1\u{2009}500\u{2009}°"
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn rotating_file_keeps_backups() {
+        use std::io::Write as _;
+
+        let dir = std::env::temp_dir().join(format!(
+            "stellar-rotating-file-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        // Parent directories are created
+        let path = dir.join("logs").join("test.log");
+        let read = |path: &std::path::Path| std::fs::read_to_string(path).unwrap_or_default();
+        let backup = |index: usize| {
+            let mut backup = path.clone().into_os_string();
+            backup.push(format!(".{index}"));
+            std::path::PathBuf::from(backup)
+        };
+
+        let mut file = RotatingFile::open(&path, 12, 2).unwrap();
+        for line in 1..=5 {
+            file.write_all(format!("line {line}\n").as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+        // Two lines don't fit in 12 bytes, so each went into a file of its own
+        assert_eq!(read(&path), "line 5\n");
+        assert_eq!(read(&backup(1)), "line 4\n");
+        assert_eq!(read(&backup(2)), "line 3\n");
+        assert!(!backup(3).exists());
+
+        // Reopening appends, counting what is already there
+        drop(file);
+        let mut file = RotatingFile::open(&path, 12, 2).unwrap();
+        file.write_all(b"more\n").unwrap();
+        assert_eq!(read(&path), "line 5\nmore\n");
+        // Too large for any file, so it gets one of its own
+        file.write_all(b"a very long line\n").unwrap();
+        file.flush().unwrap();
+        assert_eq!(read(&path), "a very long line\n");
+        assert_eq!(read(&backup(1)), "line 5\nmore\n");
+        assert_eq!(read(&backup(2)), "line 4\n");
+
+        // Without backups, the file starts over
+        let path = dir.join("no-backups.log");
+        let mut file = RotatingFile::open(&path, 12, 0).unwrap();
+        for line in 1..=3 {
+            file.write_all(format!("line {line}\n").as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+        assert_eq!(read(&path), "line 3\n");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+//! Lightweight CPU timing of the event loop, written out in the Chrome tracing format.
+//!
+//! Open the resulting file in `chrome://tracing` or <https://ui.perfetto.dev>.
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use web_time::Instant;
+
+struct Span {
+    name: &'static str,
+    start: Duration,
+    duration: Duration,
+}
+
+/// Records named spans while enabled, and does nothing otherwise.
+#[derive(Default)]
+pub struct Profiler {
+    /// Where [`Self::flush`] writes to. `None` disables recording entirely.
+    path: Option<PathBuf>,
+    start: Option<Instant>,
+    spans: Vec<Span>,
+}
+
+impl Profiler {
+    /// Record spans, and write them to `path` when flushed.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+            start: Some(Instant::now()),
+            spans: Vec::new(),
+        }
+    }
+
+    /// Enabled by setting `STELLAR_TRACE` to the output path.
+    pub fn from_env() -> Self {
+        match std::env::var_os("STELLAR_TRACE") {
+            Some(path) => Self::new(path),
+            None => Self::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Run `f`, and record how long it took under `name`.
+    #[inline]
+    pub fn scope<R>(&mut self, name: &'static str, f: impl FnOnce() -> R) -> R {
+        let Some(origin) = self.start else {
+            return f();
+        };
+
+        let start = Instant::now();
+        let result = f();
+        self.spans.push(Span {
+            name,
+            start: start - origin,
+            duration: start.elapsed(),
+        });
+        result
+    }
+
+    /// Write every span recorded so far to disk as Chrome tracing JSON.
+    pub fn flush(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let mut json = String::from("{\"traceEvents\":[\n");
+        for (i, span) in self.spans.iter().enumerate() {
+            if i > 0 {
+                json.push_str(",\n");
+            }
+            // Timestamps are in microseconds. "X" is a complete event, with a duration:
+            let _ = write!(
+                json,
+                "{{\"name\":\"{}\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\"pid\":0,\"tid\":0}}",
+                span.name,
+                span.start.as_secs_f64() * 1e6,
+                span.duration.as_secs_f64() * 1e6,
+            );
+        }
+        json.push_str("\n]}\n");
+
+        std::fs::write(path, json)?;
+        log::info!(
+            "Wrote {} trace spans to {}",
+            self.spans.len(),
+            path.display()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use std::sync::{Arc, Mutex};
+
+use eyre::WrapErr as _;
+
+use super::Graphics;
+
+/// A rendered frame on its way back from the gpu, for screenshots and recordings.
+///
+/// Created while encoding a frame, it copies the target into a buffer. Once the encoder has been
+/// submitted, either block on [`Self::read_blocking`], or [`Self::map`] it and check back with
+/// [`Self::try_read`] on later frames.
+pub struct FrameCapture {
+    buffer: wgpu::Buffer,
+    width: u32,
+    height: u32,
+    padded_bytes_per_row: u32,
+    /// Red and blue are swapped, and swapped back when read.
+    bgra: bool,
+    /// Set once [`Self::map`] has finished.
+    mapped: Arc<Mutex<Option<Result<(), wgpu::BufferAsyncError>>>>,
+}
+
+impl FrameCapture {
+    /// Whether frames in `format` can be captured. Only 8 bit RGBA and BGRA can be.
+    pub fn supports(format: wgpu::TextureFormat) -> bool {
+        matches!(
+            format,
+            wgpu::TextureFormat::Rgba8Unorm
+                | wgpu::TextureFormat::Rgba8UnormSrgb
+                | wgpu::TextureFormat::Bgra8Unorm
+                | wgpu::TextureFormat::Bgra8UnormSrgb
+        )
+    }
+
+    /// Copy `texture` into a buffer with `encoder`. The texture needs
+    /// [`wgpu::TextureUsages::COPY_SRC`], and a format [`Self::supports`].
+    pub fn new(
+        gfx: &Graphics,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+    ) -> Self {
+        debug_assert!(Self::supports(texture.format()));
+        let size = texture.size();
+        let (width, height) = (size.width, size.height);
+
+        // Rows of a buffer copy have to be aligned
+        let bytes_per_pixel = 4;
+        let padded_bytes_per_row =
+            (width * bytes_per_pixel).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = gfx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frame_capture"),
+            size: padded_bytes_per_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        Self {
+            buffer,
+            width,
+            height,
+            padded_bytes_per_row,
+            bgra: matches!(
+                texture.format(),
+                wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+            ),
+            mapped: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Start reading the frame back, once the encoder it was copied with has been submitted.
+    ///
+    /// The gpu only gets to it when the device is polled, which submitting more work also does.
+    pub fn map(&self) {
+        let mapped = self.mapped.clone();
+        self.buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                *mapped.lock().unwrap() = Some(result);
+            });
+    }
+
+    /// The frame, if [`Self::map`] has finished. Failures are returned, too, since the capture
+    /// is of no use after one.
+    pub fn try_read(&self) -> Option<eyre::Result<image::RgbaImage>> {
+        let result = self.mapped.lock().unwrap().take()?;
+        Some(
+            result
+                .wrap_err("Failed to read back the frame")
+                .and_then(|()| self.pixels()),
+        )
+    }
+
+    /// Map the frame and wait for it, stalling until the gpu has finished rendering it.
+    pub fn read_blocking(self, gfx: &Graphics) -> eyre::Result<image::RgbaImage> {
+        self.map();
+        gfx.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .wrap_err("Failed to wait for the gpu")?;
+        self.try_read()
+            .ok_or_else(|| eyre::eyre!("Frame readback was dropped"))?
+    }
+
+    fn pixels(&self) -> eyre::Result<image::RgbaImage> {
+        let unpadded_bytes_per_row = self.width as usize * 4;
+        let mut pixels = Vec::with_capacity(unpadded_bytes_per_row * self.height as usize);
+        {
+            let bytes = self.buffer.slice(..).get_mapped_range();
+            for row in bytes.chunks_exact(self.padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row]);
+            }
+        }
+        self.buffer.unmap();
+
+        if self.bgra {
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+        image::RgbaImage::from_raw(self.width, self.height, pixels)
+            .ok_or_else(|| eyre::eyre!("Frame has the wrong number of pixels"))
+    }
+}
@@ -0,0 +1,250 @@
+use crate::math::{GlobalTransform, Transform};
+use crate::renderer::{Graphics, Immediates};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct GizmoVertex {
+    position: glam::Vec3,
+    color: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct GizmoImmediates {
+    clip_from_world: glam::Mat4,
+}
+
+/// Draws the orientation of a camera as XYZ axes in the bottom left corner of the screen.
+pub struct GizmoRenderer {
+    immediates: Immediates<GizmoImmediates>,
+    pipeline: wgpu::RenderPipeline,
+    /// Kept around to rebuild the pipeline when the shader is reloaded
+    layout: wgpu::PipelineLayout,
+    output_color_format: wgpu::TextureFormat,
+    axes: wgpu::Buffer,
+    /// What `axes` was last filled with, so it is only rewritten when the theme changes
+    axis_colors: Option<[egui::Rgba; 3]>,
+
+    depth: wgpu::Texture,
+    depth_view: wgpu::TextureView,
+}
+
+impl GizmoRenderer {
+    const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+    /// Side length of the gizmo, in physical pixels.
+    const SIZE: f32 = 96.0;
+    /// Distance from the corner of the screen, in physical pixels.
+    const MARGIN: f32 = 16.0;
+
+    pub fn new(gfx: &Graphics, output_color_format: wgpu::TextureFormat) -> Self {
+        let immediates = Immediates::new(gfx, 0);
+        let shader =
+            immediates.create_shader_module(gfx, "gizmo", include_str!("shaders/gizmo.wgsl"));
+        let layout = immediates.create_pipeline_layout(gfx, &[]);
+
+        let pipeline = Self::create_pipeline(gfx, &layout, &shader, output_color_format);
+
+        let axes = gfx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gizmo_axes"),
+            size: size_of::<[GizmoVertex; 6]>() as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let (depth, depth_view) =
+            create_depth(gfx, gfx.surface_config.width, gfx.surface_config.height);
+
+        Self {
+            immediates,
+            pipeline,
+            layout,
+            output_color_format,
+            axes,
+            axis_colors: None,
+            depth,
+            depth_view,
+        }
+    }
+
+    /// Rebuild the pipeline from new shader `source`, keeping the old one if it doesn't compile.
+    #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+    pub fn reload_shader(&mut self, gfx: &Graphics, source: &str) {
+        let pipeline = super::hot_reload::try_create(gfx, "gizmo shader", || {
+            let shader = self.immediates.create_shader_module(gfx, "gizmo", source);
+            Self::create_pipeline(gfx, &self.layout, &shader, self.output_color_format)
+        });
+        if let Some(pipeline) = pipeline {
+            self.pipeline = pipeline;
+        }
+    }
+
+    fn create_pipeline(
+        gfx: &Graphics,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        output_color_format: wgpu::TextureFormat,
+    ) -> wgpu::RenderPipeline {
+        gfx.device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("gizmo_pipeline"),
+                layout: Some(layout),
+                vertex: wgpu::VertexState {
+                    module: shader,
+                    entry_point: Some("vs_main"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: size_of::<GizmoVertex>() as u64,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        // 0: vec3 position
+                        // 1: vec4 color
+                        attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x4],
+                    }],
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::LineList,
+                    ..gfx.fullscreen_primitive_state()
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: Self::DEPTH_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Less,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: shader,
+                    entry_point: Some("fs_main"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: output_color_format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview_mask: None,
+                cache: None,
+            })
+    }
+
+    /// Draw the axes of `camera` on top of whatever is already in `target`, in the linear
+    /// `axis_colors` of X, Y and Z, like [`crate::toolkit::SceneColors::axes`].
+    ///
+    /// Runs in its own pass, with a cleared depth buffer, so the axes occlude each other correctly
+    /// but are never hidden by the scene.
+    pub fn draw(
+        &mut self,
+        gfx: &Graphics,
+        world: &hecs::World,
+        camera: hecs::Entity,
+        axis_colors: [egui::Rgba; 3],
+        target: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let rotation = match world.get::<&GlobalTransform>(camera) {
+            Ok(global) => global.rotation(),
+            Err(_) => match world.get::<&Transform>(camera) {
+                Ok(transform) => transform.rotation,
+                Err(_) => return,
+            },
+        };
+
+        let [width, height] = [gfx.surface_config.width, gfx.surface_config.height];
+        if width == 0 || height == 0 {
+            return;
+        }
+        if self.depth.width() != width || self.depth.height() != height {
+            (self.depth, self.depth_view) = create_depth(gfx, width, height);
+        }
+        if self.axis_colors != Some(axis_colors) {
+            self.write_axes(gfx, axis_colors);
+        }
+
+        // Only rotate with the camera, and look at the origin from a fixed distance.
+        let view = glam::Mat4::from_translation(glam::Vec3::new(0.0, 0.0, -2.0))
+            * glam::Mat4::from_quat(rotation.inverse());
+        let proj = glam::Mat4::orthographic_rh(-1.2, 1.2, -1.2, 1.2, 0.5, 3.5);
+
+        let size = Self::SIZE.min(width as f32).min(height as f32);
+        let x = Self::MARGIN.min(width as f32 - size);
+        let y = (height as f32 - size - Self::MARGIN).max(0.0);
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("gizmo_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                stencil_ops: None,
+            }),
+            ..Default::default()
+        });
+
+        render_pass.set_viewport(x, y, size, size, 0.0, 1.0);
+        render_pass.set_pipeline(&self.pipeline);
+        self.immediates.set(
+            gfx,
+            &mut render_pass,
+            &GizmoImmediates {
+                clip_from_world: proj * view,
+            },
+        );
+        render_pass.set_vertex_buffer(0, self.axes.slice(..));
+        render_pass.draw(0..6, 0..1);
+    }
+
+    fn write_axes(&mut self, gfx: &Graphics, axis_colors: [egui::Rgba; 3]) {
+        // Written as they are, so a target that doesn't encode to sRGB needs sRGB values
+        let srgb_target = self.output_color_format.is_srgb();
+        let directions = [glam::Vec3::X, glam::Vec3::Y, glam::Vec3::Z];
+        let mut vertices = Vec::with_capacity(6);
+        for (direction, color) in directions.into_iter().zip(axis_colors) {
+            let color = if srgb_target {
+                color.to_array()
+            } else {
+                egui::Color32::from(color).to_normalized_gamma_f32()
+            };
+            vertices.push(GizmoVertex {
+                position: glam::Vec3::ZERO,
+                color,
+            });
+            vertices.push(GizmoVertex {
+                position: direction,
+                color,
+            });
+        }
+        gfx.queue
+            .write_buffer(&self.axes, 0, bytemuck::cast_slice(&vertices));
+        self.axis_colors = Some(axis_colors);
+    }
+}
+
+fn create_depth(gfx: &Graphics, width: u32, height: u32) -> (wgpu::Texture, wgpu::TextureView) {
+    let depth = gfx.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("gizmo_depth"),
+        size: wgpu::Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: GizmoRenderer::DEPTH_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let view = depth.create_view(&wgpu::TextureViewDescriptor::default());
+    (depth, view)
+}
@@ -1,5 +1,6 @@
-use std::{borrow::Cow, num::NonZero};
+use std::{borrow::Cow, marker::PhantomData, num::NonZero};
 
+use eyre::WrapErr as _;
 use smallvec::SmallVec;
 use wesl::include_wesl;
 
@@ -8,76 +9,205 @@ pub struct Graphics {
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
 
-    pub surface: wgpu::Surface<'static>,
+    /// `None` for headless graphics, which render into offscreen textures instead.
+    pub surface: Option<wgpu::Surface<'static>>,
+    /// Size and format of the final render target, even without a surface.
     pub surface_config: wgpu::SurfaceConfiguration,
 
     pub surface_format: wgpu::TextureFormat,
     pub hdr_format: wgpu::TextureFormat,
     pub bloom_format: wgpu::TextureFormat,
 
+    /// Whether the device can write GPU timestamps into render passes.
+    pub supports_timestamps: bool,
+    /// Whether pipelines can take immediates, see [`Immediates`].
+    pub supports_immediates: bool,
+    /// Whether the surface can be configured to be copied from, see [`Self::set_surface_copies`].
+    supports_surface_copies: bool,
+
     fullscreen_shader: wgpu::ShaderModule,
 }
 
+/// Options that control how [`Graphics`] picks a gpu and presents to the window.
+#[derive(Clone, Copy, Debug)]
+pub struct GraphicsConfig {
+    /// Fall back to a software adapter (llvmpipe, WARP) if no hardware adapter is usable.
+    pub allow_fallback_adapter: bool,
+    /// How many frames may be queued up for presentation, see [`Graphics::set_frame_latency`].
+    pub frame_latency: u32,
+}
+
+impl Default for GraphicsConfig {
+    fn default() -> Self {
+        Self {
+            allow_fallback_adapter: false,
+            frame_latency: 2,
+        }
+    }
+}
+
+impl GraphicsConfig {
+    /// Reads `STELLAR_FALLBACK_ADAPTER=1` and `STELLAR_FRAME_LATENCY=<frames>` from the
+    /// environment.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            allow_fallback_adapter: std::env::var("STELLAR_FALLBACK_ADAPTER")
+                .is_ok_and(|value| value == "1" || value.eq_ignore_ascii_case("true")),
+            frame_latency: std::env::var("STELLAR_FRAME_LATENCY")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default.frame_latency),
+        }
+    }
+}
+
+/// Frame latencies that make sense to ask for. Backends clamp further to what the swapchain allows.
+const FRAME_LATENCY_RANGE: std::ops::RangeInclusive<u32> = 1..=3;
+
 impl Graphics {
     pub async fn new(
         window: impl Into<wgpu::SurfaceTarget<'static>>,
         width: u32,
         height: u32,
-    ) -> Self {
+        config: GraphicsConfig,
+    ) -> eyre::Result<Self> {
         let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
-        let surface = instance.create_surface(window).unwrap();
-        let adapter = instance
+        let surface = instance
+            .create_surface(window)
+            .wrap_err("Failed to create a surface for the window")?;
+        Self::with_surface(instance, Some(surface), width, height, config).await
+    }
+
+    /// Graphics without a window, e.g. for rendering screenshots.
+    ///
+    /// The final render target has to be a texture of size `width`×`height` with
+    /// [`Self::surface_format`].
+    pub async fn new_headless(
+        width: u32,
+        height: u32,
+        config: GraphicsConfig,
+    ) -> eyre::Result<Self> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        Self::with_surface(instance, None, width, height, config).await
+    }
+
+    /// [`Self::new`], blocking until the gpu is ready.
+    ///
+    /// Not available on the web, where the browser can't be blocked and [`Self::new`] has to be
+    /// awaited instead.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new_blocking(
+        window: impl Into<wgpu::SurfaceTarget<'static>>,
+        width: u32,
+        height: u32,
+        config: GraphicsConfig,
+    ) -> eyre::Result<Self> {
+        pollster::block_on(Self::new(window, width, height, config))
+    }
+
+    /// [`Self::new_headless`], blocking until the gpu is ready.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new_headless_blocking(
+        width: u32,
+        height: u32,
+        config: GraphicsConfig,
+    ) -> eyre::Result<Self> {
+        pollster::block_on(Self::new_headless(width, height, config))
+    }
+
+    async fn with_surface(
+        instance: wgpu::Instance,
+        surface: Option<wgpu::Surface<'static>>,
+        width: u32,
+        height: u32,
+        config: GraphicsConfig,
+    ) -> eyre::Result<Self> {
+        let adapter = match instance
             .request_adapter(&wgpu::RequestAdapterOptionsBase {
                 power_preference: wgpu::PowerPreference::HighPerformance,
                 force_fallback_adapter: false,
-                compatible_surface: Some(&surface),
+                compatible_surface: surface.as_ref(),
             })
             .await
-            .expect("Failed to request gpu adapter");
-
-        let wgpu::Features {
-            features_wgpu,
-            features_webgpu,
-        } = adapter.features();
+        {
+            Ok(adapter) => adapter,
+            Err(error) if config.allow_fallback_adapter => {
+                log::warn!("No hardware gpu adapter available ({error}), using a fallback adapter");
+                log::warn!("Rendering will happen in software, expect poor performance");
+                instance
+                    .request_adapter(&wgpu::RequestAdapterOptionsBase {
+                        power_preference: wgpu::PowerPreference::HighPerformance,
+                        force_fallback_adapter: true,
+                        compatible_surface: surface.as_ref(),
+                    })
+                    .await
+                    .wrap_err("Failed to find a compatible fallback gpu adapter")?
+            }
+            Err(error) => {
+                return Err(error).wrap_err("Failed to find a compatible gpu adapter");
+            }
+        };
 
-        assert!(
-            features_wgpu.contains(wgpu::FeaturesWGPU::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        log::info!("Adapter: {:?}", adapter.get_info());
+
+        // Each has a fallback, see where they are checked below. Browsers support few of them.
+        let features = adapter.features().intersection(
+            // More formats can be rendered to and blended, e.g. for the bloom and hdr targets
+            wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
+                | wgpu::Features::RG11B10UFLOAT_RENDERABLE
+                // Otherwise passed through uniform buffers, see `Immediates`
+                | wgpu::Features::IMMEDIATES
+                // Only used to measure how long passes take on the gpu
+                | wgpu::Features::TIMESTAMP_QUERY,
         );
-        assert!(features_webgpu.contains(wgpu::FeaturesWebGPU::RG11B10UFLOAT_RENDERABLE));
+        let supports_timestamps = features.contains(wgpu::Features::TIMESTAMP_QUERY);
+        let supports_immediates = features.contains(wgpu::Features::IMMEDIATES);
+        log::info!("Optional gpu features: {features:?}");
+
+        // wgpu would only name the first limit that is too low, list them all
+        LimitsReport::new(&required_limits(features), &adapter.limits())
+            .into_result()
+            .wrap_err_with(|| {
+                format!(
+                    "The gpu adapter {:?} is not supported",
+                    adapter.get_info().name
+                )
+            })?;
 
         let (device, queue) = adapter
             .request_device(&wgpu::DeviceDescriptor {
                 label: Some("GPU Device"),
                 memory_hints: wgpu::MemoryHints::Performance,
-                required_features: wgpu::Features {
-                    features_wgpu: wgpu::FeaturesWGPU::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
-                    features_webgpu: wgpu::FeaturesWebGPU::RG11B10UFLOAT_RENDERABLE
-                        | wgpu::FeaturesWebGPU::IMMEDIATES,
-                },
-                required_limits: wgpu::Limits {
-                    max_immediate_size: 128,
-                    max_color_attachment_bytes_per_sample: 48,
-                    ..wgpu::Limits::defaults()
-                }
-                .using_resolution(adapter.limits()),
+                required_features: features,
+                required_limits: required_limits(features).using_resolution(adapter.limits()),
                 experimental_features: wgpu::ExperimentalFeatures::disabled(),
                 trace: wgpu::Trace::Off,
             })
             .await
-            .expect("Failed to request gpu device");
-
-        let surface_capabilities = surface.get_capabilities(&adapter);
-        let surface_format = surface_capabilities
-            .formats
-            .iter()
-            .copied()
-            .find(|f| !f.is_srgb())
-            .unwrap_or(surface_capabilities.formats[0]);
-
-        // Temporary
-        if surface_format.is_srgb() {
-            panic!("SRGB render target currently not supported (see tonemap.frag.wgsl)!")
-        }
+            .wrap_err("Failed to request gpu device")?;
+
+        let (surface_format, supports_surface_copies) = match &surface {
+            Some(surface) => {
+                let surface_capabilities = surface.get_capabilities(&adapter);
+                let format = surface_capabilities
+                    .formats
+                    .iter()
+                    .copied()
+                    .find(|f| !f.is_srgb())
+                    // Software adapters may only offer a single format
+                    .or(surface_capabilities.formats.first().copied())
+                    .ok_or_else(|| {
+                        eyre::eyre!("The surface is incompatible with the gpu adapter")
+                    })?;
+                let supports_copies = surface_capabilities
+                    .usages
+                    .contains(wgpu::TextureUsages::COPY_SRC);
+                (format, supports_copies)
+            }
+            // Easy to turn into an image
+            None => (wgpu::TextureFormat::Rgba8Unorm, false),
+        };
 
         log::info!("Surface format: {:?}", surface_format);
 
@@ -86,13 +216,16 @@ impl Graphics {
             format: surface_format,
             width,
             height,
+            // Both fall back to modes every adapter supports
             present_mode: wgpu::PresentMode::AutoVsync,
             alpha_mode: wgpu::CompositeAlphaMode::Auto,
             view_formats: vec![],
-            desired_maximum_frame_latency: 2,
+            desired_maximum_frame_latency: clamp_frame_latency(config.frame_latency),
         };
 
-        surface.configure(&device, &surface_config);
+        if let Some(surface) = &surface {
+            surface.configure(&device, &surface_config);
+        }
 
         let hdr_format_candidates = [
             wgpu::TextureFormat::Rgba16Float,
@@ -102,18 +235,20 @@ impl Graphics {
         let hdr_format = hdr_format_candidates
             .into_iter()
             .find(|format| {
-                adapter
-                    .get_texture_format_features(*format)
+                format_features(&adapter, features, *format)
                     .allowed_usages
                     .contains(
                         wgpu::TextureUsages::RENDER_ATTACHMENT
                             | wgpu::TextureUsages::TEXTURE_BINDING,
                     )
             })
-            .expect("Unable to find valid HDR texture format for post-processing");
+            .ok_or_else(|| {
+                eyre::eyre!("Unable to find valid HDR texture format for post-processing")
+            })?;
 
         log::info!("HDR format: {:?}", hdr_format);
 
+        // Rg11b10Ufloat can only be rendered to with its feature
         let bloom_format_candidates = [
             wgpu::TextureFormat::Rg11b10Ufloat,
             wgpu::TextureFormat::Rgba16Float,
@@ -123,15 +258,16 @@ impl Graphics {
         let bloom_format = bloom_format_candidates
             .into_iter()
             .find(|format| {
-                adapter
-                    .get_texture_format_features(*format)
+                format_features(&adapter, features, *format)
                     .allowed_usages
                     .contains(
                         wgpu::TextureUsages::RENDER_ATTACHMENT
                             | wgpu::TextureUsages::TEXTURE_BINDING,
                     )
             })
-            .expect("Unable to find valid bloom texture format for post-processing");
+            .ok_or_else(|| {
+                eyre::eyre!("Unable to find valid bloom texture format for post-processing")
+            })?;
 
         log::info!("Bloom format: {:?}", bloom_format);
 
@@ -140,7 +276,7 @@ impl Graphics {
             source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_wesl!("fullscreen"))),
         });
 
-        Self {
+        Ok(Self {
             _instance: instance,
             surface,
             device,
@@ -149,15 +285,122 @@ impl Graphics {
             surface_format,
             hdr_format,
             bloom_format,
+            supports_timestamps,
+            supports_immediates,
+            supports_surface_copies,
             fullscreen_shader,
-        }
+        })
+    }
+
+    /// Compare the device's limits against [`required_limits`].
+    ///
+    /// Devices are always created with at least those, so this only finds something when the
+    /// renderer comes to need more than it asked for. Adapters are checked the same way before a
+    /// device is requested, and one that falls short fails [`Self::new`] with the report.
+    pub fn check_limits(&self) -> LimitsReport {
+        LimitsReport::new(
+            &required_limits(self.device.features()),
+            &self.device.limits(),
+        )
     }
 
     /// Resizes graphics context and associated objects to the given window size.
     pub fn resize(&mut self, width: u32, height: u32) {
         self.surface_config.width = width;
         self.surface_config.height = height;
-        self.surface.configure(&self.device, &self.surface_config);
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.surface_config);
+        }
+    }
+
+    pub fn frame_latency(&self) -> u32 {
+        self.surface_config.desired_maximum_frame_latency
+    }
+
+    /// How many frames the gpu may queue up before presenting, reconfiguring the surface.
+    ///
+    /// Lower values reduce the delay between input and seeing its effect, at the cost of the cpu
+    /// stalling while the gpu catches up. `1` has the least latency, `2` is the default and `3`
+    /// is triple buffering, which keeps the gpu busiest. Clamped to `1..=3`.
+    ///
+    /// wgpu doesn't report what the surface supports, and the backend may clamp the value further,
+    /// e.g. to the number of swapchain images.
+    pub fn set_frame_latency(&mut self, frame_latency: u32) {
+        let frame_latency = clamp_frame_latency(frame_latency);
+        if frame_latency == self.surface_config.desired_maximum_frame_latency {
+            return;
+        }
+        self.surface_config.desired_maximum_frame_latency = frame_latency;
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.surface_config);
+        }
+    }
+
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.surface_config.present_mode
+    }
+
+    /// How frames are handed to the display, reconfiguring the surface.
+    ///
+    /// The `Auto*` modes fall back to ones every adapter supports, e.g.
+    /// [`wgpu::PresentMode::AutoNoVsync`] to draw as fast as possible for benchmarking.
+    pub fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) {
+        if present_mode == self.surface_config.present_mode {
+            return;
+        }
+        self.surface_config.present_mode = present_mode;
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.surface_config);
+        }
+    }
+
+    /// Whether frames rendered to the surface can be copied from, reconfiguring the surface.
+    ///
+    /// Copying frames out is what lets them be recorded. It is only turned on while recording,
+    /// since it can keep the driver off its fastest way of presenting. Does nothing
+    /// where the platform doesn't allow copies, see [`Self::can_capture_surface`].
+    pub fn set_surface_copies(&mut self, enabled: bool) {
+        let usage = if enabled && self.supports_surface_copies {
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC
+        } else {
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+        };
+        if usage == self.surface_config.usage {
+            return;
+        }
+        self.surface_config.usage = usage;
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.surface_config);
+        }
+    }
+
+    /// The next texture to render to, reconfiguring the surface if it is outdated.
+    ///
+    /// # Panics
+    /// If these graphics are headless.
+    pub fn get_current_texture(&self) -> Result<wgpu::SurfaceTexture, wgpu::SurfaceError> {
+        let surface = self
+            .surface
+            .as_ref()
+            .expect("Headless graphics have no surface");
+        match surface.get_current_texture() {
+            Err(wgpu::SurfaceError::Outdated) => {
+                surface.configure(&self.device, &self.surface_config);
+                surface.get_current_texture()
+            }
+            result => result,
+        }
+    }
+
+    /// Whether frames rendered to the surface can be copied out with a [`super::FrameCapture`],
+    /// which needs [`Self::set_surface_copies`].
+    pub fn can_capture_surface(&self) -> bool {
+        self.surface.is_some()
+            && self
+                .surface_config
+                .usage
+                .contains(wgpu::TextureUsages::COPY_SRC)
+            && super::FrameCapture::supports(self.surface_format)
     }
 
     /// Constructs a new shader module from the given source code.
@@ -256,6 +499,108 @@ impl Graphics {
     }
 }
 
+/// Small parameters a pipeline takes once a pass, like the screen size for sprites, passed as
+/// immediates where the device supports them.
+///
+/// Browsers don't yet, so otherwise they go through a uniform buffer bound at the group after the
+/// pipeline's other bind groups. Shaders declare them with `var<immediate>` either way, which
+/// [`Self::create_shader_module`] rewrites. The buffer holds one value, so only the last one set
+/// before a submission is seen.
+#[derive(Debug)]
+pub struct Immediates<T> {
+    group: u32,
+    uniform: Option<ImmediatesUniform>,
+    _marker: PhantomData<T>,
+}
+
+#[derive(Debug)]
+struct ImmediatesUniform {
+    buffer: wgpu::Buffer,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+}
+
+impl<T: bytemuck::Pod> Immediates<T> {
+    /// Immediates for pipelines with `group` other bind groups.
+    pub fn new(gfx: &Graphics, group: u32) -> Self {
+        let uniform = (!gfx.supports_immediates).then(|| {
+            let buffer = gfx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("immediates"),
+                // Uniform bindings are sized in multiples of 16 bytes
+                size: size_of::<T>().next_multiple_of(16) as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            let bind_group_layout = gfx
+                .start_bind_group_layout()
+                .label("immediates_bind_group_layout")
+                .uniform_binding(0, wgpu::ShaderStages::VERTEX_FRAGMENT)
+                .finish();
+            let bind_group = gfx
+                .start_bind_group(&bind_group_layout)
+                .label("immediates_bind_group")
+                .buffer_binding(0, &buffer, 0, None)
+                .finish();
+            ImmediatesUniform {
+                buffer,
+                bind_group_layout,
+                bind_group,
+            }
+        });
+        Self {
+            group,
+            uniform,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Like [`Graphics::create_shader_module`], with the immediates declared as a uniform when
+    /// they are passed as one.
+    pub fn create_shader_module(
+        &self,
+        gfx: &Graphics,
+        name: &str,
+        source: &str,
+    ) -> wgpu::ShaderModule {
+        match &self.uniform {
+            Some(_) => {
+                let declaration = format!("@group({}) @binding(0) var<uniform>", self.group);
+                gfx.create_shader_module(name, &source.replace("var<immediate>", &declaration))
+            }
+            None => gfx.create_shader_module(name, source),
+        }
+    }
+
+    /// Like [`Graphics::create_pipeline_layout`], with room for the immediates.
+    pub fn create_pipeline_layout(
+        &self,
+        gfx: &Graphics,
+        bind_group_layouts: &[&wgpu::BindGroupLayout],
+    ) -> wgpu::PipelineLayout {
+        debug_assert_eq!(bind_group_layouts.len(), self.group as usize);
+        match &self.uniform {
+            Some(uniform) => {
+                let mut layouts = bind_group_layouts.to_vec();
+                layouts.push(&uniform.bind_group_layout);
+                gfx.create_pipeline_layout(0, &layouts)
+            }
+            None => gfx.create_pipeline_layout(size_of::<T>() as u32, bind_group_layouts),
+        }
+    }
+
+    /// Pass `value` to the pipeline set on `render_pass`.
+    pub fn set(&self, gfx: &Graphics, render_pass: &mut wgpu::RenderPass<'_>, value: &T) {
+        match &self.uniform {
+            Some(uniform) => {
+                gfx.queue
+                    .write_buffer(&uniform.buffer, 0, bytemuck::bytes_of(value));
+                render_pass.set_bind_group(self.group, &uniform.bind_group, &[]);
+            }
+            None => render_pass.set_immediates(0, bytemuck::bytes_of(value)),
+        }
+    }
+}
+
 pub struct BindGroupLayoutBuilder<'a> {
     gfx: &'a Graphics,
     label: Option<&'a str>,
@@ -524,3 +869,120 @@ impl<'a> PostProcessingBuilder<'a> {
             })
     }
 }
+
+fn clamp_frame_latency(frame_latency: u32) -> u32 {
+    let clamped = frame_latency.clamp(*FRAME_LATENCY_RANGE.start(), *FRAME_LATENCY_RANGE.end());
+    if clamped != frame_latency {
+        log::warn!("Frame latency {frame_latency} is out of range, using {clamped}");
+    }
+    clamped
+}
+
+/// Limits to start from before raising the few we need, low enough for browsers.
+fn base_limits() -> wgpu::Limits {
+    if cfg!(target_arch = "wasm32") {
+        wgpu::Limits::downlevel_webgl2_defaults()
+    } else {
+        wgpu::Limits::defaults()
+    }
+}
+
+/// Limits the renderer needs from a device with `features`. Texture sizes are raised further to
+/// whatever the adapter allows, but don't need to be.
+pub fn required_limits(features: wgpu::Features) -> wgpu::Limits {
+    let base = base_limits();
+    wgpu::Limits {
+        // Small per-draw parameters, like sprites' and gizmos', are passed as immediates
+        max_immediate_size: if features.contains(wgpu::Features::IMMEDIATES) {
+            128
+        } else {
+            base.max_immediate_size
+        },
+        // The raymarching pass writes several targets at once
+        max_color_attachment_bytes_per_sample: 48,
+        ..base
+    }
+}
+
+/// What `format` supports on a device created from `adapter` with `features`.
+///
+/// Without [`wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`] only what WebGPU
+/// guarantees can be used, however much more the adapter reports.
+fn format_features(
+    adapter: &wgpu::Adapter,
+    features: wgpu::Features,
+    format: wgpu::TextureFormat,
+) -> wgpu::TextureFormatFeatures {
+    if features.contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+        adapter.get_texture_format_features(format)
+    } else {
+        format.guaranteed_format_features(features)
+    }
+}
+
+/// A limit a gpu falls short of, see [`LimitsReport`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LimitShortfall {
+    /// The field of [`wgpu::Limits`].
+    pub name: &'static str,
+    pub required: u64,
+    pub available: u64,
+}
+
+/// Every limit a gpu falls short of, see [`Graphics::check_limits`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LimitsReport {
+    pub shortfalls: Vec<LimitShortfall>,
+}
+
+impl LimitsReport {
+    /// Compare `available` against `required`, both the maximums that must be at least as high
+    /// and the alignments that must be at least as small.
+    pub fn new(required: &wgpu::Limits, available: &wgpu::Limits) -> Self {
+        let mut shortfalls = Vec::new();
+        required.check_limits_with_fail_fn(available, false, |name, required, available| {
+            shortfalls.push(LimitShortfall {
+                name,
+                required,
+                available,
+            });
+        });
+        Self { shortfalls }
+    }
+
+    /// No limit falls short.
+    pub fn is_ok(&self) -> bool {
+        self.shortfalls.is_empty()
+    }
+
+    /// An error listing every shortfall, if there are any.
+    pub fn into_result(self) -> eyre::Result<()> {
+        if self.is_ok() {
+            Ok(())
+        } else {
+            Err(eyre::eyre!("{self}"))
+        }
+    }
+}
+
+impl std::fmt::Display for LimitsReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_ok() {
+            return write!(f, "The gpu meets every limit the renderer needs");
+        }
+        write!(
+            f,
+            "The gpu falls short of {} limit(s) the renderer needs:",
+            self.shortfalls.len()
+        )?;
+        for LimitShortfall {
+            name,
+            required,
+            available,
+        } in &self.shortfalls
+        {
+            write!(f, "\n  {name}: needs {required}, has {available}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+/// A ground grid on the XZ plane, drawn with [`super::Renderer::draw_reference_grid`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReferenceGrid {
+    /// Distance between minor lines, in world units.
+    pub minor_spacing: f32,
+    /// Every how many minor lines a major line is drawn.
+    pub major_every: u32,
+    /// Distance from the camera at which the grid has faded out completely.
+    pub fade_distance: f32,
+    /// Linear, like the rest of the renderer's colors.
+    pub minor_color: egui::Rgba,
+    pub major_color: egui::Rgba,
+}
+
+impl Default for ReferenceGrid {
+    fn default() -> Self {
+        Self {
+            minor_spacing: 1.0,
+            major_every: 10,
+            fade_distance: 100.0,
+            minor_color: egui::Color32::from_gray(60).into(),
+            major_color: egui::Color32::from_gray(120).into(),
+        }
+    }
+}
+
+impl ReferenceGrid {
+    pub fn with_spacing(mut self, minor_spacing: f32, major_every: u32) -> Self {
+        self.minor_spacing = minor_spacing;
+        self.major_every = major_every;
+        self
+    }
+
+    pub fn with_fade_distance(mut self, fade_distance: f32) -> Self {
+        self.fade_distance = fade_distance;
+        self
+    }
+
+    /// Colors for the lines, either linear or sRGB [`egui::Color32`]s, which are converted.
+    pub fn with_colors(
+        mut self,
+        minor_color: impl Into<egui::Rgba>,
+        major_color: impl Into<egui::Rgba>,
+    ) -> Self {
+        self.minor_color = minor_color.into();
+        self.major_color = major_color.into();
+        self
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct GridUniform {
+    minor_color: [f32; 4],
+    major_color: [f32; 4],
+    minor_spacing: f32,
+    major_spacing: f32,
+    fade_distance: f32,
+    _padding: f32,
+}
+
+impl From<&ReferenceGrid> for GridUniform {
+    fn from(grid: &ReferenceGrid) -> Self {
+        let minor_spacing = grid.minor_spacing.max(f32::EPSILON);
+        Self {
+            minor_color: grid.minor_color.to_array(),
+            major_color: grid.major_color.to_array(),
+            minor_spacing,
+            major_spacing: minor_spacing * grid.major_every.max(1) as f32,
+            fade_distance: grid.fade_distance.max(f32::EPSILON),
+            _padding: 0.0,
+        }
+    }
+}
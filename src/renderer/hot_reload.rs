@@ -0,0 +1,75 @@
+//! Reloading WGSL shaders from disk while the app runs, in debug builds.
+//!
+//! Only plain WGSL pipelines can be reloaded. WESL shaders are linked by the build script.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+
+use notify::Watcher as _;
+
+use super::Graphics;
+
+/// Where the shaders live in the source tree.
+const SHADER_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/renderer/shaders");
+
+/// Watches the shader directory for changes.
+pub struct ShaderWatcher {
+    _watcher: notify::RecommendedWatcher,
+    events: Receiver<notify::Result<notify::Event>>,
+}
+
+impl ShaderWatcher {
+    pub fn new() -> eyre::Result<Self> {
+        let (sender, events) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(Path::new(SHADER_DIR), notify::RecursiveMode::Recursive)?;
+        log::info!("Watching {SHADER_DIR} for shader changes");
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    /// Every `.wgsl` file written to since the last call.
+    pub fn changed(&self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for event in self.events.try_iter() {
+            let event = match event {
+                Ok(event) => event,
+                Err(error) => {
+                    log::warn!("Shader watcher error: {error}");
+                    continue;
+                }
+            };
+            // Editors often save by writing a new file and renaming it over the old one
+            if !(event.kind.is_modify() || event.kind.is_create()) {
+                continue;
+            }
+            for path in event.paths {
+                if path.extension().is_some_and(|ext| ext == "wgsl") && !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// Run `create`, and return what it made unless wgpu reported a validation error.
+///
+/// Errors are logged rather than panicking, so the caller can keep its last good pipeline.
+pub fn try_create<T>(gfx: &Graphics, name: &str, create: impl FnOnce() -> T) -> Option<T> {
+    let scope = gfx.device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let value = create();
+    match pollster::block_on(scope.pop()) {
+        None => {
+            log::info!("Reloaded {name}");
+            Some(value)
+        }
+        Some(error) => {
+            log::error!("Failed to reload {name}, keeping the previous pipeline: {error}");
+            None
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use glam::Vec3;
+
+use crate::renderer::Graphics;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct LineVertex {
+    position: Vec3,
+    color: [f32; 4],
+}
+
+/// Lines in world space, drawn over every camera for one frame and then forgotten.
+///
+/// Lines are depth tested against each other, but not against the scene, whose depth buffer holds
+/// material ids rather than depth.
+#[derive(Debug, Default)]
+pub struct DebugLines {
+    vertices: Vec<LineVertex>,
+}
+
+impl DebugLines {
+    pub fn draw_line(&mut self, a: Vec3, b: Vec3, color: egui::Color32) {
+        let color = egui::Rgba::from(color).to_array();
+        self.vertices.push(LineVertex { position: a, color });
+        self.vertices.push(LineVertex { position: b, color });
+    }
+
+    /// The twelve edges of an axis-aligned box.
+    pub fn draw_aabb(&mut self, min: Vec3, max: Vec3, color: egui::Color32) {
+        let corner = |x: bool, y: bool, z: bool| {
+            Vec3::new(
+                if x { max.x } else { min.x },
+                if y { max.y } else { min.y },
+                if z { max.z } else { min.z },
+            )
+        };
+        for a in [false, true] {
+            for b in [false, true] {
+                self.draw_line(corner(false, a, b), corner(true, a, b), color);
+                self.draw_line(corner(a, false, b), corner(a, true, b), color);
+                self.draw_line(corner(a, b, false), corner(a, b, true), color);
+            }
+        }
+    }
+
+    /// A square grid on the XZ plane around `center`, reaching `half_extent` out in each direction
+    /// with a line every `spacing`.
+    pub fn draw_grid(
+        &mut self,
+        center: Vec3,
+        half_extent: f32,
+        spacing: f32,
+        color: egui::Color32,
+    ) {
+        if spacing <= 0.0 || half_extent <= 0.0 {
+            return;
+        }
+        let count = (half_extent / spacing).floor() as i32;
+        for i in -count..=count {
+            let offset = i as f32 * spacing;
+            self.draw_line(
+                center + Vec3::new(offset, 0.0, -half_extent),
+                center + Vec3::new(offset, 0.0, half_extent),
+                color,
+            );
+            self.draw_line(
+                center + Vec3::new(-half_extent, 0.0, offset),
+                center + Vec3::new(half_extent, 0.0, offset),
+                color,
+            );
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+}
+
+/// The vertex buffer [`DebugLines`] are uploaded into, grown as needed.
+#[derive(Debug)]
+pub(crate) struct LineBuffer {
+    buffer: Option<wgpu::Buffer>,
+    vertex_count: u32,
+}
+
+impl LineBuffer {
+    pub fn new() -> Self {
+        Self {
+            buffer: None,
+            vertex_count: 0,
+        }
+    }
+
+    pub fn upload(&mut self, gfx: &Graphics, lines: &DebugLines) {
+        self.vertex_count = lines.vertices.len() as u32;
+        if lines.is_empty() {
+            return;
+        }
+
+        let size = size_of_val(lines.vertices.as_slice()) as u64;
+        if self
+            .buffer
+            .as_ref()
+            .is_none_or(|buffer| buffer.size() < size)
+        {
+            self.buffer = Some(gfx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("debug_lines"),
+                size: size.next_power_of_two(),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+        }
+
+        let buffer = self.buffer.as_ref().expect("allocated above");
+        gfx.queue
+            .write_buffer(buffer, 0, bytemuck::cast_slice(&lines.vertices));
+    }
+
+    /// The uploaded vertices, or `None` if there is nothing to draw.
+    pub fn vertices(&self) -> Option<(wgpu::BufferSlice<'_>, u32)> {
+        let buffer = self.buffer.as_ref().filter(|_| self.vertex_count > 0)?;
+        let size = self.vertex_count as u64 * size_of::<LineVertex>() as u64;
+        Some((buffer.slice(..size), self.vertex_count))
+    }
+
+    pub fn vertex_layout() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 2] =
+            wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x4];
+        wgpu::VertexBufferLayout {
+            array_stride: size_of::<LineVertex>() as u64,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            // 0: vec3 position
+            // 1: vec4 color
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
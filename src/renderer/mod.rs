@@ -1,26 +1,97 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use crate::components::Camera;
 use crate::math::Transform;
+use gizmo::GizmoRenderer;
 use hecs::Entity;
+use lines::LineBuffer;
 use smallvec::SmallVec;
+use sprite::SpriteRenderer;
+use supersample::Supersampler;
+use timer::GpuTimer;
 use ui::UiRenderer;
 
+mod capture;
+mod gizmo;
 mod graphics;
+mod grid;
+#[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+mod hot_reload;
+mod lines;
+mod sprite;
 mod stack;
+mod supersample;
+mod timer;
 mod ui;
 
-pub use graphics::Graphics;
+pub use capture::FrameCapture;
+pub use graphics::{
+    Graphics, GraphicsConfig, Immediates, LimitShortfall, LimitsReport, required_limits,
+};
+pub use grid::ReferenceGrid;
+pub use lines::DebugLines;
+pub use sprite::SpriteTexture;
 use stack::RenderStack;
-pub use ui::{UiCallback, UiScreen};
+pub use ui::{UiCallback, UiScreen, scale_ui_input};
 
 #[derive(Debug, Default)]
 pub struct Assets {
     pub textures: HashMap<String, wgpu::Texture>,
 }
 
+/// Gpu resources held by the [`Renderer`], for spotting leaks.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ResourceStats {
+    /// Live egui textures, including the font atlas.
+    pub ui_textures: usize,
+    /// Bytes taken by the egui textures.
+    pub ui_texture_bytes: u64,
+    /// Bytes the device has allocated, if the backend reports it.
+    pub device_allocated_bytes: Option<u64>,
+    /// Bytes the device has reserved, including unused parts of its memory blocks.
+    pub device_reserved_bytes: Option<u64>,
+}
+
+/// The render scales [`Renderer::set_render_scale`] supports.
+///
+/// Downsampling filters each pixel from the four texels around it, so past twice the size some
+/// texels would be skipped and it would alias again.
+pub const RENDER_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.25..=2.0;
+
+/// The background until [`Renderer::set_clear_color`] is called.
+pub const DEFAULT_CLEAR_COLOR: egui::Color32 = egui::Color32::from_rgb(48, 61, 107);
+
+/// A linear color, like [`crate::toolkit::SceneColors`], for a render pass or [`wgpu::LoadOp`].
+pub fn wgpu_color(color: egui::Rgba) -> wgpu::Color {
+    let [r, g, b, a] = color.to_array();
+    wgpu::Color {
+        r: r.into(),
+        g: g.into(),
+        b: b.into(),
+        a: a.into(),
+    }
+}
+
 pub struct Renderer {
+    /// Format of the texture rendered into, which every final pass is built for
+    target_format: wgpu::TextureFormat,
     ui: UiRenderer,
+    sprites: SpriteRenderer,
+    gizmos: GizmoRenderer,
+    /// Lines drawn over every camera this frame
+    debug_lines: DebugLines,
+    line_buffer: LineBuffer,
+    /// Times the composite pass, if the device supports timestamp queries
+    gpu_timer: Option<GpuTimer>,
+    /// Picks up edits to WGSL shaders, in debug builds
+    #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+    shader_watcher: Option<hot_reload::ShaderWatcher>,
+    /// Renders at `render_scale` times the surface size, when that isn't one
+    supersampler: Supersampler,
+    render_scale: f32,
+    /// Fills whatever the ui and sprites leave uncovered, see [`Self::set_clear_color`]
+    clear_color: egui::Rgba,
     /// Render stacks associated with each camera
     stacks: HashMap<hecs::Entity, RenderStack>,
 
@@ -30,14 +101,45 @@ pub struct Renderer {
     // Temporary state
     paint_jobs: Vec<egui::ClippedPrimitive>,
     screen: UiScreen,
+
+    /// What the last frame was rendered from, to tell whether the next one would be identical
+    last_shapes: Vec<egui::epaint::ClippedShape>,
+    last_screen: Option<UiScreen>,
 }
 
 impl Renderer {
+    /// A renderer for the window's surface.
     pub fn new(gfx: &Graphics) -> Self {
-        let ui = UiRenderer::new(&gfx.device, gfx.surface_format);
+        Self::new_for_format(gfx, gfx.surface_format)
+    }
+
+    /// A renderer for targets of `target_format`, rather than the surface, e.g. for offscreen
+    /// captures.
+    ///
+    /// sRGB formats work too: the tonemapper and ui output linear colors for them, and the
+    /// hardware encodes them back to sRGB.
+    pub fn new_for_format(gfx: &Graphics, target_format: wgpu::TextureFormat) -> Self {
+        let ui = UiRenderer::new(&gfx.device, target_format);
+        let sprites = SpriteRenderer::new(gfx, target_format);
+        let gizmos = GizmoRenderer::new(gfx, target_format);
+        let gpu_timer = GpuTimer::new(gfx);
+        let supersampler = Supersampler::new(gfx, target_format);
 
         Self {
+            target_format,
             ui,
+            sprites,
+            gizmos,
+            debug_lines: DebugLines::default(),
+            line_buffer: LineBuffer::new(),
+            gpu_timer,
+            #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+            shader_watcher: hot_reload::ShaderWatcher::new()
+                .inspect_err(|error| log::warn!("Shader hot-reloading is disabled: {error:#}"))
+                .ok(),
+            supersampler,
+            render_scale: 1.0,
+            clear_color: DEFAULT_CLEAR_COLOR.into(),
             stacks: HashMap::new(),
             assets: Assets::default(),
             paint_jobs: vec![],
@@ -45,11 +147,175 @@ impl Renderer {
                 size_in_pixels: [0, 0],
                 pixels_per_point: 0.0,
             },
+            last_shapes: Vec::new(),
+            last_screen: None,
         }
     }
 }
 
 impl Renderer {
+    /// The format of the textures this renders into.
+    pub fn target_format(&self) -> wgpu::TextureFormat {
+        self.target_format
+    }
+
+    /// Whether the ui pipeline writes linear colors for an sRGB-encoding target.
+    ///
+    /// The surface format is chosen to be non-sRGB, so this is normally `false`.
+    pub fn ui_srgb_target(&self) -> bool {
+        self.ui.srgb_target()
+    }
+
+    /// How long the final composite pass (ui and all composited viewports) took on the gpu.
+    ///
+    /// A few frames out of date, and `None` if the device doesn't support timestamp queries.
+    pub fn last_gpu_time(&self) -> Option<Duration> {
+        self.gpu_timer.as_ref().and_then(GpuTimer::last)
+    }
+
+    /// Count the textures the ui holds, and ask the device how much memory it uses.
+    ///
+    /// Generating the device report walks every allocation, so don't call this every frame unless
+    /// the numbers are being looked at.
+    pub fn resource_stats(&self, gfx: &Graphics) -> ResourceStats {
+        let (ui_textures, ui_texture_bytes) = self.ui.texture_stats();
+        let report = gfx.device.generate_allocator_report();
+        ResourceStats {
+            ui_textures,
+            ui_texture_bytes,
+            device_allocated_bytes: report.as_ref().map(|report| report.total_allocated_bytes),
+            device_reserved_bytes: report.as_ref().map(|report| report.total_reserved_bytes),
+        }
+    }
+
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale
+    }
+
+    /// Render at `render_scale` times the size of the surface, and filter down onto it.
+    ///
+    /// `2.0` supersamples for crisp screenshots, and values below one trade sharpness for speed.
+    /// Clamped to [`RENDER_SCALE_RANGE`]. Run the ui with [`scale_ui_input`] and pass the
+    /// [`UiScreen`] through [`UiScreen::scaled`] to match.
+    pub fn set_render_scale(&mut self, render_scale: f32) {
+        self.render_scale =
+            render_scale.clamp(*RENDER_SCALE_RANGE.start(), *RENDER_SCALE_RANGE.end());
+        if self.render_scale == 1.0 {
+            self.supersampler.release();
+        }
+    }
+
+    /// The background of every frame, under the sprites and the ui.
+    ///
+    /// Match it to the ui's panel color, like [`crate::toolkit::SceneColors::background`], so any
+    /// part of the window the ui hasn't covered yet, like a newly exposed edge while resizing,
+    /// doesn't flash a different color. sRGB [`egui::Color32`]s are converted to linear.
+    pub fn set_clear_color(&mut self, clear_color: impl Into<egui::Rgba>) {
+        let clear_color = clear_color.into();
+        if clear_color != self.clear_color {
+            self.clear_color = clear_color;
+            self.invalidate();
+        }
+    }
+
+    /// Linear, see [`Self::set_clear_color`].
+    pub fn clear_color(&self) -> egui::Rgba {
+        self.clear_color
+    }
+
+    /// [`Self::clear_color`] for a render pass, in the same color space the ui is drawn in.
+    fn wgpu_clear_color(&self) -> wgpu::Color {
+        if self.ui.srgb_target() {
+            wgpu_color(self.clear_color)
+        } else {
+            // The ui writes sRGB values as they are, so the background has to as well
+            let [r, g, b, a] = egui::Color32::from(self.clear_color).to_normalized_gamma_f32();
+            wgpu_color(egui::Rgba::from_rgba_premultiplied(r, g, b, a))
+        }
+    }
+
+    /// Reallocate resources that follow the size of the target, after the window was resized.
+    ///
+    /// Call right after [`Graphics::resize`] with the same size, and before the next frame is
+    /// prepared. Anything sized to the target belongs here, rather than in its own resize
+    /// handling. Camera viewports follow their [`Camera`]s instead, see [`Self::prepare`].
+    pub fn resize(&mut self, gfx: &Graphics, width: u32, height: u32) {
+        // The surface was reconfigured, and its contents are gone
+        self.invalidate();
+
+        if self.render_scale != 1.0 {
+            let size_in_pixels = [width, height]
+                .map(|size| ((size as f32 * self.render_scale).round() as u32).max(1));
+            self.supersampler.target(gfx, size_in_pixels);
+        }
+    }
+
+    /// Rebuild pipelines whose WGSL changed on disk. Does nothing in release builds.
+    ///
+    /// Shaders that fail to compile are logged, and the previous pipeline is kept.
+    pub fn reload_shaders(&mut self, gfx: &Graphics) {
+        #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+        {
+            let Some(watcher) = &self.shader_watcher else {
+                return;
+            };
+            for path in watcher.changed() {
+                let source = match std::fs::read_to_string(&path) {
+                    Ok(source) => source,
+                    Err(error) => {
+                        log::error!("Failed to read shader {}: {error}", path.display());
+                        continue;
+                    }
+                };
+                match path.file_name().and_then(|name| name.to_str()) {
+                    Some("sprite.wgsl") => self.sprites.reload_shader(gfx, &source),
+                    Some("gizmo.wgsl") => self.gizmos.reload_shader(gfx, &source),
+                    _ => log::debug!("{} can't be hot-reloaded", path.display()),
+                }
+            }
+        }
+        #[cfg(not(all(debug_assertions, not(target_arch = "wasm32"))))]
+        let _ = gfx;
+    }
+
+    /// Whether a frame of `shapes` on `screen` would look any different from the last one.
+    ///
+    /// Remembers what it was given when it returns `true`, so call once per frame and only skip
+    /// rendering when it returns `false`. Viewports showing a camera are drawn with a fresh paint
+    /// callback every frame, so a ui containing one is never considered unchanged.
+    pub fn needs_redraw(
+        &mut self,
+        screen: UiScreen,
+        shapes: &[egui::epaint::ClippedShape],
+        textures_delta: &egui::TexturesDelta,
+    ) -> bool {
+        let unchanged = self.last_screen == Some(screen)
+            && textures_delta.is_empty()
+            && self.last_shapes.as_slice() == shapes;
+        if !unchanged {
+            self.last_screen = Some(screen);
+            self.last_shapes.clear();
+            self.last_shapes.extend_from_slice(shapes);
+        }
+        !unchanged
+    }
+
+    /// Make the next [`Self::needs_redraw`] return `true`, e.g. after the surface was reconfigured
+    /// and its contents are gone.
+    pub fn invalidate(&mut self) {
+        self.last_screen = None;
+    }
+
+    /// Lines to draw over every camera this frame. Cleared once the frame is rendered.
+    pub fn debug_lines_mut(&mut self) -> &mut DebugLines {
+        &mut self.debug_lines
+    }
+
+    /// Upload an image to be drawn by [`crate::components::Sprite`]s.
+    pub fn load_texture(&mut self, gfx: &Graphics, image: &image::RgbaImage) -> SpriteTexture {
+        self.sprites.load_texture(gfx, image)
+    }
+
     pub fn prepare_ui(
         &mut self,
         gfx: &Graphics,
@@ -91,14 +357,17 @@ impl Renderer {
             self.stacks.remove(&e);
         }
 
-        // Update any existing stacks
+        // Update any existing stacks, at the render scale so viewports are as sharp as the ui
         for (e, camera, _) in world.query_mut::<(Entity, &Camera, &Transform)>() {
+            let physical_size = camera
+                .physical_size()
+                .map(|size| ((size as f32 * self.render_scale).round() as u32).max(1));
             let stack = self.stacks.entry(e).or_insert_with(|| {
-                RenderStack::new(gfx, &mut self.assets, e, camera.physical_size())
+                RenderStack::new(gfx, &mut self.assets, e, physical_size, self.target_format)
             });
 
-            if camera.physical_size() != stack.physical_size {
-                stack.resize(gfx, camera.physical_size());
+            if physical_size != stack.physical_size {
+                stack.resize(gfx, physical_size);
             }
         }
 
@@ -106,6 +375,9 @@ impl Renderer {
         for stack in self.stacks.values_mut() {
             stack.prepare(gfx, world, encoder);
         }
+
+        // Sprites
+        self.sprites.prepare(gfx, world);
     }
 
     pub fn render(
@@ -114,18 +386,50 @@ impl Renderer {
         surface_view: &wgpu::TextureView,
         world: &mut hecs::World,
         encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // Views may reinterpret a texture with or without the sRGB suffix, which isn't visible here
+        debug_assert_eq!(
+            surface_view.texture().format().remove_srgb_suffix(),
+            self.target_format.remove_srgb_suffix(),
+            "The target doesn't match the format the renderer was created for"
+        );
+        if self.render_scale == 1.0 {
+            self.render_to(gfx, surface_view, world, encoder);
+            return;
+        }
+
+        let target = self
+            .supersampler
+            .target(gfx, self.screen.size_in_pixels)
+            .clone();
+        self.render_to(gfx, &target, world, encoder);
+        self.supersampler.resolve(gfx, encoder, surface_view);
+    }
+
+    fn render_to(
+        &mut self,
+        gfx: &Graphics,
+        surface_view: &wgpu::TextureView,
+        world: &mut hecs::World,
+        encoder: &mut wgpu::CommandEncoder,
     ) {
         // **************************************
         // Camera Render Passes
 
         // Render stacks
+        self.line_buffer.upload(gfx, &self.debug_lines);
+        self.debug_lines.clear();
         for stack in self.stacks.values_mut() {
-            stack.render(gfx, world, encoder);
+            stack.render(gfx, world, &self.line_buffer, encoder);
         }
 
         // ******************************************
         // Composite Renderpass
 
+        if let Some(gpu_timer) = &mut self.gpu_timer {
+            gpu_timer.poll(gfx);
+        }
+
         // Final render pass (ui and all composited viewports)
         let mut render_pass = encoder
             .begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -133,21 +437,21 @@ impl Renderer {
                     view: &surface_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.19,
-                            g: 0.24,
-                            b: 0.42,
-                            a: 1.0,
-                        }),
+                        load: wgpu::LoadOp::Clear(self.wgpu_clear_color()),
                         store: wgpu::StoreOp::Store,
                     },
                     depth_slice: None,
                 })],
                 depth_stencil_attachment: None,
+                timestamp_writes: self.gpu_timer.as_ref().and_then(GpuTimer::timestamp_writes),
                 ..Default::default()
             })
             .forget_lifetime();
 
+        // Draw sprites underneath the ui
+        self.sprites
+            .draw(gfx, &mut render_pass, self.screen.size_in_pixels);
+
         // Make sure there is not some mistake
         assert!(
             !self
@@ -172,9 +476,49 @@ impl Renderer {
         self.stacks = resources.stacks;
         // End render pass
         drop(render_pass);
+
+        if let Some(gpu_timer) = &mut self.gpu_timer {
+            gpu_timer.resolve(encoder);
+        }
+    }
+
+    /// Draw `grid` as the ground plane of `camera`'s viewport this frame.
+    ///
+    /// Call between [`Self::prepare`] and [`Self::render`]. The grid is depth tested against
+    /// [`DebugLines`], and against the raymarched scene, so stars hide the grid behind them.
+    pub fn draw_reference_grid(
+        &mut self,
+        gfx: &Graphics,
+        camera: hecs::Entity,
+        grid: &ReferenceGrid,
+    ) {
+        if let Some(stack) = self.stacks.get_mut(&camera) {
+            stack.draw_reference_grid(gfx, grid);
+        }
+    }
+
+    /// Draw the orientation of `camera` in the corner of the screen, on top of everything else,
+    /// with the X, Y and Z axes in the linear `axis_colors`.
+    ///
+    /// A debugging aid, call after [`Self::render`].
+    pub fn draw_gizmos(
+        &mut self,
+        gfx: &Graphics,
+        world: &hecs::World,
+        camera: hecs::Entity,
+        axis_colors: [egui::Rgba; 3],
+        surface_view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        self.gizmos
+            .draw(gfx, world, camera, axis_colors, surface_view, encoder);
     }
 
     pub fn recall(&mut self, gfx: &Graphics, world: &mut hecs::World) {
+        if let Some(gpu_timer) = &mut self.gpu_timer {
+            gpu_timer.map();
+        }
+
         for stack in self.stacks.values_mut() {
             stack.recall(gfx, world);
         }
@@ -0,0 +1,26 @@
+// Colored debug lines, e.g. the camera orientation gizmo.
+
+struct Immediates {
+    clip_from_world: mat4x4<f32>,
+}
+var<immediate> c: Immediates;
+
+struct Vertex {
+    @location(0) position: vec3<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(vertex: Vertex) -> VertexOutput {
+    return VertexOutput(c.clip_from_world * vec4<f32>(vertex.position, 1.0), vertex.color);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
@@ -0,0 +1,78 @@
+// An infinite looking grid on the XZ plane, drawn with a fullscreen triangle.
+
+struct CameraUniform {
+    proj: mat4x4f,
+    view: mat4x4f,
+    inv_proj: mat4x4f,
+    inv_view: mat4x4f,
+}
+
+@group(0)
+@binding(0)
+var<uniform> camera: CameraUniform;
+
+struct GridUniform {
+    minor_color: vec4f,
+    major_color: vec4f,
+    minor_spacing: f32,
+    major_spacing: f32,
+    fade_distance: f32,
+}
+
+@group(1)
+@binding(0)
+var<uniform> grid: GridUniform;
+
+struct FragmentOutput {
+    @location(0) color: vec4f,
+    // Matches the debug lines, see lines.wgsl
+    @builtin(frag_depth) depth: f32,
+}
+
+fn unproject(ndc: vec2f, z: f32) -> vec3f {
+    let world = camera.inv_view * camera.inv_proj * vec4f(ndc, z, 1.0);
+    return world.xyz / world.w;
+}
+
+// How much of a line every `spacing` covers this pixel, antialiased over one pixel.
+fn grid_coverage(position: vec2f, spacing: f32) -> f32 {
+    let coord = position / spacing;
+    let width = fwidth(coord);
+    let distance_to_line = abs(fract(coord - 0.5) - 0.5) / width;
+    return 1.0 - min(min(distance_to_line.x, distance_to_line.y), 1.0);
+}
+
+@fragment
+fn fs_main(@location(0) uv: vec2f) -> FragmentOutput {
+    let ndc = vec2f(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0);
+
+    // Two points along the view ray. Neither depth is at infinity, for both the reversed
+    // infinite perspective projection and the orthographic one.
+    let a = unproject(ndc, 0.25);
+    let b = unproject(ndc, 0.75);
+
+    let eye = camera.inv_view[3].xyz;
+    let forward = -camera.inv_view[2].xyz;
+    var direction = normalize(b - a);
+    if dot(direction, forward) < 0.0 {
+        direction = -direction;
+    }
+
+    // Intersect with the y = 0 plane
+    let t = -a.y / direction.y;
+    let hit = a + t * direction;
+    let view_distance = distance(eye, hit);
+    let in_front = dot(hit - eye, forward) > 0.0 && abs(direction.y) > 1e-6;
+
+    let minor = grid_coverage(hit.xz, grid.minor_spacing);
+    let major = grid_coverage(hit.xz, grid.major_spacing);
+    let fade = 1.0 - smoothstep(0.5 * grid.fade_distance, grid.fade_distance, view_distance);
+
+    // Colors are premultiplied
+    let color = mix(grid.minor_color * minor, grid.major_color, major) * fade;
+    if !in_front || color.a <= 1.0 / 255.0 {
+        discard;
+    }
+
+    return FragmentOutput(color, 1.0 / (1.0 + view_distance));
+}
@@ -0,0 +1,41 @@
+// World space debug lines, drawn over a camera's hdr target.
+
+struct CameraUniform {
+    proj: mat4x4f,
+    view: mat4x4f,
+    inv_proj: mat4x4f,
+    inv_view: mat4x4f,
+}
+
+@group(0)
+@binding(0)
+var<uniform> camera: CameraUniform;
+
+struct Vertex {
+    @location(0) position: vec3<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) view_distance: f32,
+}
+
+@vertex
+fn vs_main(vertex: Vertex) -> VertexOutput {
+    let view_position = camera.view * vec4<f32>(vertex.position, 1.0);
+    return VertexOutput(camera.proj * view_position, vertex.color, length(view_position.xyz));
+}
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    // Closer is larger, independent of the projection, so perspective and orthographic cameras
+    // depth test the same way.
+    @builtin(frag_depth) depth: f32,
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> FragmentOutput {
+    return FragmentOutput(in.color, 1.0 / (1.0 + in.view_distance));
+}
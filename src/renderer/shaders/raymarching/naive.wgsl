@@ -51,6 +51,8 @@ struct FragOutput {
     @location(0) instance_id: u32,
     @location(1) position: vec4f,
     @location(2) direction: vec4f,
+    // Larger is closer, like the overlay's depth, see overlay.rs
+    @location(3) scene_depth: f32,
     @builtin(frag_depth) material_id: f32,
 }
 
@@ -97,13 +99,14 @@ fn ray_marcher(ro: vec3f, rd: vec3f) -> FragOutput {
         // }
         total_distance += info.dist;
         if info.dist < minimum_distance {
-            return FragOutput(info.instance_id, vec4(cur_pos, info.dist), vec4(rd, 0.0), f32(info.material_id) / 256.0);
+            let scene_depth = 1.0 / (1.0 + distance(ro, cur_pos));
+            return FragOutput(info.instance_id, vec4(cur_pos, info.dist), vec4(rd, 0.0), scene_depth, f32(info.material_id) / 256.0);
         } else if info.dist > maximum_distance {
             break;
         }
     }
 
-    return FragOutput(0, vec4(cur_pos, 0.0), vec4(rd, 0.0), f32(background_material_id) / 256.0);
+    return FragOutput(0, vec4(cur_pos, 0.0), vec4(rd, 0.0), 0.0, f32(background_material_id) / 256.0);
     // return FragOutput(0, vec4(cur_pos, 0.0), vec4(rd, 0.0), f32(0.0) / 256.0);
 
     // if hit {
@@ -0,0 +1,11 @@
+// Fills the overlay's depth buffer with the scene's, so the grid and debug lines are hidden
+// behind whatever the raymarcher hit.
+
+@group(0)
+@binding(0)
+var scene_depth: texture_2d<f32>;
+
+@fragment
+fn fs_main(@builtin(position) position: vec4f) -> @builtin(frag_depth) f32 {
+    return textureLoad(scene_depth, vec2u(position.xy), 0).r;
+}
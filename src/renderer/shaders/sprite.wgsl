@@ -0,0 +1,67 @@
+// Screen-space textured quads.
+//
+// Each instance is one sprite, drawn with 6 vertices (two triangles).
+// Positions are in physical pixels, with the origin in the top left corner of the screen.
+
+// Whether the target encodes to sRGB on write, in which case we need to output linear colors.
+override srgb_target: bool = false;
+
+struct Immediates {
+    screen_size: vec2<f32>,
+}
+var<immediate> c: Immediates;
+
+struct Instance {
+    @location(0) position: vec2<f32>,
+    @location(1) size: vec2<f32>,
+    @location(2) tint: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) tint: vec4<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32, instance: Instance) -> VertexOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(1.0, 1.0),
+    );
+    let uv = corners[vertex_index];
+
+    // Sprites are centered on their position
+    let pixel = instance.position + (uv - 0.5) * instance.size;
+    let ndc = vec2<f32>(
+        2.0 * pixel.x / c.screen_size.x - 1.0,
+        1.0 - 2.0 * pixel.y / c.screen_size.y,
+    );
+
+    return VertexOutput(vec4<f32>(ndc, 0.0, 1.0), uv, instance.tint);
+}
+
+@group(0) @binding(0) var sprite_texture: texture_2d<f32>;
+@group(0) @binding(1) var sprite_sampler: sampler;
+
+// 0-1 linear  from  0-1 sRGB gamma
+fn linear_from_gamma_rgb(srgb: vec3<f32>) -> vec3<f32> {
+    let cutoff = srgb < vec3<f32>(0.04045);
+    let lower = srgb / vec3<f32>(12.92);
+    let higher = pow((srgb + vec3<f32>(0.055)) / vec3<f32>(1.055), vec3<f32>(2.4));
+    return select(higher, lower, cutoff);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    var color = textureSample(sprite_texture, sprite_sampler, in.uv) * in.tint;
+    if srgb_target {
+        color = vec4<f32>(linear_from_gamma_rgb(color.rgb), color.a);
+    }
+    // Output premultiplied alpha, to match the ui blending
+    return vec4<f32>(color.rgb * color.a, color.a);
+}
@@ -0,0 +1,292 @@
+use wgpu::util::DeviceExt as _;
+
+use crate::components::Sprite;
+use crate::math::{GlobalTransform, Transform};
+use crate::renderer::{Graphics, Immediates};
+
+/// Handle to a texture loaded with [`crate::renderer::Renderer::load_texture`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpriteTexture(u32);
+
+/// Per-sprite vertex data.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct SpriteInstance {
+    position: glam::Vec2,
+    size: glam::Vec2,
+    tint: glam::Vec4,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct SpriteImmediates {
+    screen_size: glam::Vec2,
+}
+
+/// A contiguous range of instances that share a texture.
+struct SpriteBatch {
+    texture: SpriteTexture,
+    instances: std::ops::Range<u32>,
+}
+
+struct LoadedTexture {
+    _texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Draws [`Sprite`]s as screen-space quads, batched by texture.
+pub struct SpriteRenderer {
+    immediates: Immediates<SpriteImmediates>,
+    pipeline: wgpu::RenderPipeline,
+    /// Kept around to rebuild the pipeline when the shader is reloaded
+    layout: wgpu::PipelineLayout,
+    output_color_format: wgpu::TextureFormat,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+
+    textures: Vec<LoadedTexture>,
+
+    instance_buffer: wgpu::Buffer,
+    instances_host: Vec<SpriteInstance>,
+    batches: Vec<SpriteBatch>,
+}
+
+impl SpriteRenderer {
+    const INSTANCE_BUFFER_START_CAPACITY: u64 = (size_of::<SpriteInstance>() * 64) as u64;
+
+    pub fn new(gfx: &Graphics, output_color_format: wgpu::TextureFormat) -> Self {
+        let immediates = Immediates::new(gfx, 1);
+        let shader =
+            immediates.create_shader_module(gfx, "sprite", include_str!("shaders/sprite.wgsl"));
+
+        let bind_group_layout = gfx
+            .start_bind_group_layout()
+            .label("sprite_bind_group_layout")
+            .texture_filterable_binding(
+                0,
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::TextureViewDimension::D2,
+                false,
+            )
+            .sampler_binding(
+                1,
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::SamplerBindingType::Filtering,
+            )
+            .finish();
+
+        let layout = immediates.create_pipeline_layout(gfx, &[&bind_group_layout]);
+
+        let pipeline = create_pipeline(gfx, &layout, &shader, output_color_format);
+
+        let sampler = gfx.device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("sprite_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::MipmapFilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Self {
+            immediates,
+            pipeline,
+            layout,
+            output_color_format,
+            bind_group_layout,
+            sampler,
+            textures: Vec::new(),
+            instance_buffer: create_instance_buffer(gfx, Self::INSTANCE_BUFFER_START_CAPACITY),
+            instances_host: Vec::new(),
+            batches: Vec::new(),
+        }
+    }
+
+    /// Rebuild the pipeline from new shader `source`, keeping the old one if it doesn't compile.
+    #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+    pub fn reload_shader(&mut self, gfx: &Graphics, source: &str) {
+        let pipeline = super::hot_reload::try_create(gfx, "sprite shader", || {
+            let shader = self.immediates.create_shader_module(gfx, "sprite", source);
+            create_pipeline(gfx, &self.layout, &shader, self.output_color_format)
+        });
+        if let Some(pipeline) = pipeline {
+            self.pipeline = pipeline;
+        }
+    }
+
+    /// Upload an image to the gpu, so it can be used by [`Sprite`]s.
+    ///
+    /// Like egui, sprites are blended in gamma space, so the image is uploaded as is.
+    pub fn load_texture(&mut self, gfx: &Graphics, image: &image::RgbaImage) -> SpriteTexture {
+        let texture = gfx.device.create_texture_with_data(
+            &gfx.queue,
+            &wgpu::TextureDescriptor {
+                label: Some("sprite_texture"),
+                size: wgpu::Extent3d {
+                    width: image.width(),
+                    height: image.height(),
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            image.as_raw(),
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let bind_group = gfx
+            .start_bind_group(&self.bind_group_layout)
+            .label("sprite_bind_group")
+            .texture_view_binding(0, &view)
+            .sampler_binding(1, &self.sampler)
+            .finish();
+
+        let handle = SpriteTexture(self.textures.len() as u32);
+        self.textures.push(LoadedTexture {
+            _texture: texture,
+            bind_group,
+        });
+        handle
+    }
+
+    pub fn prepare(&mut self, gfx: &Graphics, world: &mut hecs::World) {
+        let mut sprites: Vec<(f32, SpriteTexture, SpriteInstance)> = world
+            .query_mut::<(&Sprite, &Transform, Option<&GlobalTransform>)>()
+            .into_iter()
+            .filter(|(sprite, _, _)| (sprite.texture.0 as usize) < self.textures.len())
+            .map(|(sprite, transform, global)| {
+                let position = global.map_or(transform.translation, GlobalTransform::translation);
+                let instance = SpriteInstance {
+                    position: position.truncate(),
+                    size: sprite.size,
+                    tint: sprite.tint,
+                };
+                (sprite.z, sprite.texture, instance)
+            })
+            .collect();
+
+        // Draw back to front, grouping by texture within the same depth to minimize binds.
+        sprites.sort_by(|(za, ta, _), (zb, tb, _)| za.total_cmp(zb).then(ta.cmp(tb)));
+
+        self.instances_host.clear();
+        self.batches.clear();
+        for (_, texture, instance) in sprites {
+            let index = self.instances_host.len() as u32;
+            match self.batches.last_mut() {
+                Some(batch) if batch.texture == texture => batch.instances.end = index + 1,
+                _ => self.batches.push(SpriteBatch {
+                    texture,
+                    instances: index..index + 1,
+                }),
+            }
+            self.instances_host.push(instance);
+        }
+
+        if self.instances_host.is_empty() {
+            return;
+        }
+
+        let required = (self.instances_host.len() * size_of::<SpriteInstance>()) as u64;
+        if self.instance_buffer.size() < required {
+            let capacity = (self.instance_buffer.size() * 2).max(required);
+            self.instance_buffer = create_instance_buffer(gfx, capacity);
+        }
+
+        gfx.queue.write_buffer(
+            &self.instance_buffer,
+            0,
+            bytemuck::cast_slice(&self.instances_host),
+        );
+    }
+
+    pub fn draw(
+        &self,
+        gfx: &Graphics,
+        render_pass: &mut wgpu::RenderPass<'_>,
+        screen_size: [u32; 2],
+    ) {
+        if self.batches.is_empty() {
+            return;
+        }
+
+        render_pass.set_pipeline(&self.pipeline);
+        self.immediates.set(
+            gfx,
+            render_pass,
+            &SpriteImmediates {
+                screen_size: glam::UVec2::from_array(screen_size).as_vec2(),
+            },
+        );
+        render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+
+        for batch in &self.batches {
+            let texture = &self.textures[batch.texture.0 as usize];
+            render_pass.set_bind_group(0, &texture.bind_group, &[]);
+            render_pass.draw(0..6, batch.instances.clone());
+        }
+    }
+}
+
+fn create_pipeline(
+    gfx: &Graphics,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    output_color_format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    gfx
+        .device
+        .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("sprite_pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<SpriteInstance>() as u64,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    // 0: vec2 position
+                    // 1: vec2 size
+                    // 2: vec4 tint
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x4],
+                }],
+            },
+            primitive: gfx.fullscreen_primitive_state(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions {
+                    constants: &[(
+                        "srgb_target",
+                        f64::from(u8::from(output_color_format.is_srgb())),
+                    )],
+                    ..Default::default()
+                },
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: output_color_format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview_mask: None,
+            cache: None,
+        })
+}
+
+fn create_instance_buffer(gfx: &Graphics, size: u64) -> wgpu::Buffer {
+    gfx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("sprite_instance_buffer"),
+        size,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
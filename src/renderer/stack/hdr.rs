@@ -10,13 +10,24 @@ pub struct HdrTextures {
     depth: wgpu::Texture,
     depth_view: wgpu::TextureView,
 
+    /// Closeness of the scene to the camera, `1 / (1 + distance)` or zero where nothing was hit.
+    ///
+    /// The depth attachment holds material ids, so the raymarcher writes this alongside.
+    scene_depth: wgpu::Texture,
+    scene_depth_view: wgpu::TextureView,
+
     bind_group_layout: wgpu::BindGroupLayout,
     color_bind_group: wgpu::BindGroup,
 
+    scene_depth_bind_group_layout: wgpu::BindGroupLayout,
+    scene_depth_bind_group: wgpu::BindGroup,
+
     physical_size: [u32; 2],
 }
 
 impl HdrTextures {
+    pub const SCENE_DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Float;
+
     pub fn new(gfx: &Graphics, physical_size: [u32; 2]) -> Self {
         let sampler = create_hdr_sampler(gfx);
 
@@ -26,6 +37,9 @@ impl HdrTextures {
         let depth = create_hdr_depth(gfx, physical_size[0], physical_size[1]);
         let depth_view = create_hdr_depth_view(&depth);
 
+        let scene_depth = create_scene_depth(gfx, physical_size[0], physical_size[1]);
+        let scene_depth_view = scene_depth.create_view(&wgpu::TextureViewDescriptor::default());
+
         let bind_group_layout = gfx
             .start_bind_group_layout()
             .label("hdr_bind_group_layout")
@@ -44,14 +58,35 @@ impl HdrTextures {
 
         let bind_group = create_hdr_bind_group(gfx, &bind_group_layout, &color_view, &sampler);
 
+        let scene_depth_bind_group_layout = gfx
+            .start_bind_group_layout()
+            .label("scene_depth_bind_group_layout")
+            .texture_binding(
+                0,
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::TextureSampleType::Float { filterable: false },
+                wgpu::TextureViewDimension::D2,
+                false,
+            )
+            .finish();
+        let scene_depth_bind_group = gfx
+            .start_bind_group(&scene_depth_bind_group_layout)
+            .label("scene_depth_bind_group")
+            .texture_view_binding(0, &scene_depth_view)
+            .finish();
+
         Self {
             sampler,
             color,
             color_view,
             depth,
             depth_view,
+            scene_depth,
+            scene_depth_view,
             bind_group_layout,
             color_bind_group: bind_group,
+            scene_depth_bind_group_layout,
+            scene_depth_bind_group,
             physical_size,
         }
     }
@@ -80,6 +115,16 @@ impl HdrTextures {
 
         self.depth = create_hdr_depth(gfx, physical_size[0], physical_size[1]);
         self.depth_view = create_hdr_depth_view(&self.depth);
+
+        self.scene_depth = create_scene_depth(gfx, physical_size[0], physical_size[1]);
+        self.scene_depth_view = self
+            .scene_depth
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        self.scene_depth_bind_group = gfx
+            .start_bind_group(&self.scene_depth_bind_group_layout)
+            .label("scene_depth_bind_group")
+            .texture_view_binding(0, &self.scene_depth_view)
+            .finish();
     }
 
     pub fn bind_group_layout(&self) -> &wgpu::BindGroupLayout {
@@ -98,6 +143,18 @@ impl HdrTextures {
         &self.depth_view
     }
 
+    pub fn scene_depth_view(&self) -> &wgpu::TextureView {
+        &self.scene_depth_view
+    }
+
+    pub fn scene_depth_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.scene_depth_bind_group_layout
+    }
+
+    pub fn scene_depth_bind_group(&self) -> &wgpu::BindGroup {
+        &self.scene_depth_bind_group
+    }
+
     /// Size of hdr render attachments.
     pub fn _physical_size(&self) -> [u32; 2] {
         self.physical_size
@@ -140,6 +197,23 @@ fn create_hdr_depth(gfx: &Graphics, width: u32, height: u32) -> wgpu::Texture {
     texture
 }
 
+fn create_scene_depth(gfx: &Graphics, width: u32, height: u32) -> wgpu::Texture {
+    gfx.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("scene_depth"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: HdrTextures::SCENE_DEPTH_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    })
+}
+
 fn create_hdr_color_view(texture: &wgpu::Texture) -> wgpu::TextureView {
     texture.create_view(&wgpu::TextureViewDescriptor {
         label: Some("hdr_color_attachment_view"),
@@ -1,9 +1,11 @@
 use crate::components::SchwarschildBlackHole;
+use crate::renderer::grid::ReferenceGrid;
+use crate::renderer::lines::LineBuffer;
 use crate::renderer::stack::standard::StandardPipeline;
-use crate::renderer::{Assets, Graphics};
+use crate::renderer::{Assets, Graphics, Immediates};
 use crate::{
     components::{Camera, Global, Pipeline},
-    math::Transform,
+    math::{GlobalTransform, Transform},
 };
 use wesl::include_wesl;
 use wgpu::{BufferDescriptor, BufferUsages, RenderPass, ShaderStages};
@@ -11,10 +13,12 @@ use wgpu::{BufferDescriptor, BufferUsages, RenderPass, ShaderStages};
 mod bloom;
 mod composite;
 mod hdr;
+mod overlay;
 mod standard;
 
 use bloom::BloomPipeline;
 use hdr::HdrTextures;
+use overlay::OverlayPipeline;
 
 #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 #[repr(C)]
@@ -90,12 +94,14 @@ impl FrameData {
         {
             let camera_handle = camera;
             let camera = world.get::<&Camera>(camera_handle).unwrap();
-            let transform = world.get::<&Transform>(camera_handle).unwrap();
+            let inv_view = match world.get::<&GlobalTransform>(camera_handle) {
+                Ok(global) => global.to_matrix(),
+                Err(_) => world.get::<&Transform>(camera_handle).unwrap().to_matrix(),
+            };
 
             let proj = camera.projection.get_clip_from_view();
             let inv_proj = proj.inverse();
-            let view = transform.to_matrix().inverse();
-            let inv_view = transform.to_matrix();
+            let view = inv_view.inverse();
 
             let mut uniform = staging_belt.write_buffer(
                 encoder,
@@ -183,8 +189,12 @@ pub struct RenderStack {
     // Fractal Pipelines
     fractal: [wgpu::RenderPipeline; 2],
     // Schwarschild Pipeline
+    schwarschild_immediates: Immediates<SchwarschildImmediates>,
     schwarschild: wgpu::RenderPipeline,
 
+    // Debug lines and reference grid
+    overlay_pipeline: OverlayPipeline,
+
     // Bloom Manager
     bloom_pipeline: BloomPipeline,
 
@@ -200,6 +210,7 @@ impl RenderStack {
         assets: &mut Assets,
         camera: hecs::Entity,
         physical_size: [u32; 2],
+        target_format: wgpu::TextureFormat,
     ) -> Self {
         // Hdr Textures
         let hdr = HdrTextures::new(gfx, physical_size);
@@ -226,6 +237,8 @@ impl RenderStack {
 
         // Standard Pipeline
         let standard_pipeline = StandardPipeline::new(gfx, assets, &frame_data, physical_size);
+        // Overlay Pipeline
+        let overlay_pipeline = OverlayPipeline::new(gfx, &frame_data, &hdr, physical_size);
         // Bloom Pipeline
         let bloom_pipeline = BloomPipeline::new(gfx, physical_size);
 
@@ -238,8 +251,12 @@ impl RenderStack {
         let composite = gfx
             .start_post_processing_pipeline(&composite_shader)
             .label("composite")
-            .color_format(gfx.surface_format)
+            .color_format(target_format)
             .layout(&composite_layout)
+            .add_constant(
+                "output_linear",
+                f64::from(u8::from(target_format.is_srgb())),
+            )
             .finish();
 
         // *****************************
@@ -267,10 +284,14 @@ impl RenderStack {
         // ******************************
         // Schwarschild Pipeline
 
-        let schwarschild_shader =
-            gfx.create_shader_module("schwarschild", include_str!("../shaders/schwarschild.wgsl"));
-        let schwarschild_layout = gfx.create_pipeline_layout(
-            32,
+        let schwarschild_immediates = Immediates::new(gfx, 2);
+        let schwarschild_shader = schwarschild_immediates.create_shader_module(
+            gfx,
+            "schwarschild",
+            include_str!("../shaders/schwarschild.wgsl"),
+        );
+        let schwarschild_layout = schwarschild_immediates.create_pipeline_layout(
+            gfx,
             &[
                 frame_data.bind_group_layout(),
                 standard_pipeline.skybox_bind_group_layout(),
@@ -301,11 +322,13 @@ impl RenderStack {
             composite_bind_group,
 
             standard_pipeline,
+            overlay_pipeline,
             bloom_pipeline,
 
             composite,
 
             fractal: [mandlebulb, sierpinski],
+            schwarschild_immediates,
             schwarschild,
 
             staging_belt,
@@ -322,6 +345,7 @@ impl RenderStack {
     pub fn resize(&mut self, gfx: &Graphics, physical_size: [u32; 2]) {
         self.hdr.resize(gfx, physical_size);
         self.standard_pipeline.resize(gfx, physical_size);
+        self.overlay_pipeline.resize(gfx, physical_size);
     }
 
     pub fn prepare(
@@ -376,6 +400,7 @@ impl RenderStack {
         &mut self,
         gfx: &Graphics,
         world: &mut hecs::World,
+        lines: &LineBuffer,
         encoder: &mut wgpu::CommandEncoder,
     ) {
         let global_default = Global::default();
@@ -385,6 +410,8 @@ impl RenderStack {
             .next()
             .unwrap_or(&global_default);
 
+        // Only the raymarcher writes the scene depth
+        let scene_depth = global.pipeline == Pipeline::Standard;
         match global.pipeline {
             Pipeline::Mandlebulb | Pipeline::Sierpinski => {
                 let fractal_index = match global.pipeline {
@@ -446,9 +473,10 @@ impl RenderStack {
                 render_pass.set_pipeline(&self.schwarschild);
                 render_pass.set_bind_group(0, self.frame_data.bind_group(), &[]);
                 render_pass.set_bind_group(1, self.standard_pipeline.milkyway_bind_group(), &[]);
-                render_pass.set_immediates(
-                    0,
-                    bytemuck::cast_slice(&[SchwarschildImmediates {
+                self.schwarschild_immediates.set(
+                    gfx,
+                    &mut render_pass,
+                    &SchwarschildImmediates {
                         mass: schwarschild.mass,
                         tolerance: 1.0e-3,
                         safety_factor: 0.9,
@@ -457,7 +485,7 @@ impl RenderStack {
                         max_adaptive_iter: 100,
                         max_steps: 500,
                         max_time: 100.0,
-                    }]),
+                    },
                 );
                 render_pass.draw(0..3, 0..1);
             }
@@ -467,9 +495,17 @@ impl RenderStack {
             }
         }
 
+        self.overlay_pipeline
+            .render(&self.hdr, &self.frame_data, lines, scene_depth, encoder);
+
         self.bloom_pipeline.render(gfx, world, &self.hdr, encoder);
     }
 
+    /// Draw `grid` over the scene the next time this stack is rendered.
+    pub fn draw_reference_grid(&mut self, gfx: &Graphics, grid: &ReferenceGrid) {
+        self.overlay_pipeline.draw_reference_grid(gfx, grid);
+    }
+
     pub fn recall(&mut self, _gfx: &Graphics, _world: &mut hecs::World) {
         self.staging_belt.recall();
     }
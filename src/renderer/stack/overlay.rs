@@ -0,0 +1,275 @@
+use wgpu::ShaderStages;
+
+use crate::renderer::Graphics;
+use crate::renderer::grid::{GridUniform, ReferenceGrid};
+use crate::renderer::lines::LineBuffer;
+
+use super::FrameData;
+use super::hdr::HdrTextures;
+
+/// Debug lines and the reference grid, drawn over the finished scene, before bloom and compositing.
+///
+/// Has its own depth buffer, since the scene's holds material ids. Depth is stored as
+/// `1 / (1 + distance)` from the camera, so larger is closer. It starts out as the
+/// [`HdrTextures`]' scene depth when the scene wrote one, so scene geometry hides the overlay.
+#[derive(Debug)]
+pub struct OverlayPipeline {
+    depth: wgpu::Texture,
+    depth_view: wgpu::TextureView,
+
+    /// Copies the scene depth into `depth`
+    scene_depth: wgpu::RenderPipeline,
+
+    lines: wgpu::RenderPipeline,
+
+    grid: wgpu::RenderPipeline,
+    grid_buffer: wgpu::Buffer,
+    grid_bind_group: wgpu::BindGroup,
+    /// Whether the grid was asked for this frame
+    draw_grid: bool,
+}
+
+impl OverlayPipeline {
+    pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+    pub fn new(
+        gfx: &Graphics,
+        frame_data: &FrameData,
+        hdr: &HdrTextures,
+        physical_size: [u32; 2],
+    ) -> Self {
+        let (depth, depth_view) = create_overlay_depth(gfx, physical_size);
+
+        let scene_depth_shader =
+            gfx.create_shader_module("scene_depth", include_str!("../shaders/scene_depth.wgsl"));
+        let scene_depth_layout =
+            gfx.create_pipeline_layout(0, &[hdr.scene_depth_bind_group_layout()]);
+        let scene_depth = gfx
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("scene_depth_pipeline"),
+                layout: Some(&scene_depth_layout),
+                vertex: gfx.fullscreen_vertex_state(),
+                primitive: gfx.fullscreen_primitive_state(),
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: Self::DEPTH_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Always,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &scene_depth_shader,
+                    entry_point: Some("fs_main"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    // Only depth is written
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: gfx.hdr_format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::empty(),
+                    })],
+                }),
+                multiview_mask: None,
+                cache: None,
+            });
+
+        let shader = gfx.create_shader_module("lines", include_str!("../shaders/lines.wgsl"));
+        let layout = gfx.create_pipeline_layout(0, &[frame_data.bind_group_layout()]);
+        let lines = gfx
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("debug_lines_pipeline"),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    buffers: &[LineBuffer::vertex_layout()],
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::LineList,
+                    ..gfx.fullscreen_primitive_state()
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: Self::DEPTH_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Greater,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: gfx.hdr_format,
+                        blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview_mask: None,
+                cache: None,
+            });
+
+        let grid_buffer = gfx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("grid_buffer"),
+            size: size_of::<GridUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let grid_bind_group_layout = gfx
+            .start_bind_group_layout()
+            .label("grid_bind_group_layout")
+            .uniform_binding(0, ShaderStages::FRAGMENT)
+            .finish();
+        let grid_bind_group = gfx
+            .start_bind_group(&grid_bind_group_layout)
+            .buffer_binding(0, &grid_buffer, 0, None)
+            .finish();
+
+        let grid_shader = gfx.create_shader_module("grid", include_str!("../shaders/grid.wgsl"));
+        let grid_layout = gfx.create_pipeline_layout(
+            0,
+            &[frame_data.bind_group_layout(), &grid_bind_group_layout],
+        );
+        let grid = gfx
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("grid_pipeline"),
+                layout: Some(&grid_layout),
+                vertex: gfx.fullscreen_vertex_state(),
+                primitive: gfx.fullscreen_primitive_state(),
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: Self::DEPTH_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Greater,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &grid_shader,
+                    entry_point: Some("fs_main"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: gfx.hdr_format,
+                        blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview_mask: None,
+                cache: None,
+            });
+
+        Self {
+            depth,
+            depth_view,
+            scene_depth,
+            lines,
+            grid,
+            grid_buffer,
+            grid_bind_group,
+            draw_grid: false,
+        }
+    }
+
+    pub fn resize(&mut self, gfx: &Graphics, physical_size: [u32; 2]) {
+        if [self.depth.width(), self.depth.height()] == physical_size {
+            return;
+        }
+        (self.depth, self.depth_view) = create_overlay_depth(gfx, physical_size);
+    }
+
+    /// Draw `grid` the next time this stack is rendered.
+    pub fn draw_reference_grid(&mut self, gfx: &Graphics, grid: &ReferenceGrid) {
+        gfx.queue.write_buffer(
+            &self.grid_buffer,
+            0,
+            bytemuck::cast_slice(&[GridUniform::from(grid)]),
+        );
+        self.draw_grid = true;
+    }
+
+    /// Draw the grid and lines over `hdr`. Set `scene_depth` when the scene wrote the
+    /// [`HdrTextures`]' scene depth this frame, otherwise nothing hides the overlay.
+    pub fn render(
+        &mut self,
+        hdr: &HdrTextures,
+        frame_data: &FrameData,
+        lines: &LineBuffer,
+        scene_depth: bool,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let draw_grid = std::mem::take(&mut self.draw_grid);
+        let lines = lines.vertices();
+        if !draw_grid && lines.is_none() {
+            return;
+        }
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("overlay_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: hdr.color_view(),
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0.0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                stencil_ops: None,
+            }),
+            ..Default::default()
+        });
+
+        if scene_depth {
+            render_pass.set_pipeline(&self.scene_depth);
+            render_pass.set_bind_group(0, hdr.scene_depth_bind_group(), &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        render_pass.set_bind_group(0, frame_data.bind_group(), &[]);
+
+        if draw_grid {
+            render_pass.set_pipeline(&self.grid);
+            render_pass.set_bind_group(1, &self.grid_bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        if let Some((vertices, vertex_count)) = lines {
+            render_pass.set_pipeline(&self.lines);
+            render_pass.set_vertex_buffer(0, vertices);
+            render_pass.draw(0..vertex_count, 0..1);
+        }
+    }
+}
+
+fn create_overlay_depth(
+    gfx: &Graphics,
+    physical_size: [u32; 2],
+) -> (wgpu::Texture, wgpu::TextureView) {
+    let depth = gfx.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("overlay_depth_attachment"),
+        size: wgpu::Extent3d {
+            width: physical_size[0].max(1),
+            height: physical_size[1].max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: OverlayPipeline::DEPTH_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let view = depth.create_view(&wgpu::TextureViewDescriptor::default());
+    (depth, view)
+}
@@ -2,9 +2,9 @@ use image::{GenericImageView, imageops::sample_bilinear};
 
 use crate::{
     components::Star,
-    math::Transform,
+    math::{GlobalTransform, Transform},
     renderer::{
-        Assets, Graphics,
+        Assets, Graphics, Immediates,
         stack::{FrameData, hdr::HdrTextures},
     },
 };
@@ -199,6 +199,7 @@ pub struct StandardPipeline {
     spheres_bind_group_layout: wgpu::BindGroupLayout,
     spheres_bind_group: wgpu::BindGroup,
     // Naive Raymarching Pipeline
+    naive_immediates: Immediates<NaiveImmediates>,
     naive_pipeline: wgpu::RenderPipeline,
 
     // Star material
@@ -249,10 +250,14 @@ impl StandardPipeline {
             .buffer_binding(0, &spheres_buffer, 0, None)
             .finish();
 
-        let naive_shader =
-            gfx.create_shader_module("naive", include_str!("../shaders/raymarching/naive.wgsl"));
-        let naive_layout = gfx.create_pipeline_layout(
-            size_of::<NaiveImmediates>() as u32,
+        let naive_immediates = Immediates::new(gfx, 2);
+        let naive_shader = naive_immediates.create_shader_module(
+            gfx,
+            "naive",
+            include_str!("../shaders/raymarching/naive.wgsl"),
+        );
+        let naive_layout = naive_immediates.create_pipeline_layout(
+            gfx,
             &[frame.bind_group_layout(), &spheres_bind_group_layout],
         );
 
@@ -295,6 +300,11 @@ impl StandardPipeline {
                             blend: None,
                             write_mask: wgpu::ColorWrites::ALL,
                         }),
+                        Some(wgpu::ColorTargetState {
+                            format: HdrTextures::SCENE_DEPTH_FORMAT,
+                            blend: None,
+                            write_mask: wgpu::ColorWrites::ALL,
+                        }),
                     ],
                 }),
                 multiview_mask: None,
@@ -529,6 +539,7 @@ impl StandardPipeline {
             spheres_buffer,
             spheres_bind_group_layout,
             spheres_bind_group,
+            naive_immediates,
             naive_pipeline,
 
             star_black_body_lookup,
@@ -582,7 +593,11 @@ impl StandardPipeline {
         self.stars_host.clear();
         self.stars_host.reserve(num_stars);
 
-        for (transform, star) in world.query_mut::<(&Transform, &Star)>().into_iter() {
+        for (transform, global, star) in world
+            .query_mut::<(&Transform, Option<&GlobalTransform>, &Star)>()
+            .into_iter()
+        {
+            let transform = global.map_or(*transform, GlobalTransform::compute_transform);
             let instance_id = self.stars_host.len() as u32;
 
             let temp = star.temperature;
@@ -685,7 +700,7 @@ impl StandardPipeline {
 
     pub fn render(
         &mut self,
-        _gfx: &Graphics,
+        gfx: &Graphics,
         _world: &mut hecs::World,
         hdr: &HdrTextures,
         frame: &FrameData,
@@ -736,6 +751,16 @@ impl StandardPipeline {
                     },
                     depth_slice: None,
                 }),
+                // Read by the overlay, which is hidden behind whatever was hit
+                Some(wgpu::RenderPassColorAttachment {
+                    view: hdr.scene_depth_view(),
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                }),
             ],
             depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
                 view: hdr.depth_view(),
@@ -748,11 +773,12 @@ impl StandardPipeline {
             ..Default::default()
         });
         render_pass.set_pipeline(&self.naive_pipeline);
-        render_pass.set_immediates(
-            0,
-            bytemuck::cast_slice(&[NaiveImmediates {
+        self.naive_immediates.set(
+            gfx,
+            &mut render_pass,
+            &NaiveImmediates {
                 num_stars: self.spheres_host.len() as u32,
-            }]),
+            },
         );
         render_pass.set_bind_group(0, frame.bind_group(), &[]);
         render_pass.set_bind_group(1, &self.spheres_bind_group, &[]);
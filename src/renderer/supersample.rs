@@ -0,0 +1,77 @@
+use super::Graphics;
+
+/// An offscreen color target at a multiple of the surface size, filtered down onto the surface.
+///
+/// Rendering at twice the size and averaging back down antialiases everything drawn, including
+/// the ui and camera viewports.
+///
+/// At most twice the size, see [`super::RENDER_SCALE_RANGE`].
+pub struct Supersampler {
+    format: wgpu::TextureFormat,
+    blitter: wgpu::util::TextureBlitter,
+    target: Option<(wgpu::Texture, wgpu::TextureView)>,
+}
+
+impl Supersampler {
+    pub fn new(gfx: &Graphics, format: wgpu::TextureFormat) -> Self {
+        // Linear filtering averages each 2×2 block when downsampling by exactly two
+        let blitter = wgpu::util::TextureBlitterBuilder::new(&gfx.device, format)
+            .sample_type(wgpu::FilterMode::Linear)
+            .build();
+
+        Self {
+            format,
+            blitter,
+            target: None,
+        }
+    }
+
+    /// The view to render into, (re)allocated to `size_in_pixels` if needed.
+    pub fn target(&mut self, gfx: &Graphics, size_in_pixels: [u32; 2]) -> &wgpu::TextureView {
+        let [width, height] =
+            size_in_pixels.map(|size| size.clamp(1, gfx.device.limits().max_texture_dimension_2d));
+
+        let outdated = self
+            .target
+            .as_ref()
+            .is_none_or(|(texture, _)| texture.width() != width || texture.height() != height);
+        if outdated {
+            let texture = gfx.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("supersample_target"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.target = Some((texture, view));
+        }
+
+        &self.target.as_ref().expect("allocated above").1
+    }
+
+    /// Filter the target down onto `output`.
+    pub fn resolve(
+        &self,
+        gfx: &Graphics,
+        encoder: &mut wgpu::CommandEncoder,
+        output: &wgpu::TextureView,
+    ) {
+        if let Some((_, view)) = &self.target {
+            self.blitter.copy(&gfx.device, encoder, view, output);
+        }
+    }
+
+    /// Free the target while not supersampling.
+    pub fn release(&mut self) {
+        self.target = None;
+    }
+}
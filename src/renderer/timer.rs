@@ -0,0 +1,141 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::Graphics;
+
+/// Size of the two `u64` timestamps, at the start and end of the pass.
+const TIMESTAMPS_SIZE: u64 = 2 * std::mem::size_of::<u64>() as u64;
+
+enum Readback {
+    /// The readback buffer is free, so this frame's timestamps can be copied into it.
+    Idle,
+    /// Timestamps were copied into the readback buffer, and it can be mapped once submitted.
+    Copied,
+    /// Waiting for the readback buffer to be mapped, which fills in the result.
+    Mapping(Arc<Mutex<Option<Result<(), wgpu::BufferAsyncError>>>>),
+}
+
+/// Measures how long a single render pass takes on the gpu, using timestamp queries.
+///
+/// Results arrive a few frames late, so while one is being read back, passes aren't timed.
+pub struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    /// Nanoseconds per timestamp tick.
+    period: f32,
+    readback: Readback,
+    last: Option<Duration>,
+}
+
+impl GpuTimer {
+    /// Returns `None` if the device doesn't support timestamp queries.
+    pub fn new(gfx: &Graphics) -> Option<Self> {
+        if !gfx.supports_timestamps {
+            return None;
+        }
+
+        let query_set = gfx.device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("gpu_timer"),
+            ty: wgpu::QueryType::Timestamp,
+            count: 2,
+        });
+        let resolve_buffer = gfx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gpu_timer_resolve"),
+            size: TIMESTAMPS_SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = gfx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gpu_timer_readback"),
+            size: TIMESTAMPS_SIZE,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            period: gfx.queue.get_timestamp_period(),
+            readback: Readback::Idle,
+            last: None,
+        })
+    }
+
+    /// How long the last timed pass took.
+    pub fn last(&self) -> Option<Duration> {
+        self.last
+    }
+
+    /// Timestamp writes for the pass to time, or `None` if a previous result is still in flight.
+    pub fn timestamp_writes(&self) -> Option<wgpu::RenderPassTimestampWrites<'_>> {
+        matches!(self.readback, Readback::Idle).then_some(wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(0),
+            end_of_pass_write_index: Some(1),
+        })
+    }
+
+    /// Copy the timestamps written by the pass somewhere they can be read. Call after the pass.
+    pub fn resolve(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if !matches!(self.readback, Readback::Idle) {
+            return;
+        }
+
+        encoder.resolve_query_set(&self.query_set, 0..2, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &self.readback_buffer,
+            0,
+            TIMESTAMPS_SIZE,
+        );
+        self.readback = Readback::Copied;
+    }
+
+    /// Start reading back the timestamps. Call after the commands have been submitted.
+    pub fn map(&mut self) {
+        if !matches!(self.readback, Readback::Copied) {
+            return;
+        }
+
+        let mapped = Arc::new(Mutex::new(None));
+        let result_slot = mapped.clone();
+        self.readback_buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                *result_slot.lock().unwrap() = Some(result);
+            });
+        self.readback = Readback::Mapping(mapped);
+    }
+
+    /// Pick up the result of an earlier [`Self::map`], if it is ready.
+    pub fn poll(&mut self, gfx: &Graphics) {
+        let Readback::Mapping(mapped) = &self.readback else {
+            return;
+        };
+
+        let _ = gfx.device.poll(wgpu::PollType::Poll);
+        let Some(result) = mapped.lock().unwrap().take() else {
+            return;
+        };
+        if let Err(error) = result {
+            // Nothing was mapped, so the buffer is free to time another pass
+            log::warn!("Failed to read back gpu timestamps: {error}");
+            self.readback = Readback::Idle;
+            return;
+        }
+
+        {
+            let bytes = self.readback_buffer.slice(..).get_mapped_range();
+            let timestamps: &[u64] = bytemuck::cast_slice(&bytes);
+            let ticks = timestamps[1].saturating_sub(timestamps[0]);
+            self.last = Some(Duration::from_nanos(
+                (ticks as f64 * self.period as f64) as u64,
+            ));
+        }
+        self.readback_buffer.unmap();
+        self.readback = Readback::Idle;
+    }
+}
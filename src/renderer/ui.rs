@@ -9,7 +9,7 @@ use wgpu::util::DeviceExt;
 
 use crate::renderer::Graphics;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 /// Information about the screen used for rendering.
 pub struct UiScreen {
     /// Size of the window in physical pixels.
@@ -20,6 +20,19 @@ pub struct UiScreen {
 }
 
 impl UiScreen {
+    /// The same screen at `render_scale` times the resolution, see [`super::Renderer::set_render_scale`].
+    ///
+    /// Only the size is scaled. `pixels_per_point` should be the one egui returned after
+    /// [`scale_ui_input`], which includes the scale already.
+    pub fn scaled(self, render_scale: f32) -> Self {
+        Self {
+            size_in_pixels: self
+                .size_in_pixels
+                .map(|size| ((size as f32 * render_scale).round() as u32).max(1)),
+            ..self
+        }
+    }
+
     /// size in "logical" points
     fn screen_size_in_points(&self) -> [f32; 2] {
         [
@@ -29,6 +42,21 @@ impl UiScreen {
     }
 }
 
+/// Have egui lay out `raw_input`'s frame for `render_scale` times its native resolution, see
+/// [`super::Renderer::set_render_scale`]. Call before running the ui.
+///
+/// The ui keeps its size in points, but text is rasterized and shapes are rounded to the pixels
+/// they are drawn to, so they stay sharp instead of being scaled. Tessellate with the
+/// `pixels_per_point` of the output, which includes the scale.
+pub fn scale_ui_input(raw_input: &mut egui::RawInput, render_scale: f32) {
+    let viewport = raw_input
+        .viewports
+        .entry(raw_input.viewport_id)
+        .or_default();
+    let native_pixels_per_point = viewport.native_pixels_per_point.unwrap_or(1.0);
+    viewport.native_pixels_per_point = Some(native_pixels_per_point * render_scale);
+}
+
 /// Uniform buffer used when rendering.
 #[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
 #[repr(C)]
@@ -60,6 +88,10 @@ struct Texture {
 pub struct UiRenderer {
     pipeline: wgpu::RenderPipeline,
 
+    /// Whether the pipeline converts egui's gamma-space colors to linear before writing them,
+    /// so that the hardware sRGB encoding of the target brings them back to gamma space.
+    srgb_target: bool,
+
     index_buffer: SlicedBuffer,
     vertex_buffer: SlicedBuffer,
 
@@ -80,7 +112,23 @@ pub struct UiRenderer {
 }
 
 impl UiRenderer {
+    /// Creates a renderer for the given target format, inferring the gamma handling from it.
     pub fn new(device: &wgpu::Device, output_color_format: wgpu::TextureFormat) -> Self {
+        Self::new_with_srgb_target(device, output_color_format, output_color_format.is_srgb())
+    }
+
+    /// Creates a renderer with explicit gamma handling.
+    ///
+    /// egui colors are in gamma space. If `srgb_target` is `true` they are converted to linear
+    /// in the shader, which is correct when the hardware encodes to sRGB on write (i.e. the
+    /// view being rendered into has an `*Srgb` format). Otherwise they are written as is.
+    ///
+    /// Getting this wrong makes the ui look either washed out or too dark.
+    pub fn new_with_srgb_target(
+        device: &wgpu::Device,
+        output_color_format: wgpu::TextureFormat,
+        srgb_target: bool,
+    ) -> Self {
         let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("ui"),
             source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("shaders/ui.wgsl"))),
@@ -200,7 +248,7 @@ impl UiRenderer {
 
                 fragment: Some(wgpu::FragmentState {
                     module: &module,
-                    entry_point: Some(if output_color_format.is_srgb() {
+                    entry_point: Some(if srgb_target {
                         log::warn!("Detected a linear (sRGBA aware) framebuffer {output_color_format:?}. egui prefers Rgba8Unorm or Bgra8Unorm");
                         "fs_main_linear_framebuffer"
                     } else {
@@ -236,6 +284,7 @@ impl UiRenderer {
 
         Self {
             pipeline,
+            srgb_target,
             vertex_buffer: SlicedBuffer {
                 buffer: create_vertex_buffer(device, VERTEX_BUFFER_START_CAPACITY),
                 slices: Vec::with_capacity(64),
@@ -258,6 +307,12 @@ impl UiRenderer {
         }
     }
 
+    /// Whether the ui is being rendered into an sRGB target, see [`Self::new_with_srgb_target`].
+    #[inline]
+    pub fn srgb_target(&self) -> bool {
+        self.srgb_target
+    }
+
     /// Executes the egui renderer onto an existing wgpu renderpass.
     ///
     /// Note that the lifetime of `render_pass` is `'static` which requires a call to [`wgpu::RenderPass::forget_lifetime`].
@@ -514,11 +569,33 @@ impl UiRenderer {
     }
 
     pub fn free_texture(&mut self, id: &epaint::TextureId) {
-        if let Some(texture) = self.textures.remove(id).and_then(|t| t.texture) {
+        let Some(texture) = self.textures.remove(id) else {
+            // Freeing twice, or freeing something never allocated, means the bookkeeping is off
+            log::warn!("Tried to free ui texture {id:?}, which is not allocated");
+            return;
+        };
+        if let Some(texture) = texture.texture {
             texture.destroy();
         }
     }
 
+    /// How many textures are registered, and how many bytes the ones owned by the renderer take.
+    ///
+    /// Textures registered with [`Self::_register_native_texture`] are counted, but not their bytes.
+    pub fn texture_stats(&self) -> (usize, u64) {
+        let bytes = self
+            .textures
+            .values()
+            .filter_map(|texture| texture.texture.as_ref())
+            .map(|texture| {
+                let size = texture.size();
+                let texel_size = texture.format().block_copy_size(None).unwrap_or(4);
+                size.width as u64 * size.height as u64 * texel_size as u64
+            })
+            .sum();
+        (self.textures.len(), bytes)
+    }
+
     /// Registers a [`wgpu::Texture`] with a [`epaint::TextureId`].
     ///
     /// This enables the application to reference the texture inside an image ui element.
@@ -992,3 +1069,122 @@ pub trait UiCallbackTrait: Send + Sync {
         callback_resources: &UiCallbackResources,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::{FrameCapture, GraphicsConfig};
+    use crate::toolkit::HasDesignTokens as _;
+
+    const SIZE: u32 = 16;
+
+    /// Fill a `format` target with `color` through the ui renderer, and read back the pixel in
+    /// the middle.
+    fn render_fill(
+        gfx: &Graphics,
+        format: wgpu::TextureFormat,
+        srgb_target: bool,
+        color: egui::Color32,
+    ) -> [u8; 4] {
+        let ctx = egui::Context::default();
+        let screen_rect =
+            egui::Rect::from_min_size(egui::Pos2::ZERO, egui::Vec2::splat(SIZE as f32));
+        let output = ctx.run_ui(
+            egui::RawInput {
+                screen_rect: Some(screen_rect),
+                ..Default::default()
+            },
+            |ui| {
+                let layer = egui::LayerId::new(egui::Order::Foreground, egui::Id::new("fill"));
+                ui.ctx()
+                    .layer_painter(layer)
+                    .rect_filled(screen_rect, 0.0, color);
+            },
+        );
+        let screen = UiScreen {
+            size_in_pixels: [SIZE; 2],
+            pixels_per_point: 1.0,
+        };
+        let paint_jobs = ctx.tessellate(output.shapes, screen.pixels_per_point);
+
+        let mut renderer = UiRenderer::new_with_srgb_target(&gfx.device, format, srgb_target);
+        for (id, image_delta) in &output.textures_delta.set {
+            renderer.update_texture(gfx, *id, image_delta);
+        }
+        let target = gfx.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("ui_test_target"),
+            size: wgpu::Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = gfx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        renderer.update_buffers(gfx, &mut encoder, &paint_jobs, &screen);
+        {
+            let mut render_pass = encoder
+                .begin_render_pass(&wgpu::RenderPassDescriptor {
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: wgpu::StoreOp::Store,
+                        },
+                        depth_slice: None,
+                    })],
+                    ..Default::default()
+                })
+                .forget_lifetime();
+            renderer.draw(&mut render_pass, &paint_jobs, screen);
+        }
+        let capture = FrameCapture::new(gfx, &mut encoder, &target);
+        gfx.queue.submit(std::iter::once(encoder.finish()));
+
+        let image = capture.read_blocking(gfx).unwrap();
+        image.get_pixel(SIZE / 2, SIZE / 2).0
+    }
+
+    /// Token colors are sRGB, and should come out as the same bytes whether the target encodes to
+    /// sRGB on write or not, as long as the renderer is told which it is.
+    #[test]
+    #[ignore = "needs a gpu adapter, run with --ignored"]
+    fn token_color_matches_on_srgb_and_linear_targets() {
+        let config = GraphicsConfig {
+            allow_fallback_adapter: true,
+            ..Default::default()
+        };
+        let gfx = Graphics::new_headless_blocking(SIZE, SIZE, config)
+            .expect("creating a headless gpu context");
+
+        let color = egui::Context::default().tokens().top_bar_color;
+        for format in [
+            wgpu::TextureFormat::Rgba8Unorm,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+        ] {
+            let pixel = render_fill(&gfx, format, format.is_srgb(), color);
+            // Dithering may nudge a channel by one step
+            for (actual, expected) in pixel.iter().zip(color.to_array()) {
+                assert!(
+                    actual.abs_diff(expected) <= 2,
+                    "{format:?}: rendered {pixel:?}, expected {:?}",
+                    color.to_array()
+                );
+            }
+        }
+
+        // Telling the renderer the wrong thing washes the color out
+        let washed_out = render_fill(&gfx, wgpu::TextureFormat::Rgba8UnormSrgb, false, color);
+        assert!(washed_out[0] > color.r() + 2);
+    }
+}
@@ -5,7 +5,10 @@ use egui::{ecolor, epaint::ViewportInPixels};
 use peroxide::fuga::{ODEIntegrator, ODEProblem, RKF45};
 
 use crate::{
-    components::{Camera, Global, PanOrbitController, Pipeline, SchwarschildBlackHole, Star},
+    components::{
+        Camera, Global, PanOrbitController, PickBounds, Pipeline, SchwarschildBlackHole, Star,
+        WorldLabel, draw_world_labels, pick, select,
+    },
     math::{Projection, Transform},
     renderer::{DrawCameraCallback, UiCallback},
 };
@@ -38,6 +41,10 @@ impl FractalState {
         }
     }
 
+    pub fn camera(&self) -> hecs::Entity {
+        self.camera
+    }
+
     pub fn start(&mut self, world: &mut hecs::World) {
         self.camera = world.spawn(
             hecs::EntityBuilder::new()
@@ -151,6 +158,8 @@ impl FractalState {
                             rect,
                             DrawCameraCallback::new(self.camera),
                         ));
+
+                        draw_world_labels(world, ui, self.camera, rect);
                     });
             });
     }
@@ -184,6 +193,10 @@ impl SpaceState {
         }
     }
 
+    pub fn camera(&self) -> hecs::Entity {
+        self.camera
+    }
+
     pub fn start(&mut self, world: &mut hecs::World) {
         self.camera = world.spawn(
             hecs::EntityBuilder::new()
@@ -200,6 +213,8 @@ impl SpaceState {
             hecs::EntityBuilder::new()
                 .add(Transform::from_xyz(-3.0, 0.0, 0.0).with_uniform_scale(1.2))
                 .add(Star::sun().with_temperature(5800.0))
+                .add(WorldLabel::new("Sun").with_position(glam::Vec3::new(0.0, 1.1, 0.0)))
+                .add(PickBounds::sphere(1.0))
                 .add(StarPhysics {
                     velocity: glam::vec3(0.0, 0.0, -2.3 / 2.0),
                     mass: 40.0,
@@ -350,6 +365,14 @@ impl SpaceState {
                             response.request_focus();
                         }
 
+                        // Select whatever is under the cursor, or nothing when clicking empty space
+                        if response.clicked()
+                            && let Some(pointer) = response.interact_pointer_pos()
+                        {
+                            let picked = pick(world, self.camera, rect, pointer);
+                            select(world, picked);
+                        }
+
                         // Update Camera
                         let mut camera = world.get::<&mut Camera>(self.camera).unwrap();
                         camera.update(viewport.width_px as u32, viewport.height_px as u32);
@@ -364,6 +387,8 @@ impl SpaceState {
                             rect,
                             DrawCameraCallback::new(self.camera),
                         ));
+
+                        draw_world_labels(world, ui, self.camera, rect);
                     });
             });
     }
@@ -940,6 +965,10 @@ impl BlackHole3dState {
         }
     }
 
+    pub fn camera(&self) -> hecs::Entity {
+        self.camera
+    }
+
     pub fn start(&mut self, world: &mut hecs::World) {
         self.camera = world.spawn(
             hecs::EntityBuilder::new()
@@ -1025,6 +1054,8 @@ impl BlackHole3dState {
                             rect,
                             DrawCameraCallback::new(self.camera),
                         ));
+
+                        draw_world_labels(world, ui, self.camera, rect);
                     });
             });
     }
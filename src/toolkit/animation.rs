@@ -0,0 +1,205 @@
+//! Values that move toward a target over a few frames, for polishing widgets.
+
+use std::ops::{Add, Mul, Sub};
+
+/// Something [`AnimatedValue`] can move smoothly.
+pub trait Animatable:
+    Copy + PartialEq + Add<Output = Self> + Sub<Output = Self> + Mul<f32, Output = Self>
+{
+    const ZERO: Self;
+
+    /// How far apart two values are, to tell when a spring has settled.
+    fn distance(self, other: Self) -> f32;
+}
+
+impl Animatable for f32 {
+    const ZERO: Self = 0.0;
+
+    fn distance(self, other: Self) -> f32 {
+        (self - other).abs()
+    }
+}
+
+impl Animatable for egui::Vec2 {
+    const ZERO: Self = egui::Vec2::ZERO;
+
+    fn distance(self, other: Self) -> f32 {
+        (self - other).length()
+    }
+}
+
+impl Animatable for glam::Vec2 {
+    const ZERO: Self = glam::Vec2::ZERO;
+
+    fn distance(self, other: Self) -> f32 {
+        self.distance(other)
+    }
+}
+
+impl Animatable for glam::Vec3 {
+    const ZERO: Self = glam::Vec3::ZERO;
+
+    fn distance(self, other: Self) -> f32 {
+        self.distance(other)
+    }
+}
+
+/// How an [`AnimatedValue`] gets to its target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    /// At a constant speed, over the duration.
+    Linear,
+    /// Fast at first and slowing down at the end, over the duration.
+    EaseOut,
+    /// Pulled toward the target by a damped spring, ignoring the duration.
+    ///
+    /// Retargeting keeps the current velocity, so interrupted animations stay smooth. Low damping
+    /// overshoots and bounces.
+    Spring { stiffness: f32, damping: f32 },
+}
+
+impl Easing {
+    /// A quick spring that barely overshoots.
+    pub fn spring() -> Self {
+        Self::Spring {
+            stiffness: 170.0,
+            damping: 26.0,
+        }
+    }
+}
+
+/// Distance below which a spring snaps to its target.
+const SETTLE_DISTANCE: f32 = 1e-3;
+/// Springs are integrated in steps no longer than this, so slow frames don't blow them up.
+const MAX_SPRING_STEP: f32 = 1.0 / 120.0;
+
+/// A value that moves toward its target each frame.
+///
+/// ```ignore
+/// let mut open = AnimatedValue::new(0.0).with_easing(Easing::spring());
+/// open.set_target(1.0);
+/// let width = 200.0 * open.update_with_ctx(ui.ctx());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct AnimatedValue<T> {
+    value: T,
+    target: T,
+    /// Where the current animation started, for the eased curves.
+    start: T,
+    velocity: T,
+    elapsed: f32,
+    duration: f32,
+    easing: Easing,
+}
+
+impl<T: Animatable> AnimatedValue<T> {
+    /// A value resting at `value`, easing out over a fifth of a second when retargeted.
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            target: value,
+            start: value,
+            velocity: T::ZERO,
+            elapsed: 0.0,
+            duration: 0.2,
+            easing: Easing::EaseOut,
+        }
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// How many seconds linear and ease-out animations take.
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration.max(0.0);
+        self
+    }
+
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    pub fn target(&self) -> T {
+        self.target
+    }
+
+    /// Start moving toward `target`, from wherever the value is now.
+    pub fn set_target(&mut self, target: T) {
+        if target == self.target {
+            return;
+        }
+        self.target = target;
+        self.start = self.value;
+        self.elapsed = 0.0;
+    }
+
+    /// Jump to `value` without animating.
+    pub fn set_immediately(&mut self, value: T) {
+        *self = Self {
+            easing: self.easing,
+            duration: self.duration,
+            ..Self::new(value)
+        };
+    }
+
+    /// Whether the value hasn't reached its target yet, so another frame should be drawn.
+    pub fn is_animating(&self) -> bool {
+        self.value != self.target
+    }
+
+    /// Move `delta_time` seconds further along, and return the new value.
+    pub fn update(&mut self, delta_time: f32) -> T {
+        if !self.is_animating() {
+            return self.value;
+        }
+
+        match self.easing {
+            Easing::Linear | Easing::EaseOut => {
+                self.elapsed += delta_time;
+                let t = if self.duration > 0.0 {
+                    (self.elapsed / self.duration).min(1.0)
+                } else {
+                    1.0
+                };
+                let t = match self.easing {
+                    Easing::EaseOut => egui::emath::easing::cubic_out(t),
+                    _ => t,
+                };
+                self.value = if t >= 1.0 {
+                    self.target
+                } else {
+                    self.start + (self.target - self.start) * t
+                };
+            }
+            Easing::Spring { stiffness, damping } => {
+                let mut remaining = delta_time.max(0.0);
+                while remaining > 0.0 {
+                    let step = remaining.min(MAX_SPRING_STEP);
+                    remaining -= step;
+                    let acceleration =
+                        (self.target - self.value) * stiffness - self.velocity * damping;
+                    self.velocity = self.velocity + acceleration * step;
+                    self.value = self.value + self.velocity * step;
+                }
+                if self.value.distance(self.target) < SETTLE_DISTANCE
+                    && self.velocity.distance(T::ZERO) < SETTLE_DISTANCE
+                {
+                    self.value = self.target;
+                    self.velocity = T::ZERO;
+                }
+            }
+        }
+        self.value
+    }
+
+    /// [`Self::update`] by egui's frame time, requesting another frame while still animating.
+    pub fn update_with_ctx(&mut self, ctx: &egui::Context) -> T {
+        let value = self.update(ctx.input(|input| input.stable_dt));
+        if self.is_animating() {
+            ctx.request_repaint();
+        }
+        value
+    }
+}
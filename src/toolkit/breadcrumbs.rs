@@ -0,0 +1,102 @@
+//! A breadcrumb bar, for showing where in a hierarchy the user is, like a scene tree or a path.
+
+use super::{HasDesignTokens as _, UiExt as _, icons};
+
+/// Show `segments` from the root to the current item, separated by chevrons.
+///
+/// Returns the index of the segment that was clicked, so the caller can navigate up to it. When
+/// the bar doesn't fit, the middle segments collapse into a `…` menu, keeping the root and as many
+/// segments at the end as there is room for.
+pub fn ui(ui: &mut egui::Ui, segments: &[&str]) -> Option<usize> {
+    let tokens = ui.tokens();
+    let last = segments.len().checked_sub(1)?;
+
+    let item_spacing = ui.spacing().item_spacing.x;
+    let separator_width = tokens.small_icon_size.x + item_spacing;
+    let segment_widths: Vec<f32> = segments
+        .iter()
+        .map(|segment| {
+            let galley = egui::WidgetText::from(*segment).into_galley(
+                ui,
+                Some(egui::TextWrapMode::Extend),
+                f32::INFINITY,
+                egui::TextStyle::Button,
+            );
+            galley.size().x + 2.0 * ui.spacing().button_padding.x + item_spacing
+        })
+        .collect();
+
+    // Keep the root and the current segment, and as many before the current one as fit
+    let available = ui.available_width();
+    let total: f32 = segment_widths.iter().sum::<f32>() + last as f32 * separator_width;
+    let hidden = if total <= available || segments.len() <= 2 {
+        0..0
+    } else {
+        let ellipsis_width = ui.spacing().interact_size.x + separator_width;
+        let mut width = segment_widths[0] + segment_widths[last] + separator_width + ellipsis_width;
+        let mut first_shown = last;
+        while first_shown > 1
+            && width + segment_widths[first_shown - 1] + separator_width <= available
+        {
+            first_shown -= 1;
+            width += segment_widths[first_shown] + separator_width;
+        }
+        1..first_shown
+    };
+
+    let mut clicked = None;
+    ui.horizontal_directed(|ui| {
+        let separator = |ui: &mut egui::Ui| {
+            ui.add(
+                icons::BREADCRUMBS_SEPARATOR
+                    .as_image()
+                    .tint(tokens.text_subdued)
+                    .fit_to_exact_size(tokens.small_icon_size),
+            );
+        };
+
+        for (index, segment) in segments.iter().enumerate() {
+            if hidden.contains(&index) {
+                if index == hidden.start {
+                    ui.menu_button("…", |ui| {
+                        for hidden_index in hidden.clone() {
+                            if ui.button(segments[hidden_index]).clicked() {
+                                clicked = Some(hidden_index);
+                                ui.close();
+                            }
+                        }
+                    })
+                    .response
+                    .on_hover_text("Show hidden segments");
+                    separator(ui);
+                }
+                continue;
+            }
+
+            let is_current = index == last;
+            let color = if is_current {
+                tokens.text_strong
+            } else {
+                tokens.text_subdued
+            };
+            let response = ui.add(
+                egui::Button::new(egui::RichText::new(*segment).color(color))
+                    .frame(false)
+                    .sense(if is_current {
+                        egui::Sense::hover()
+                    } else {
+                        egui::Sense::click()
+                    }),
+            );
+            if ui.focusable(response).clicked() {
+                clicked = Some(index);
+            }
+
+            if !is_current {
+                separator(ui);
+            }
+        }
+    });
+
+    clicked
+}
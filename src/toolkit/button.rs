@@ -1,4 +1,5 @@
-use super::{DesignTokens, UiExt as _};
+use super::animation::{AnimatedValue, Easing};
+use super::{DesignTokens, HasDesignTokens as _, TooltipExt as _, UiExt as _};
 use egui::style::WidgetVisuals;
 use egui::{CornerRadius, IntoAtoms, Style};
 
@@ -160,6 +161,78 @@ impl egui::Widget for Button<'_> {
         self.variant.apply(style, tokens);
         let response = ui.add(self.inner);
         ui.set_style(previous_style);
-        response
+        ui.focusable(response)
     }
 }
+
+/// How long [`hold_to_confirm`] has to be held, in seconds.
+pub const HOLD_TO_CONFIRM_SECONDS: f32 = 1.0;
+
+/// How far along a [`hold_to_confirm`] button is.
+#[derive(Clone, Copy, Debug)]
+struct HoldState {
+    /// From zero to one while held, back to zero when let go early.
+    progress: AnimatedValue<f32>,
+    /// Whether this hold already confirmed, so it only fires once.
+    fired: bool,
+}
+
+/// A button for destructive actions, that has to be held down for [`HOLD_TO_CONFIRM_SECONDS`]
+/// rather than clicked.
+///
+/// Fills with the error color while held, with the mouse or with Space or Enter while focused.
+/// Letting go early drains it again. Returns `true` on the frame it filled up, once per hold.
+pub fn hold_to_confirm(ui: &mut egui::Ui, label: impl Into<egui::WidgetText>) -> bool {
+    let tokens = ui.tokens();
+    let response = ui
+        .add(Button::new(label.into()).outlined())
+        .on_tooltip_text("Hold to confirm");
+
+    let id = response.id.with("hold_to_confirm");
+    let mut state = ui
+        .data(|data| data.get_temp::<HoldState>(id))
+        .unwrap_or(HoldState {
+            progress: AnimatedValue::new(0.0),
+            fired: false,
+        });
+
+    let held = response.is_pointer_button_down_on()
+        || (response.has_focus()
+            && ui.input(|i| i.key_down(egui::Key::Space) || i.key_down(egui::Key::Enter)));
+    let current = state.progress.value();
+    if held && state.progress.target() != 1.0 && !state.fired {
+        // Fill at a steady rate from wherever it is
+        state.progress = AnimatedValue::new(current)
+            .with_easing(Easing::Linear)
+            .with_duration(HOLD_TO_CONFIRM_SECONDS * (1.0 - current));
+        state.progress.set_target(1.0);
+    } else if !held && state.progress.target() != 0.0 {
+        state.progress = AnimatedValue::new(current);
+        state.progress.set_target(0.0);
+    }
+    if !held {
+        state.fired = false;
+    }
+
+    let progress = state.progress.update_with_ctx(ui.ctx());
+    let confirmed = held && !state.fired && progress >= 1.0;
+    if confirmed {
+        state.fired = true;
+    }
+
+    if progress > 0.0 && ui.is_rect_visible(response.rect) {
+        let rect = response.rect;
+        let fill = egui::Rect::from_min_max(
+            rect.min,
+            egui::pos2(rect.left() + progress * rect.width(), rect.max.y),
+        );
+        ui.painter().with_clip_rect(fill).rect_filled(
+            rect,
+            ui.visuals().widgets.inactive.corner_radius,
+            tokens.error_fg_color.gamma_multiply(0.35),
+        );
+    }
+
+    ui.data_mut(|data| data.insert_temp(id, state));
+    confirmed
+}
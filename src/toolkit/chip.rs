@@ -0,0 +1,106 @@
+//! Small rounded labels for tags and filters, see [`super::UiExt::chip`].
+
+use super::icons;
+use super::{HasDesignTokens as _, TooltipExt as _, UiExt as _};
+
+/// Space between the edge of a chip and its text.
+const PADDING: egui::Vec2 = egui::vec2(6.0, 2.0);
+
+/// What happened to a chip this frame.
+pub struct ChipResponse {
+    /// The whole chip, close button included.
+    pub response: egui::Response,
+    /// The close button was clicked, or Delete pressed while the chip had focus. The chip is
+    /// still shown until the caller stops adding it.
+    pub closed: bool,
+}
+
+impl ChipResponse {
+    pub fn clicked(&self) -> bool {
+        self.response.clicked() && !self.closed
+    }
+}
+
+pub(super) fn show(
+    ui: &mut egui::Ui,
+    text: &str,
+    color: Option<egui::Color32>,
+    closable: bool,
+) -> ChipResponse {
+    let tokens = ui.tokens();
+    let color = color.unwrap_or_else(|| tokens.color_for_hash(egui::Id::new(text).value()));
+    let galley = ui.text_galley(
+        text,
+        egui::TextStyle::Body.resolve(ui.style()),
+        f32::INFINITY,
+    );
+
+    let icon_size = tokens.small_icon_size;
+    let close_width = if closable {
+        tokens.text_to_icon_padding() + icon_size.x
+    } else {
+        0.0
+    };
+    let size = egui::vec2(
+        galley.size().x + close_width,
+        galley.size().y.max(icon_size.y),
+    ) + 2.0 * PADDING;
+    let (rect, mut response) = ui.allocate_exact_size(size, egui::Sense::click());
+    response
+        .widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, ui.is_enabled(), text));
+
+    let mut closed = false;
+    let mut close_hovered = false;
+    let close_rect = egui::Rect::from_min_size(
+        egui::pos2(
+            rect.right() - PADDING.x - icon_size.x,
+            rect.center().y - 0.5 * icon_size.y,
+        ),
+        icon_size,
+    );
+    if closable {
+        let close = ui
+            .interact(close_rect, response.id.with("close"), egui::Sense::click())
+            .on_tooltip_text("Remove");
+        close_hovered = close.hovered();
+        closed = close.clicked()
+            || (response.has_focus()
+                && ui.input_mut(|input| {
+                    input.consume_key(egui::Modifiers::NONE, egui::Key::Delete)
+                        || input.consume_key(egui::Modifiers::NONE, egui::Key::Backspace)
+                }));
+        response |= close;
+    }
+
+    if ui.is_rect_visible(rect) {
+        let fill = color.gamma_multiply(if response.hovered() { 0.35 } else { 0.2 });
+        ui.painter().rect(
+            rect,
+            tokens.small_corner_radius(),
+            fill,
+            egui::Stroke::new(1.0, color),
+            egui::StrokeKind::Inside,
+        );
+        let text_pos = egui::pos2(
+            rect.left() + PADDING.x,
+            rect.center().y - 0.5 * galley.size().y,
+        );
+        ui.painter().galley(text_pos, galley, tokens.text_strong);
+        if closable {
+            let tint = if close_hovered {
+                tokens.text_strong
+            } else {
+                tokens.text_subdued
+            };
+            icons::CLOSE_SMALL
+                .as_image()
+                .tint(tint)
+                .paint_at(ui, close_rect);
+        }
+    }
+
+    ChipResponse {
+        response: ui.focusable(response),
+        closed,
+    }
+}
@@ -232,3 +232,170 @@ impl ColorToken {
         Self { hue, scale: shade }
     }
 }
+
+/// A stable color for any hash, e.g. an entity or type id.
+///
+/// Hues are picked by stepping around the OKLCh color wheel by the golden ratio, so consecutive
+/// ids end up far apart. `lightness` and `chroma` are in OKLCh units, see
+/// [`super::DesignTokens::color_for_hash`] for the values tuned to each theme.
+pub fn color_for_hash(hash: u64, lightness: f32, chroma: f32) -> egui::Color32 {
+    // Fibonacci hashing: multiplying by 2^64 / φ is the same as stepping the hue by 1 / φ turns.
+    const GOLDEN_RATIO_STEP: u64 = 0x9E37_79B9_7F4A_7C15;
+    let turns = (hash.wrapping_mul(GOLDEN_RATIO_STEP) >> 40) as f32 / (1u64 << 24) as f32;
+
+    let hue = turns * std::f32::consts::TAU;
+    oklab_to_color32(lightness, chroma * hue.cos(), chroma * hue.sin())
+}
+
+/// Blend from `a` to `b` in OKLab, where equal steps of `t` look like equal changes in color.
+///
+/// Unlike a lerp of the sRGB values, midpoints don't turn muddy or dark, e.g. between
+/// complementary hues. Alpha is blended linearly, and `t` is clamped to `0..=1`, with NaN as `0`.
+pub fn lerp_oklab(a: egui::Color32, b: egui::Color32, t: f32) -> egui::Color32 {
+    let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+    let [from, to] = [a, b].map(color32_to_oklab);
+    let [l, a_, b_] = std::array::from_fn(|i| egui::lerp(from[i]..=to[i], t));
+    let alpha = egui::lerp(f32::from(a.a())..=f32::from(b.a()), t).round() as u8;
+
+    let [r, g, b, _] = oklab_to_color32(l, a_, b_).to_array();
+    egui::Color32::from_rgba_unmultiplied(r, g, b, alpha)
+}
+
+/// The color at `t` along a gradient through `stops`, blending neighbouring stops with
+/// [`lerp_oklab`].
+///
+/// `stops` are `(position, color)` pairs sorted by position. Before the first stop and after the
+/// last, their colors are held, and NaN is before the first. Transparent if there are no stops.
+pub fn gradient(stops: &[(f32, egui::Color32)], t: f32) -> egui::Color32 {
+    let (Some(&(first_at, first)), Some(&(last_at, last))) = (stops.first(), stops.last()) else {
+        return egui::Color32::TRANSPARENT;
+    };
+    // NaN compares false with every stop, and would be looked up before the first
+    if t.is_nan() || t <= first_at {
+        return first;
+    }
+    if t >= last_at {
+        return last;
+    }
+
+    let next = stops.partition_point(|&(at, _)| at <= t);
+    let (from_at, from) = stops[next - 1];
+    let (to_at, to) = stops[next];
+    let span = to_at - from_at;
+    if span <= 0.0 {
+        return to;
+    }
+    lerp_oklab(from, to, (t - from_at) / span)
+}
+
+/// Convert from sRGB to OKLab, ignoring alpha.
+///
+/// See <https://bottosson.github.io/posts/oklab/>.
+fn color32_to_oklab(color: egui::Color32) -> [f32; 3] {
+    let [r, g, b, _] = color.to_srgba_unmultiplied();
+    let [r, g, b] = [r, g, b].map(egui::ecolor::linear_f32_from_gamma_u8);
+
+    let l = 0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b;
+    let m = 0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b;
+    let s = 0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b;
+
+    let (l_, m_, s_) = (l.cbrt(), m.cbrt(), s.cbrt());
+
+    [
+        0.210_454_26 * l_ + 0.793_617_8 * m_ - 0.004_072_047 * s_,
+        1.977_998_5 * l_ - 2.428_592_2 * m_ + 0.450_593_7 * s_,
+        0.025_904_037 * l_ + 0.782_771_77 * m_ - 0.808_675_77 * s_,
+    ]
+}
+
+/// Convert from OKLab to (clamped) sRGB.
+///
+/// See <https://bottosson.github.io/posts/oklab/>.
+fn oklab_to_color32(l: f32, a: f32, b: f32) -> egui::Color32 {
+    let l_ = l + 0.396_337_78 * a + 0.215_803_76 * b;
+    let m_ = l - 0.105_561_346 * a - 0.063_854_17 * b;
+    let s_ = l - 0.089_484_18 * a - 1.291_485_5 * b;
+
+    let (l, m, s) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+
+    let r = 4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s;
+    let g = -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s;
+    let b = -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s;
+
+    egui::Rgba::from_rgb(r.clamp(0.0, 1.0), g.clamp(0.0, 1.0), b.clamp(0.0, 1.0)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::Color32;
+
+    const RED: Color32 = Color32::from_rgb(255, 0, 0);
+    const BLUE: Color32 = Color32::from_rgb(0, 0, 255);
+
+    #[test]
+    fn lerp_oklab_endpoints_and_clamping() {
+        for color in [Color32::BLACK, Color32::WHITE, RED, BLUE] {
+            // Through OKLab and back, without drifting
+            assert_eq!(lerp_oklab(color, color, 0.5), color);
+        }
+        assert_eq!(lerp_oklab(RED, BLUE, 0.0), RED);
+        assert_eq!(lerp_oklab(RED, BLUE, 1.0), BLUE);
+        assert_eq!(lerp_oklab(RED, BLUE, -1.0), RED);
+        assert_eq!(lerp_oklab(RED, BLUE, 2.0), BLUE);
+        assert_eq!(lerp_oklab(RED, BLUE, f32::NEG_INFINITY), RED);
+        assert_eq!(lerp_oklab(RED, BLUE, f32::INFINITY), BLUE);
+        assert_eq!(lerp_oklab(RED, BLUE, f32::NAN), RED);
+    }
+
+    #[test]
+    fn lerp_oklab_midpoint() {
+        // Perceptually halfway, so lighter than the sRGB average of black and white
+        let gray = lerp_oklab(Color32::BLACK, Color32::WHITE, 0.5);
+        assert_eq!(gray.r(), gray.g());
+        assert_eq!(gray.g(), gray.b());
+        assert!((gray.r() as i32 - 99).abs() <= 1, "{gray:?}");
+
+        // Lighter than the sRGB midpoint of red and blue, #800080, but still purple
+        let purple = lerp_oklab(RED, BLUE, 0.5);
+        assert!(
+            purple.r() > 128 && purple.b() > 128 && purple.g() < purple.r().min(purple.b()),
+            "{purple:?}"
+        );
+
+        let half_transparent = lerp_oklab(Color32::TRANSPARENT, Color32::WHITE, 0.5);
+        assert_eq!(half_transparent.a(), 128);
+    }
+
+    #[test]
+    fn gradient_holds_and_blends_stops() {
+        assert_eq!(gradient(&[], 0.5), Color32::TRANSPARENT);
+        assert_eq!(gradient(&[(0.5, RED)], 0.0), RED);
+        assert_eq!(gradient(&[(0.5, RED)], 1.0), RED);
+
+        let stops = [(0.0, RED), (0.5, Color32::WHITE), (1.0, BLUE)];
+        for (t, color) in [
+            (-1.0, RED),
+            (f32::NEG_INFINITY, RED),
+            (f32::NAN, RED),
+            (0.0, RED),
+            (0.5, Color32::WHITE),
+            (1.0, BLUE),
+            (2.0, BLUE),
+            (f32::INFINITY, BLUE),
+        ] {
+            assert_eq!(gradient(&stops, t), color, "at {t}");
+        }
+        // Each span is blended on its own
+        assert_eq!(gradient(&stops, 0.25), lerp_oklab(RED, Color32::WHITE, 0.5));
+        assert_eq!(
+            gradient(&stops, 0.75),
+            lerp_oklab(Color32::WHITE, BLUE, 0.5)
+        );
+
+        // Two stops at the same position make a hard edge
+        let edge = [(0.0, RED), (0.5, RED), (0.5, BLUE), (1.0, BLUE)];
+        assert_eq!(gradient(&edge, 0.499), RED);
+        assert_eq!(gradient(&edge, 0.5), BLUE);
+    }
+}
@@ -0,0 +1,258 @@
+//! Scientific colormaps, for mapping values to colors in plots, heatmaps and overlays.
+//!
+//! Each is a polynomial fit of the original table. Viridis and magma are accurate to within a few
+//! sRGB steps, turbo drifts by up to about thirty near its ends.
+
+use std::ops::RangeInclusive;
+
+use strum::{EnumIter, IntoStaticStr};
+
+use super::HasDesignTokens as _;
+use crate::misc::format_f64;
+
+/// A colormap to [`Colormap::sample`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, EnumIter, IntoStaticStr)]
+pub enum Colormap {
+    /// Perceptually uniform from dark blue through green to yellow, and readable when printed in
+    /// grayscale or seen with color blindness. A safe default.
+    #[default]
+    Viridis,
+    /// Perceptually uniform from black through purple to pale yellow, for data on a dark background.
+    Magma,
+    /// A brighter rainbow, for telling apart small differences at the cost of uniformity.
+    Turbo,
+}
+
+impl Colormap {
+    /// The color at `t`, clamped to `0..=1`.
+    pub fn sample(self, t: f32) -> egui::Color32 {
+        match self {
+            Self::Viridis => viridis(t),
+            Self::Magma => magma(t),
+            Self::Turbo => turbo(t),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        self.into()
+    }
+}
+
+/// Viridis at `t`, clamped to `0..=1`.
+pub fn viridis(t: f32) -> egui::Color32 {
+    // Fit by Matt Zucker, see <https://www.shadertoy.com/view/WlfXRN>
+    polynomial(
+        t,
+        &[
+            [0.277_727_33, 0.005_407_344_5, 0.334_099_8],
+            [0.105_093_04, 1.404_613_5, 1.384_590_2],
+            [-0.330_861_83, 0.214_847_56, 0.095_095_16],
+            [-4.634_230_6, -5.799_101, -19.332_441],
+            [6.228_27, 14.179_933, 56.690_55],
+            [4.776_385, -13.745_145, -65.353_03],
+            [-5.435_456, 4.645_852_6, 26.312_435],
+        ],
+    )
+}
+
+/// Magma at `t`, clamped to `0..=1`.
+pub fn magma(t: f32) -> egui::Color32 {
+    // Fit by Matt Zucker, see <https://www.shadertoy.com/view/WlfXRN>
+    polynomial(
+        t,
+        &[
+            [-0.002_136_485, -0.000_749_655_05, -0.005_386_127_7],
+            [0.251_660_54, 0.677_523_2, 2.494_026_6],
+            [8.353_717, -3.577_719_5, 0.314_467_9],
+            [-27.668_733, 14.264_731, -13.649_213],
+            [52.176_14, -27.943_607, 12.944_169],
+            [-50.768_524, 29.046_583, 4.234_153],
+            [18.655_706, -11.489_774, -5.601_961_5],
+        ],
+    )
+}
+
+/// Turbo at `t`, clamped to `0..=1`.
+pub fn turbo(t: f32) -> egui::Color32 {
+    // Fit by Ruofei Du, see <https://research.google/blog/turbo-an-improved-rainbow-colormap-for-visualization/>
+    polynomial(
+        t,
+        &[
+            [0.135_721_38, 0.091_402_61, 0.106_673_3],
+            [4.615_392_6, 2.194_188_4, 12.641_946],
+            [-42.660_323, 4.842_966_6, -60.582_05],
+            [132.131_08, -14.185_033, 110.362_77],
+            [-152.942_4, 4.277_299, -89.903_11],
+            [59.286_38, 2.829_566, 27.348_25],
+        ],
+    )
+}
+
+/// Evaluate a polynomial in `t` for each sRGB channel, with `coefficients` from the constant term
+/// up. NaN is taken as `0`.
+fn polynomial(t: f32, coefficients: &[[f32; 3]]) -> egui::Color32 {
+    let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+    let [r, g, b] = coefficients
+        .iter()
+        .rev()
+        .fold([0.0; 3], |sum, coefficient| {
+            std::array::from_fn(|channel| sum[channel] * t + coefficient[channel])
+        })
+        .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+    egui::Color32::from_rgb(r, g, b)
+}
+
+/// A horizontal bar showing `colormap` from the start of `range` to its end, with `ticks` evenly
+/// spaced values labelled below it.
+///
+/// A legend for whatever is colored with [`Colormap::sample`] of values normalized to `range`.
+pub fn colorbar(
+    ui: &mut egui::Ui,
+    colormap: Colormap,
+    range: RangeInclusive<f64>,
+    ticks: usize,
+) -> egui::Response {
+    let tokens = ui.tokens();
+    let font_id = egui::TextStyle::Small.resolve(ui.style());
+    let bar_height = ui.spacing().interact_size.y * 0.5;
+    let label_height = ui.fonts_mut(|fonts| fonts.row_height(&font_id));
+    let tick_length = 4.0;
+    let size = egui::vec2(
+        ui.available_width(),
+        bar_height + tick_length + label_height,
+    );
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+    if !ui.is_rect_visible(rect) {
+        return response;
+    }
+
+    let bar = egui::Rect::from_min_size(rect.min, egui::vec2(rect.width(), bar_height));
+    // One vertical strip per couple of points, blended by the gpu in between
+    let mut mesh = egui::Mesh::default();
+    let steps = ((bar.width() / 2.0).ceil() as usize).max(1);
+    for step in 0..=steps {
+        let t = step as f32 / steps as f32;
+        let x = bar.left() + t * bar.width();
+        let color = colormap.sample(t);
+        mesh.colored_vertex(egui::pos2(x, bar.top()), color);
+        mesh.colored_vertex(egui::pos2(x, bar.bottom()), color);
+        if step > 0 {
+            let i = 2 * step as u32;
+            mesh.add_triangle(i - 2, i - 1, i);
+            mesh.add_triangle(i - 1, i, i + 1);
+        }
+    }
+    let painter = ui.painter();
+    painter.add(mesh);
+    painter.rect_stroke(
+        bar,
+        0.0,
+        egui::Stroke::new(1.0, tokens.widget_noninteractive_bg_stroke),
+        egui::StrokeKind::Outside,
+    );
+
+    let stroke = egui::Stroke::new(1.0, tokens.text_subdued);
+    let last = ticks.max(2) - 1;
+    for tick in 0..=last {
+        let t = tick as f32 / last as f32;
+        let x = bar.left() + t * bar.width();
+        painter.vline(x, bar.bottom()..=bar.bottom() + tick_length, stroke);
+
+        // The end labels stay inside the bar's width
+        let align = if tick == 0 {
+            egui::Align2::LEFT_TOP
+        } else if tick == last {
+            egui::Align2::RIGHT_TOP
+        } else {
+            egui::Align2::CENTER_TOP
+        };
+        let value = egui::lerp(range.clone(), f64::from(t));
+        painter.text(
+            egui::pos2(x, bar.bottom() + tick_length),
+            align,
+            format_f64(value),
+            font_id.clone(),
+            tokens.text_default,
+        );
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator as _;
+
+    use super::*;
+
+    /// Within `tolerance` sRGB steps in every channel.
+    fn assert_close(actual: egui::Color32, expected: egui::Color32, tolerance: u8) {
+        let close = (0..4).all(|i| actual[i].abs_diff(expected[i]) <= tolerance);
+        assert!(
+            close,
+            "{actual:?} is not within {tolerance} of {expected:?}"
+        );
+    }
+
+    #[test]
+    fn endpoints_match_the_original_tables() {
+        let hex = |text| crate::misc::parse_hex_color(text).unwrap();
+        for (colormap, sample, [start, end], tolerance) in [
+            (
+                Colormap::Viridis,
+                viridis as fn(f32) -> _,
+                ["#440154", "#FDE725"],
+                6,
+            ),
+            (Colormap::Magma, magma, ["#000004", "#FCFDBF"], 6),
+            // A looser fit, see the module docs
+            (Colormap::Turbo, turbo, ["#30123B", "#7A0403"], 32),
+        ] {
+            assert_close(sample(0.0), hex(start), tolerance);
+            assert_close(sample(1.0), hex(end), tolerance);
+            assert!(sample(0.0).is_opaque() && sample(1.0).is_opaque());
+            assert_eq!(colormap.sample(0.0), sample(0.0), "{colormap:?}");
+            assert_eq!(colormap.sample(1.0), sample(1.0), "{colormap:?}");
+        }
+    }
+
+    #[test]
+    fn midpoints_match_the_original_tables() {
+        let hex = |text| crate::misc::parse_hex_color(text).unwrap();
+        for (colormap, expected, tolerance) in [
+            (Colormap::Viridis, "#21918C", 6),
+            (Colormap::Magma, "#B73779", 6),
+            (Colormap::Turbo, "#A4FC3C", 32),
+        ] {
+            assert_close(colormap.sample(0.5), hex(expected), tolerance);
+        }
+    }
+
+    #[test]
+    fn samples_are_clamped() {
+        for colormap in Colormap::iter() {
+            let [start, end] = [colormap.sample(0.0), colormap.sample(1.0)];
+            assert_eq!(colormap.sample(-1.0), start, "{colormap:?}");
+            assert_eq!(colormap.sample(f32::NEG_INFINITY), start, "{colormap:?}");
+            assert_eq!(colormap.sample(f32::NAN), start, "{colormap:?}");
+            assert_eq!(colormap.sample(2.0), end, "{colormap:?}");
+            assert_eq!(colormap.sample(f32::INFINITY), end, "{colormap:?}");
+        }
+    }
+
+    #[test]
+    fn viridis_and_magma_brighten_steadily() {
+        let luma = |color: egui::Color32| {
+            0.2126 * color.r() as f32 + 0.7152 * color.g() as f32 + 0.0722 * color.b() as f32
+        };
+        for colormap in [Colormap::Viridis, Colormap::Magma] {
+            let lumas: Vec<f32> = (0..=32)
+                .map(|step| luma(colormap.sample(step as f32 / 32.0)))
+                .collect();
+            assert!(
+                lumas.windows(2).all(|pair| pair[1] >= pair[0]),
+                "{colormap:?}: {lumas:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! An in-app log console.
+//!
+//! [`install`] wraps the [`env_logger`] setup, so every record still goes to stderr, but is also
+//! kept in a [`LogBuffer`] that [`console_ui`] can show, and optionally written to a file.
+
+use std::collections::VecDeque;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use web_time::Instant;
+
+use super::{DesignTokens, UiExt as _};
+
+/// A single log message, as stored in a [`LogBuffer`].
+#[derive(Clone, Debug)]
+pub struct LogRecord {
+    pub level: log::Level,
+    /// Time since the [`LogBuffer`] was created.
+    pub time: Duration,
+    pub target: String,
+    pub message: String,
+}
+
+struct LogBufferInner {
+    start: Instant,
+    capacity: usize,
+    records: VecDeque<LogRecord>,
+}
+
+/// A ring buffer of the most recent log records.
+///
+/// Cheap to clone, and safe to write to from any thread.
+#[derive(Clone)]
+pub struct LogBuffer {
+    inner: Arc<Mutex<LogBufferInner>>,
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+impl LogBuffer {
+    /// Keep at most `capacity` records, dropping the oldest first.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(LogBufferInner {
+                start: Instant::now(),
+                capacity,
+                records: VecDeque::with_capacity(capacity),
+            })),
+        }
+    }
+
+    pub fn push(&self, record: &log::Record<'_>) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.capacity == 0 {
+            return;
+        }
+        if inner.records.len() == inner.capacity {
+            inner.records.pop_front();
+        }
+
+        let time = inner.start.elapsed();
+        inner.records.push_back(LogRecord {
+            level: record.level(),
+            time,
+            target: record.target().to_owned(),
+            message: record.args().to_string(),
+        });
+    }
+
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().records.clear();
+    }
+
+    /// Time since the buffer was created, as stored in [`LogRecord::time`].
+    pub fn elapsed(&self) -> Duration {
+        self.inner.lock().unwrap().start.elapsed()
+    }
+
+    /// Copy out every record at least as severe as `filter`, oldest first.
+    pub fn records(&self, filter: log::LevelFilter) -> Vec<LogRecord> {
+        self.inner
+            .lock()
+            .unwrap()
+            .records
+            .iter()
+            .filter(|record| record.level <= filter)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Forwards to [`env_logger`], and keeps a copy of everything it lets through.
+#[cfg(not(target_arch = "wasm32"))]
+struct ConsoleLogger {
+    env_logger: env_logger::Logger,
+    buffer: LogBuffer,
+    file: Option<Mutex<LogFile>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct LogFile {
+    writer: Box<dyn std::io::Write + Send>,
+    /// Whether the last write failed, so a full disk is reported once instead of on every record.
+    failing: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl LogFile {
+    /// Failures go to stderr, since logging them would only come back here.
+    fn report(&mut self, result: std::io::Result<()>) {
+        match result {
+            Ok(()) => self.failing = false,
+            Err(error) => {
+                if !self.failing {
+                    eprintln!("Failed to write to the log file: {error}");
+                }
+                self.failing = true;
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl log::Log for ConsoleLogger {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        self.env_logger.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if self.env_logger.matches(record) {
+            self.env_logger.log(record);
+            self.buffer.push(record);
+            if let Some(file) = &self.file {
+                // Plain text, without the terminal's colors, and a whole line per write so the file
+                // is never rotated halfway through one
+                let line = format!(
+                    "{:>9.3} [{}] {}: {}\n",
+                    self.buffer.elapsed().as_secs_f64(),
+                    record.level(),
+                    record.target(),
+                    record.args()
+                );
+                let mut file = file.lock().unwrap();
+                let result = file.writer.write_all(line.as_bytes());
+                file.report(result);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.env_logger.flush();
+        if let Some(file) = &self.file {
+            let mut file = file.lock().unwrap();
+            let result = file.writer.flush();
+            file.report(result);
+        }
+    }
+}
+
+/// Install the logger built by `builder`, also writing into the returned [`LogBuffer`], and into
+/// `file` if given.
+///
+/// Use this instead of [`env_logger::Builder::init`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn install(
+    builder: &mut env_logger::Builder,
+    file: Option<Box<dyn std::io::Write + Send>>,
+) -> Result<LogBuffer, log::SetLoggerError> {
+    let env_logger = builder.build();
+    let buffer = LogBuffer::default();
+
+    log::set_max_level(env_logger.filter());
+    log::set_boxed_logger(Box::new(ConsoleLogger {
+        env_logger,
+        buffer: buffer.clone(),
+        file: file.map(|writer| {
+            Mutex::new(LogFile {
+                writer,
+                failing: false,
+            })
+        }),
+    }))?;
+
+    Ok(buffer)
+}
+
+fn level_color(tokens: &DesignTokens, level: log::Level) -> egui::Color32 {
+    match level {
+        log::Level::Error => tokens.error_fg_color,
+        log::Level::Warn => tokens.warn_fg_color,
+        log::Level::Info => tokens.info_log_text_color,
+        log::Level::Debug => tokens.debug_log_text_color,
+        log::Level::Trace => tokens.trace_log_text_color,
+    }
+}
+
+/// Show the records in `buffer`, newest at the bottom, with a level filter and search.
+pub fn console_ui(ui: &mut egui::Ui, buffer: &LogBuffer) {
+    let tokens = ui.tokens();
+    let id = ui.make_persistent_id("console_filter");
+    let mut filter = ui
+        .data(|data| data.get_temp::<log::LevelFilter>(id))
+        .unwrap_or(log::LevelFilter::Info);
+    let search_id = id.with("search");
+    let mut search = ui
+        .data(|data| data.get_temp::<String>(search_id))
+        .unwrap_or_default();
+
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt(id)
+            .selected_text(filter.as_str())
+            .show_ui(ui, |ui| {
+                for level in log::LevelFilter::iter() {
+                    ui.selectable_value(&mut filter, level, level.as_str());
+                }
+            });
+        if ui.button("Clear").clicked() {
+            buffer.clear();
+        }
+        ui.search_field(&mut search);
+    });
+    ui.data_mut(|data| {
+        data.insert_temp(id, filter);
+        data.insert_temp(search_id, search.clone());
+    });
+
+    ui.separator();
+
+    let mut records = buffer.records(filter);
+    if !search.is_empty() {
+        let search = search.to_lowercase();
+        records.retain(|record| record.message.to_lowercase().contains(&search));
+    }
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .stick_to_bottom(true)
+        .show_rows(ui, row_height, records.len(), |ui, rows| {
+            for record in &records[rows] {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!("{:>9.3}", record.time.as_secs_f64()))
+                            .monospace()
+                            .color(tokens.text_subdued),
+                    );
+                    ui.label(
+                        egui::RichText::new(format!("{:<5}", record.level))
+                            .monospace()
+                            .color(level_color(tokens, record.level)),
+                    );
+                    ui.label(egui::RichText::new(&record.message).monospace())
+                        .on_hover_text(&record.target);
+                });
+            }
+        });
+}
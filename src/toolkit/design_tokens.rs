@@ -3,7 +3,7 @@ use std::sync::OnceLock;
 use egui::{Color32, Margin, Stroke, Theme, Vec2};
 use eyre::WrapErr as _;
 
-use crate::toolkit::color_table::{ColorTable, ColorToken, Hue, Scale};
+use crate::toolkit::color_table::{self, ColorTable, ColorToken, Hue, Scale};
 
 struct DesignTokensPerTheme {
     dark: DesignTokens,
@@ -66,6 +66,34 @@ pub enum TableStyle {
     Spacious,
 }
 
+/// Theme colors for what the renderer draws under the ui, like the background and the reference
+/// grid, so scene passes can match it.
+///
+/// Linear, premultiplied RGBA, the way shaders want them, and plain data, so the renderer can hold
+/// on to it without an [`egui::Context`]. See [`super::scene_colors`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SceneColors {
+    /// Behind everything, the same as the ui's panels.
+    pub background: egui::Rgba,
+    /// The finer lines of a grid.
+    pub grid_minor: egui::Rgba,
+    /// The coarser, more visible lines of a grid.
+    pub grid_major: egui::Rgba,
+    /// Highlights, like the selection.
+    pub accent: egui::Rgba,
+    /// The X, Y and Z axes, like those of the orientation gizmo.
+    pub axes: [egui::Rgba; 3],
+}
+
+/// An sRGB `color`, like every [`Color32`] in the tokens, in linear RGBA.
+///
+/// Undoes the sRGB transfer function on the color channels, while alpha is linear already.
+/// Shaders blending in linear space, or writing to an sRGB target, need this, rather than the raw
+/// bytes divided by 255.
+pub fn linear_rgba(color: Color32) -> egui::Rgba {
+    egui::Rgba::from(color)
+}
+
 /// The look and feel of the UI.
 ///
 /// Not everything is covered by this.
@@ -143,6 +171,7 @@ pub struct DesignTokens {
     pub icon_color_on_primary_hovered: Color32,
     pub selection_stroke_color: Color32,
     pub selection_bg_fill: Color32,
+    /// Drawn around the widget that has keyboard focus, see [`super::UiExt::focusable`].
     pub focus_outline_stroke: Stroke,
     pub focus_halo_stroke: Stroke,
 
@@ -236,6 +265,12 @@ pub struct DesignTokens {
     pub axis_color_z: Color32,
     pub frustum_color: Color32,
 
+    /// OKLCh lightness of the colors from [`Self::color_for_hash`].
+    pub hash_color_lightness: f32,
+
+    /// OKLCh chroma of the colors from [`Self::color_for_hash`].
+    pub hash_color_chroma: f32,
+
     // List item colors
     pub list_item_active_text: Color32,
     pub list_item_noninteractive_text: Color32,
@@ -412,6 +447,9 @@ impl DesignTokens {
             axis_color_z: get_color("axis_color_z"),
             frustum_color: get_color("frustum_color"),
 
+            hash_color_lightness: get_scalar("hash_color_lightness")?,
+            hash_color_chroma: get_scalar("hash_color_chroma")?,
+
             // List item colors
             list_item_active_text: get_color("list_item_active_text"),
             list_item_noninteractive_text: get_color("list_item_noninteractive_text"),
@@ -458,6 +496,9 @@ impl DesignTokens {
 
         style.number_formatter =
             egui::style::NumberFormatter::new(crate::misc::format_with_decimals_in_range);
+
+        style.interaction.tooltip_delay = Self::tooltip_delay();
+        style.interaction.tooltip_grace_time = Self::tooltip_grace_time();
     }
 
     pub(crate) fn set_fonts(&self, ctx: &egui::Context) {
@@ -475,9 +516,23 @@ impl DesignTokens {
             .get_mut(&egui::FontFamily::Proportional)
             .unwrap()
             .insert(0, "Inter-Medium".into());
+        super::fonts::insert_custom_fonts(ctx, &mut font_definitions);
         ctx.set_fonts(font_definitions);
     }
 
+    /// The font for regular text: Inter, in [`egui::FontFamily::Proportional`].
+    pub fn body_font(&self) -> egui::FontId {
+        egui::FontId::proportional(parse_px(&self.typography.fontSize))
+    }
+
+    /// The font for text that should line up in columns, like numbers in a table.
+    ///
+    /// The design only defines a proportional family, so this is egui's default
+    /// [`egui::FontFamily::Monospace`], unless replaced with [`super::add_font`].
+    pub fn monospace_font(&self) -> egui::FontId {
+        egui::FontId::monospace(parse_px(&self.typography.fontSize))
+    }
+
     fn set_text_styles(&self, egui_style: &mut egui::Style) {
         let font_size = parse_px(&self.typography.fontSize);
 
@@ -676,6 +731,26 @@ impl DesignTokens {
         egui::Margin::symmetric(self.view_padding(), 0)
     }
 
+    /// Width of the gradient egui fades shape edges out over, in physical pixels.
+    ///
+    /// Narrower than egui's default of one pixel, so one point dividers stay sharp on HiDPI
+    /// displays, while diagonal and curved edges are still smoothed.
+    pub fn feathering_size_in_pixels() -> f32 {
+        0.75
+    }
+
+    /// Seconds the pointer has to rest on a widget, or keyboard focus stay on it, before its
+    /// tooltip shows.
+    pub fn tooltip_delay() -> f32 {
+        0.4
+    }
+
+    /// Seconds after a tooltip closes during which the next one shows without
+    /// [`Self::tooltip_delay`], so skimming along a toolbar reads each button straight away.
+    pub fn tooltip_grace_time() -> f32 {
+        0.3
+    }
+
     pub fn menu_button_padding() -> f32 {
         6.0
     }
@@ -741,6 +816,11 @@ impl DesignTokens {
         28.0 // Don't waste vertical space, especially important for embedded web viewers
     }
 
+    /// Height of the status bar along the bottom of the window.
+    pub fn status_bar_height(&self) -> f32 {
+        22.0
+    }
+
     /// Height of the title row in the blueprint view and selection view,
     /// as well as the tab bar height in the viewport view.
     pub fn title_bar_height(&self) -> f32 {
@@ -784,6 +864,25 @@ impl DesignTokens {
         frame
     }
 
+    /// The colors scene passes use to match the ui, see [`SceneColors`].
+    pub fn scene_colors(&self) -> SceneColors {
+        SceneColors {
+            background: linear_rgba(self.panel_bg_color),
+            grid_minor: linear_rgba(self.widget_inactive_bg_fill),
+            grid_major: linear_rgba(self.strong_fg_color.gamma_multiply(0.5)),
+            accent: linear_rgba(self.selection_bg_fill),
+            axes: [self.axis_color_x, self.axis_color_y, self.axis_color_z].map(linear_rgba),
+        }
+    }
+
+    /// The background of tooltips: the floating color and shadow of menus, with tighter corners.
+    pub fn tooltip_frame(&self, style: &egui::Style) -> egui::Frame {
+        egui::Frame::popup(style)
+            .fill(self.floating_color)
+            .corner_radius(self.small_corner_radius())
+            .inner_margin(6.0)
+    }
+
     /// Something that provides contrast vs the background
     pub fn popup_frame(&self, style: &egui::Style) -> egui::Frame {
         egui::Frame::window(style)
@@ -844,6 +943,11 @@ impl DesignTokens {
     pub fn collapsing_triangle_size(&self) -> Vec2 {
         self.small_icon_size
     }
+
+    /// A stable, distinct color for e.g. an entity id, readable on this theme's background.
+    pub fn color_for_hash(&self, hash: u64) -> Color32 {
+        color_table::color_for_hash(hash, self.hash_color_lightness, self.hash_color_chroma)
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -0,0 +1,130 @@
+//! Native file dialogs, as futures so waiting on the user doesn't block the render loop.
+//!
+//! Spawn them on a task pool and poll the handle each frame:
+//!
+//! ```ignore
+//! let handle = tasks.spawn(
+//!     dialog::FileDialog::new()
+//!         .title("Open Scene")
+//!         .filter("Scene", &["ron"])
+//!         .open_file(),
+//! );
+//! ```
+//!
+//! On the web these show the browser's file input instead. It has no paths, so files come back
+//! as just their names, and there is nothing to save to or folders to pick.
+
+use std::path::PathBuf;
+
+/// Files a dialog lists, by extension.
+#[derive(Clone, Debug)]
+pub struct FileFilter {
+    pub name: String,
+    /// Without the leading dot, like `"png"`.
+    pub extensions: Vec<String>,
+}
+
+/// Settings shared by all kinds of file dialog, consumed by the one that is shown.
+#[derive(Clone, Debug, Default)]
+pub struct FileDialog {
+    title: Option<String>,
+    directory: Option<PathBuf>,
+    file_name: Option<String>,
+    filters: Vec<FileFilter>,
+}
+
+impl FileDialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Where the dialog starts out, rather than wherever the platform picks.
+    pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// The name suggested when saving.
+    pub fn file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Only list files ending in one of `extensions`, under `name`. Each filter added is another
+    /// choice in the dialog, and the first is picked to start with.
+    pub fn filter(mut self, name: impl Into<String>, extensions: &[&str]) -> Self {
+        self.filters.push(FileFilter {
+            name: name.into(),
+            extensions: extensions.iter().map(ToString::to_string).collect(),
+        });
+        self
+    }
+
+    /// Ask for a single file to open, or `None` if the dialog was cancelled.
+    pub async fn open_file(self) -> Option<PathBuf> {
+        let handle = self.to_rfd().pick_file().await?;
+        Some(path_of(&handle))
+    }
+
+    /// Ask for any number of files to open, or none if the dialog was cancelled.
+    pub async fn open_files(self) -> Vec<PathBuf> {
+        let handles = self.to_rfd().pick_files().await.unwrap_or_default();
+        handles.iter().map(path_of).collect()
+    }
+
+    /// Ask where to save a file, or `None` if the dialog was cancelled.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn save_file(self) -> Option<PathBuf> {
+        let handle = self.to_rfd().save_file().await?;
+        Some(path_of(&handle))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub async fn save_file(self) -> Option<PathBuf> {
+        None
+    }
+
+    /// Ask for a folder, or `None` if the dialog was cancelled.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn pick_folder(self) -> Option<PathBuf> {
+        let handle = self.to_rfd().pick_folder().await?;
+        Some(path_of(&handle))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub async fn pick_folder(self) -> Option<PathBuf> {
+        None
+    }
+
+    fn to_rfd(&self) -> rfd::AsyncFileDialog {
+        let mut dialog = rfd::AsyncFileDialog::new();
+        if let Some(title) = &self.title {
+            dialog = dialog.set_title(title);
+        }
+        if let Some(directory) = &self.directory {
+            dialog = dialog.set_directory(directory);
+        }
+        if let Some(file_name) = &self.file_name {
+            dialog = dialog.set_file_name(file_name);
+        }
+        for filter in &self.filters {
+            dialog = dialog.add_filter(&filter.name, &filter.extensions);
+        }
+        dialog
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn path_of(handle: &rfd::FileHandle) -> PathBuf {
+    handle.path().to_path_buf()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn path_of(handle: &rfd::FileHandle) -> PathBuf {
+    PathBuf::from(handle.file_name())
+}
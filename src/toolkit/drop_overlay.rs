@@ -0,0 +1,46 @@
+//! Feedback while files are dragged over the window.
+
+use std::path::PathBuf;
+
+use super::HasDesignTokens as _;
+
+/// Dim the whole window and say what would be opened, while `hovered` files are dragged over it.
+///
+/// Does nothing if `hovered` is empty.
+pub fn drop_overlay_ui(ctx: &egui::Context, hovered: &[PathBuf]) {
+    if hovered.is_empty() {
+        return;
+    }
+
+    let tokens = ctx.tokens();
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("drop_overlay"),
+    ));
+    let rect = ctx.content_rect();
+
+    painter.rect_filled(rect, 0.0, egui::Color32::BLACK.gamma_multiply(0.6));
+    painter.rect_stroke(
+        rect.shrink(16.0),
+        tokens.native_window_corner_radius(),
+        egui::Stroke::new(2.0, tokens.selection_bg_fill),
+        egui::StrokeKind::Inside,
+    );
+
+    let text = match hovered {
+        [path] => format!(
+            "Drop to open {}",
+            path.file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+        ),
+        paths => format!("Drop to open {} files", paths.len()),
+    };
+    painter.text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        text,
+        egui::TextStyle::Heading.resolve(&ctx.style()),
+        tokens.strong_fg_color,
+    );
+}
@@ -0,0 +1,49 @@
+//! Showing an [`eyre::Report`] to the user, rather than only logging it.
+
+use super::icons;
+use super::{HasDesignTokens as _, UiExt as _};
+
+/// Show `report`: its message next to an error icon, the chain of causes added with
+/// [`eyre::WrapErr`] in a collapsible section, and a button to copy all of it for a bug report.
+pub fn show(ui: &mut egui::Ui, report: &eyre::Report) -> egui::Response {
+    let tokens = ui.tokens();
+    let color = tokens.error_fg_color;
+
+    ui.vertical(|ui| {
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = tokens.text_to_icon_padding();
+            ui.add(
+                icons::ERROR
+                    .as_image()
+                    .fit_to_exact_size(tokens.small_icon_size)
+                    .tint(color),
+            );
+            ui.add(egui::Label::new(egui::RichText::new(report.to_string()).color(color)).wrap());
+
+            if ui.icon_button(&icons::COPY, "Copy error").clicked() {
+                // The debug format lists the causes, and the backtrace if one was captured
+                ui.ctx().copy_text(format!("{report:?}"));
+            }
+        });
+
+        let causes: Vec<String> = report.chain().skip(1).map(ToString::to_string).collect();
+        if !causes.is_empty() {
+            egui::CollapsingHeader::new(
+                egui::RichText::new(format!("Caused by ({})", causes.len()))
+                    .color(tokens.text_subdued),
+            )
+            .id_salt(ui.auto_id_with("error_causes"))
+            .show(ui, |ui| {
+                for (index, cause) in causes.iter().enumerate() {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(
+                            egui::RichText::new(format!("{index}:")).color(tokens.text_subdued),
+                        );
+                        ui.label(egui::RichText::new(cause).color(tokens.text_default));
+                    });
+                }
+            });
+        }
+    })
+    .response
+}
@@ -0,0 +1,76 @@
+//! Fonts added on top of the ones the design tokens install, see [`add_font`].
+
+use std::sync::Arc;
+
+use super::design_tokens_of;
+
+#[derive(Clone)]
+struct CustomFont {
+    name: String,
+    data: Arc<egui::FontData>,
+    family: egui::FontFamily,
+}
+
+fn custom_fonts_id() -> egui::Id {
+    egui::Id::new("toolkit_custom_fonts")
+}
+
+/// Register the font in `bytes` (TTF or OTF) under `name`, and make it part of `family`.
+///
+/// - In [`egui::FontFamily::Proportional`] it becomes a fallback after the design font, e.g. for
+///   CJK glyphs the design font lacks.
+/// - In any other family it comes first, e.g. to swap out the monospace font.
+/// - A [`egui::FontFamily::Name`] that doesn't exist yet is created, falling back to the
+///   proportional fonts for missing glyphs.
+///
+/// Adding a font under a name that was already added replaces it. The fonts survive the design
+/// tokens re-installing theirs, e.g. on theme changes.
+pub fn add_font(
+    egui_ctx: &egui::Context,
+    name: impl Into<String>,
+    bytes: impl Into<std::borrow::Cow<'static, [u8]>>,
+    family: egui::FontFamily,
+) {
+    let font = CustomFont {
+        name: name.into(),
+        data: Arc::new(egui::FontData {
+            font: bytes.into(),
+            index: 0,
+            tweak: Default::default(),
+        }),
+        family,
+    };
+    egui_ctx.data_mut(|data| {
+        let fonts = data.get_temp_mut_or_default::<Vec<CustomFont>>(custom_fonts_id());
+        fonts.retain(|existing| existing.name != font.name);
+        fonts.push(font);
+    });
+
+    // It's the same fonts in dark/light mode:
+    design_tokens_of(egui::Theme::Dark).set_fonts(egui_ctx);
+}
+
+/// Add every font from [`add_font`] to `definitions`.
+pub(super) fn insert_custom_fonts(
+    egui_ctx: &egui::Context,
+    definitions: &mut egui::FontDefinitions,
+) {
+    let fonts = egui_ctx.data(|data| data.get_temp::<Vec<CustomFont>>(custom_fonts_id()));
+    for font in fonts.unwrap_or_default() {
+        definitions
+            .font_data
+            .insert(font.name.clone(), font.data.clone());
+
+        let proportional = definitions.families[&egui::FontFamily::Proportional].clone();
+        let family = definitions
+            .families
+            .entry(font.family.clone())
+            .or_insert_with(|| proportional);
+        family.retain(|existing| *existing != font.name);
+        if font.family == egui::FontFamily::Proportional {
+            family.push(font.name);
+        } else {
+            family.insert(0, font.name);
+        }
+    }
+}
@@ -167,6 +167,9 @@ pub const DISCORD: Icon = icon_from_path!("data/icons/discord.svg");
 
 pub const URL: Icon = icon_from_path!("data/icons/url.svg");
 
+/// Components that change together, like a uniform scale.
+pub const CHAIN: Icon = icon_from_path!("data/icons/chain.svg");
+
 pub const CONTAINER_HORIZONTAL: Icon = icon_from_path!("data/icons/container_horizontal.svg");
 pub const CONTAINER_GRID: Icon = icon_from_path!("data/icons/container_grid.svg");
 pub const CONTAINER_TABS: Icon = icon_from_path!("data/icons/container_tabs.svg");
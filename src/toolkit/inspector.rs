@@ -0,0 +1,134 @@
+//! A generic inspector over a [`hecs::World`].
+//!
+//! hecs is type-erased, so components have to be registered with an [`InspectorRegistry`]
+//! before they can be shown with anything more than their [`TypeId`].
+
+use std::any::TypeId;
+use std::collections::HashMap;
+
+use super::UiExt as _;
+
+/// A component that knows how to draw an editor for itself.
+pub trait Inspect {
+    fn inspect(&mut self, ui: &mut egui::Ui);
+}
+
+type InspectFn = fn(&hecs::World, hecs::Entity, &mut egui::Ui);
+
+struct RegisteredComponent {
+    name: &'static str,
+    inspect: Option<InspectFn>,
+}
+
+/// Maps component types to their display name and (optionally) an editor.
+#[derive(Default)]
+pub struct InspectorRegistry {
+    components: HashMap<TypeId, RegisteredComponent>,
+}
+
+impl InspectorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a component that is editable through [`Inspect`].
+    pub fn register<T: Inspect + hecs::Component>(&mut self) -> &mut Self {
+        self.components.insert(
+            TypeId::of::<T>(),
+            RegisteredComponent {
+                name: short_type_name::<T>(),
+                inspect: Some(inspect_component::<T>),
+            },
+        );
+        self
+    }
+
+    /// Register a component that is only shown by name.
+    pub fn register_opaque<T: hecs::Component>(&mut self) -> &mut Self {
+        self.components.insert(
+            TypeId::of::<T>(),
+            RegisteredComponent {
+                name: short_type_name::<T>(),
+                inspect: None,
+            },
+        );
+        self
+    }
+}
+
+fn inspect_component<T: Inspect + hecs::Component>(
+    world: &hecs::World,
+    entity: hecs::Entity,
+    ui: &mut egui::Ui,
+) {
+    if let Ok(mut component) = world.get::<&mut T>(entity) {
+        component.inspect(ui);
+    }
+}
+
+/// `stellar::math::transform::Transform` -> `Transform`
+fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// List every entity in the `world`, along with the components it holds.
+///
+/// Components registered with [`InspectorRegistry::register`] are editable.
+pub fn world_ui(ui: &mut egui::Ui, world: &mut hecs::World, registry: &InspectorRegistry) {
+    let tokens = ui.tokens();
+
+    let entities: Vec<(hecs::Entity, Vec<TypeId>)> = world
+        .iter()
+        .map(|entity| (entity.entity(), entity.component_types().collect()))
+        .collect();
+
+    if entities.is_empty() {
+        ui.empty_state(
+            &super::icons::ENTITY_EMPTY,
+            "No entities",
+            Some("Entities in the world show up here"),
+        );
+        return;
+    }
+
+    for (entity, component_types) in entities {
+        let title = egui::RichText::new(format!("Entity {}", entity.id()))
+            .color(tokens.color_for_hash(entity.to_bits().get()));
+        let header = egui::CollapsingHeader::new(title)
+            .id_salt(entity)
+            .show(ui, |ui| {
+                for type_id in component_types {
+                    match registry.components.get(&type_id) {
+                        Some(RegisteredComponent {
+                            name,
+                            inspect: Some(inspect),
+                        }) => {
+                            egui::CollapsingHeader::new(*name)
+                                .id_salt((entity, type_id))
+                                .default_open(true)
+                                .show(ui, |ui| inspect(world, entity, ui));
+                        }
+                        Some(RegisteredComponent {
+                            name,
+                            inspect: None,
+                        }) => {
+                            ui.truncated_label(name);
+                        }
+                        None => {
+                            ui.label(
+                                egui::RichText::new(format!("{type_id:?}"))
+                                    .color(tokens.text_subdued),
+                            );
+                        }
+                    }
+                }
+            });
+        super::menu::context_menu(&header.header_response, |menu| {
+            if menu.item("Copy Id", None, true) {
+                let id = entity.to_bits().get().to_string();
+                menu.ui().ctx().copy_text(id);
+            }
+        });
+    }
+}
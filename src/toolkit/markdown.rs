@@ -0,0 +1,423 @@
+//! A small subset of Markdown, for help text, changelogs and rich tooltips.
+//!
+//! Supported:
+//! - `#` to `######` headings
+//! - paragraphs, separated by blank lines
+//! - `-`, `*` and `+` bullets and `1.` numbered lists, nested by indenting two spaces
+//! - fenced code blocks between lines of ```` ``` ````
+//! - `---` horizontal rules
+//! - `**bold**`, `*italic*` and `_italic_`, `` `inline code` `` and `[links](https://…)`
+//! - `\` before a marker to show it as is
+//!
+//! Anything else is shown as plain text. Markers without a partner, like a lone `*`, are too.
+
+use super::UiExt as _;
+
+/// Show `src` as Markdown, see the [module docs](self) for what is supported. Links open in the
+/// browser.
+pub fn markdown_ui(ui: &mut egui::Ui, src: &str) -> egui::Response {
+    let tokens = ui.tokens();
+    ui.vertical(|ui| {
+        for (index, block) in parse_blocks(src).into_iter().enumerate() {
+            if index > 0 {
+                ui.add_space(tokens.view_padding() as f32 / 2.0);
+            }
+            match block {
+                Block::Heading { level, text } => {
+                    let size = heading_size(ui.style(), level);
+                    spans_ui(ui, &parse_spans(&text), Some(size));
+                }
+                Block::Paragraph(text) => spans_ui(ui, &parse_spans(&text), None),
+                Block::ListItem {
+                    depth,
+                    marker,
+                    text,
+                } => {
+                    ui.horizontal(|ui| {
+                        ui.add_space(depth as f32 * ui.spacing().indent);
+                        let marker = match marker {
+                            ListMarker::Bullet => "•".to_owned(),
+                            ListMarker::Number(number) => format!("{number}."),
+                        };
+                        ui.label(egui::RichText::new(marker).color(tokens.text_subdued));
+                        spans_ui(ui, &parse_spans(&text), None);
+                    });
+                }
+                Block::Code(code) => {
+                    egui::Frame::new()
+                        .fill(ui.visuals().extreme_bg_color)
+                        .corner_radius(tokens.small_corner_radius())
+                        .inner_margin(tokens.view_padding() as f32 / 2.0)
+                        .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.add(
+                                egui::Label::new(
+                                    egui::RichText::new(code)
+                                        .monospace()
+                                        .color(tokens.text_default),
+                                )
+                                .extend(),
+                            );
+                        });
+                }
+                Block::Rule => {
+                    ui.separator();
+                }
+            }
+        }
+    })
+    .response
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Block {
+    Heading {
+        level: u8,
+        text: String,
+    },
+    Paragraph(String),
+    ListItem {
+        depth: usize,
+        marker: ListMarker,
+        text: String,
+    },
+    Code(String),
+    Rule,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ListMarker {
+    Bullet,
+    Number(u32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Span {
+    Text {
+        text: String,
+        bold: bool,
+        italic: bool,
+    },
+    Code(String),
+    Link {
+        text: String,
+        url: String,
+    },
+}
+
+/// Split `src` into blocks, a line at a time.
+fn parse_blocks(src: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    // Lines of the paragraph being collected, joined with spaces once it ends
+    let mut paragraph: Vec<&str> = Vec::new();
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(paragraph.join(" ")));
+            paragraph.clear();
+        }
+    };
+
+    let mut lines = src.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") {
+            flush(&mut paragraph, &mut blocks);
+            // An unclosed fence runs to the end
+            let code: Vec<&str> = lines
+                .by_ref()
+                .take_while(|line| !line.trim_start().starts_with("```"))
+                .collect();
+            blocks.push(Block::Code(code.join("\n")));
+        } else if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if let Some((level, text)) = parse_heading(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading {
+                level,
+                text: text.to_owned(),
+            });
+        } else if is_rule(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Rule);
+        } else if let Some((marker, text)) = parse_list_marker(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            let indent = line.len() - line.trim_start().len();
+            blocks.push(Block::ListItem {
+                depth: indent / 2,
+                marker,
+                text: text.to_owned(),
+            });
+        } else if paragraph.is_empty()
+            && line.starts_with(char::is_whitespace)
+            && let Some(Block::ListItem { text, .. }) = blocks.last_mut()
+        {
+            // An indented line right after a list item continues it
+            text.push(' ');
+            text.push_str(trimmed);
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+/// `## Title` as `(2, "Title")`.
+fn parse_heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.bytes().take_while(|byte| *byte == b'#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (1..=6)
+        .contains(&level)
+        .then(|| (level as u8, text.trim_end_matches('#').trim()))
+}
+
+/// Three or more `-`, `*` or `_`, and nothing else.
+fn is_rule(line: &str) -> bool {
+    let line: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    line.len() >= 3
+        && ['-', '*', '_']
+            .into_iter()
+            .any(|marker| line.chars().all(|c| c == marker))
+}
+
+/// `- item` or `3. item`, with the marker and the item's text.
+fn parse_list_marker(line: &str) -> Option<(ListMarker, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(bullet) {
+            return Some((ListMarker::Bullet, text.trim()));
+        }
+    }
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+    let text = line[digits..].strip_prefix(". ")?;
+    let number = line[..digits].parse().ok()?;
+    Some((ListMarker::Number(number), text.trim()))
+}
+
+/// Split a block's text into runs of the same style, code and links.
+fn parse_spans(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let (mut bold, mut italic) = (false, false);
+    let flush = |plain: &mut String, spans: &mut Vec<Span>, bold: bool, italic: bool| {
+        if !plain.is_empty() {
+            spans.push(Span::Text {
+                text: std::mem::take(plain),
+                bold,
+                italic,
+            });
+        }
+    };
+
+    let mut previous = None;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let after = &rest[c.len_utf8()..];
+
+        // Markers are all ASCII, so slicing one byte past them stays on a char boundary
+        if c == '\\'
+            && let Some(escaped) = after.chars().next().filter(char::is_ascii_punctuation)
+        {
+            plain.push(escaped);
+            previous = Some(escaped);
+            rest = &after[1..];
+            continue;
+        }
+        if c == '`'
+            && let Some(end) = after.find('`')
+        {
+            flush(&mut plain, &mut spans, bold, italic);
+            spans.push(Span::Code(after[..end].to_owned()));
+            previous = Some('`');
+            rest = &after[end + 1..];
+            continue;
+        }
+        if c == '['
+            && let Some((label, url, len)) = parse_link(rest)
+        {
+            flush(&mut plain, &mut spans, bold, italic);
+            spans.push(Span::Link {
+                text: label.to_owned(),
+                url: url.to_owned(),
+            });
+            previous = Some(')');
+            rest = &rest[len..];
+            continue;
+        }
+        if let Some(after_marker) = rest.strip_prefix("**") {
+            if bold || after_marker.contains("**") {
+                flush(&mut plain, &mut spans, bold, italic);
+                bold = !bold;
+            } else {
+                // Rather than as two italic markers
+                plain.push_str("**");
+            }
+            previous = Some('*');
+            rest = after_marker;
+            continue;
+        }
+        // `_` only counts at the edges of words, so snake_case stays as it is
+        let is_italic_marker = match c {
+            '*' => true,
+            '_' if italic => !after.starts_with(char::is_alphanumeric),
+            '_' => !previous.is_some_and(char::is_alphanumeric),
+            _ => false,
+        };
+        if is_italic_marker && (italic || after.contains(c)) {
+            flush(&mut plain, &mut spans, bold, italic);
+            italic = !italic;
+            previous = Some(c);
+            rest = after;
+            continue;
+        }
+
+        plain.push(c);
+        previous = Some(c);
+        rest = after;
+    }
+    flush(&mut plain, &mut spans, bold, italic);
+    spans
+}
+
+/// `[label](url)` at the start of `text`, with how many bytes it takes up.
+fn parse_link(text: &str) -> Option<(&str, &str, usize)> {
+    let label_end = text.find("](")?;
+    let label = &text[1..label_end];
+    let url_start = label_end + 2;
+    let url_len = text[url_start..].find(')')?;
+    let url = &text[url_start..url_start + url_len];
+    (!label.contains('[') && !url.is_empty()).then_some((label, url, url_start + url_len + 1))
+}
+
+/// Font size of a heading: the heading style for the top level, scaling down to the body size.
+fn heading_size(style: &egui::Style, level: u8) -> f32 {
+    let heading = egui::TextStyle::Heading.resolve(style).size;
+    let body = egui::TextStyle::Body.resolve(style).size;
+    let t = (f32::from(level) - 1.0) / 3.0;
+    egui::lerp(heading..=body, t.min(1.0))
+}
+
+/// Lay out `spans` as wrapped text, all at `size` and strong when it is a heading.
+fn spans_ui(ui: &mut egui::Ui, spans: &[Span], heading_size: Option<f32>) {
+    let tokens = ui.tokens();
+    ui.horizontal_wrapped(|ui| {
+        // The spans are pieces of the same text, the spaces are in them
+        ui.spacing_mut().item_spacing.x = 0.0;
+        for span in spans {
+            let mut text = match span {
+                Span::Text { text, bold, italic } => {
+                    let mut text = egui::RichText::new(text);
+                    text = if *bold || heading_size.is_some() {
+                        text.strong().color(tokens.text_strong)
+                    } else {
+                        text.color(tokens.text_default)
+                    };
+                    if *italic {
+                        text = text.italics();
+                    }
+                    text
+                }
+                Span::Code(code) => egui::RichText::new(code).code().color(tokens.text_strong),
+                Span::Link { text, .. } => egui::RichText::new(text),
+            };
+            if let Some(size) = heading_size {
+                text = text.size(size);
+            }
+            match span {
+                Span::Link { url, .. } => {
+                    ui.hyperlink_to(text, url).on_hover_text(url.as_str());
+                }
+                _ => {
+                    ui.label(text);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str, bold: bool, italic: bool) -> Span {
+        Span::Text {
+            text: text.to_owned(),
+            bold,
+            italic,
+        }
+    }
+
+    #[test]
+    fn blocks() {
+        let src = "# Stellar ##\nA paragraph\nover two lines.\n\n- one\n  1. nested\n    continued\n---\n```\nlet x = 1;\n\n```\n####### too deep";
+        assert_eq!(
+            parse_blocks(src),
+            [
+                Block::Heading {
+                    level: 1,
+                    text: "Stellar".to_owned()
+                },
+                Block::Paragraph("A paragraph over two lines.".to_owned()),
+                Block::ListItem {
+                    depth: 0,
+                    marker: ListMarker::Bullet,
+                    text: "one".to_owned()
+                },
+                Block::ListItem {
+                    depth: 1,
+                    marker: ListMarker::Number(1),
+                    text: "nested continued".to_owned()
+                },
+                Block::Rule,
+                Block::Code("let x = 1;\n".to_owned()),
+                Block::Paragraph("####### too deep".to_owned()),
+            ]
+        );
+
+        // An unclosed fence runs to the end
+        assert_eq!(
+            parse_blocks("```\ncode\n# not a heading"),
+            [Block::Code("code\n# not a heading".to_owned())]
+        );
+    }
+
+    #[test]
+    fn spans() {
+        assert_eq!(
+            parse_spans("**bold *both*** `code` [docs](https://example.com)"),
+            [
+                text("bold ", true, false),
+                text("both", true, true),
+                text(" ", false, false),
+                Span::Code("code".to_owned()),
+                text(" ", false, false),
+                Span::Link {
+                    text: "docs".to_owned(),
+                    url: "https://example.com".to_owned()
+                },
+            ]
+        );
+        assert_eq!(
+            parse_spans("_italic_ snake_case_name"),
+            [
+                text("italic", false, true),
+                text(" snake_case_name", false, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn unpartnered_markers_are_plain_text() {
+        for src in [
+            "2 * 3",
+            "**not bold",
+            "`unclosed",
+            "[label](",
+            "[label]()",
+            "\\*escaped\\*",
+        ] {
+            let expected = src.replace('\\', "");
+            assert_eq!(parse_spans(src), [text(&expected, false, false)], "{src:?}");
+        }
+    }
+}
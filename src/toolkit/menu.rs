@@ -1,7 +1,9 @@
+use std::path::PathBuf;
+
 use egui::style::StyleModifier;
 use egui::{Frame, InnerResponse, Ui};
 
-use super::DesignTokens;
+use super::{DesignTokens, UiExt as _};
 
 pub fn menu_style() -> StyleModifier {
     StyleModifier::new(|style| {
@@ -36,3 +38,180 @@ pub fn align_non_button_menu_items<T>(
         .inner_margin(DesignTokens::menu_button_padding())
         .show(ui, content)
 }
+
+/// Show a right-click menu on `response`, with [`menu_style`] and token colors.
+///
+/// `build` adds the items through a [`MenuBuilder`]. Returns the label of the item that was
+/// clicked, which also closes the menu.
+pub fn context_menu(
+    response: &egui::Response,
+    build: impl FnOnce(&mut MenuBuilder<'_>),
+) -> Option<String> {
+    let mut clicked = None;
+    egui::Popup::context_menu(response)
+        .style(menu_style())
+        .show(|ui| {
+            build(&mut MenuBuilder {
+                ui,
+                clicked: &mut clicked,
+            });
+        });
+    clicked
+}
+
+/// Adds items to a [`context_menu`].
+pub struct MenuBuilder<'a> {
+    ui: &'a mut Ui,
+    clicked: &'a mut Option<String>,
+}
+
+impl MenuBuilder<'_> {
+    /// An item, with the `shortcut` that does the same thing shown next to it.
+    ///
+    /// Returns whether it was clicked. Disabled items are greyed out and can't be clicked.
+    pub fn item(
+        &mut self,
+        label: &str,
+        shortcut: Option<&egui::KeyboardShortcut>,
+        enabled: bool,
+    ) -> bool {
+        let tokens = self.ui.tokens();
+        let mut button = egui::Button::new(label);
+        if let Some(shortcut) = shortcut {
+            button = button.shortcut_text(
+                egui::RichText::new(format_shortcut(shortcut)).color(tokens.text_subdued),
+            );
+        }
+        let clicked = self.ui.add_enabled(enabled, button).clicked();
+        if clicked {
+            *self.clicked = Some(label.to_owned());
+            self.ui.close();
+        }
+        clicked
+    }
+
+    pub fn separator(&mut self) {
+        self.ui.separator();
+    }
+
+    /// An item that opens a nested menu when hovered, with the items `build` adds.
+    pub fn submenu(&mut self, label: &str, build: impl FnOnce(&mut MenuBuilder<'_>)) {
+        let clicked = &mut *self.clicked;
+        self.ui
+            .menu_button(label, |ui| build(&mut MenuBuilder { ui, clicked }));
+    }
+
+    /// The menu's ui, for anything that isn't an item.
+    pub fn ui(&mut self) -> &mut Ui {
+        self.ui
+    }
+}
+
+/// What the user picked in a [`recent_files_menu`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecentFileAction {
+    Open(PathBuf),
+    /// A file that no longer exists was picked, so it should be forgotten.
+    Remove(PathBuf),
+    Clear,
+}
+
+/// Widest a recent file item gets before its path is cut short with `…`.
+const RECENT_FILE_MAX_WIDTH: f32 = 320.0;
+
+/// Recently opened `paths` as menu items, most recent first, followed by an item to clear them.
+///
+/// Items show the file name and its folder, shortened to fit, with the full path on hover.
+/// Files that no longer exist are greyed out, and picking one asks for it to be removed.
+pub fn recent_files_menu(ui: &mut Ui, paths: &[PathBuf]) -> Option<RecentFileAction> {
+    let tokens = ui.tokens();
+    if paths.is_empty() {
+        align_non_button_menu_items(ui, |ui| {
+            ui.label(egui::RichText::new("No recent files").color(tokens.text_subdued));
+        });
+        return None;
+    }
+
+    ui.set_max_width(RECENT_FILE_MAX_WIDTH);
+    let mut action = None;
+    for path in paths {
+        let name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let folder = path
+            .parent()
+            .map(|folder| folder.display().to_string())
+            .unwrap_or_default();
+
+        // Files on the web aren't on disk at all
+        let exists = cfg!(target_arch = "wasm32") || path.exists();
+        let (name_color, hover_text) = if exists {
+            (tokens.text_default, path.display().to_string())
+        } else {
+            (
+                tokens.text_subdued,
+                format!("{} no longer exists. Click to remove it.", path.display()),
+            )
+        };
+        let button = egui::Button::new(egui::RichText::new(name).color(name_color))
+            .right_text(egui::RichText::new(folder).color(tokens.text_subdued))
+            .truncate();
+        if ui.add(button).on_hover_text(hover_text).clicked() {
+            action = Some(if exists {
+                RecentFileAction::Open(path.clone())
+            } else {
+                RecentFileAction::Remove(path.clone())
+            });
+            ui.close();
+        }
+    }
+
+    ui.separator();
+    if ui.button("Clear Recent Files").clicked() {
+        action = Some(RecentFileAction::Clear);
+        ui.close();
+    }
+    action
+}
+
+/// Format a shortcut for menus and tooltips, like `Ctrl+Shift+P`, or `⇧⌘P` on macOS.
+///
+/// Modifiers are always in the platform's canonical order: `⌃⌥⇧⌘` on macOS, and
+/// `Ctrl+Alt+Shift` elsewhere.
+pub fn format_shortcut(shortcut: &egui::KeyboardShortcut) -> String {
+    let egui::KeyboardShortcut {
+        modifiers,
+        logical_key,
+    } = *shortcut;
+    let key = logical_key.symbol_or_name();
+
+    if cfg!(target_os = "macos") {
+        let mut text = String::new();
+        for (held, symbol) in [
+            (modifiers.ctrl, "⌃"),
+            (modifiers.alt, "⌥"),
+            (modifiers.shift, "⇧"),
+            (modifiers.mac_cmd || modifiers.command, "⌘"),
+        ] {
+            if held {
+                text.push_str(symbol);
+            }
+        }
+        text.push_str(key);
+        text
+    } else {
+        let mut parts = Vec::new();
+        for (held, name) in [
+            (modifiers.ctrl || modifiers.command, "Ctrl"),
+            (modifiers.alt, "Alt"),
+            (modifiers.shift, "Shift"),
+        ] {
+            if held {
+                parts.push(name);
+            }
+        }
+        parts.push(key);
+        parts.join("+")
+    }
+}
@@ -0,0 +1,136 @@
+//! A scaled-down overview of a large canvas, see [`super::UiExt::minimap`].
+
+use egui::emath::TSTransform;
+
+use super::HasDesignTokens as _;
+
+/// Longest side of the minimap, in points.
+const MAX_SIZE: f32 = 160.0;
+
+/// Show all of `content_rect` scaled down to fit [`MAX_SIZE`], with `viewport` outlined on top.
+///
+/// Dragging the outline moves `viewport`, and clicking or dragging elsewhere centers it there.
+pub(super) fn show(
+    ui: &mut egui::Ui,
+    content_rect: egui::Rect,
+    viewport: &mut egui::Rect,
+    render_thumbnail: impl Fn(&mut egui::Ui),
+) -> egui::Response {
+    let tokens = ui.tokens();
+    let id = ui.auto_id_with("minimap");
+
+    // Keep the content's aspect ratio, within the space there is
+    let max_size = egui::Vec2::splat(MAX_SIZE).min(ui.available_size());
+    let scale = if content_rect.is_positive() {
+        (max_size / content_rect.size()).min_elem()
+    } else {
+        1.0
+    };
+    let size = (content_rect.size() * scale).max(egui::Vec2::splat(1.0));
+    let (rect, mut response) = ui.allocate_exact_size(size, egui::Sense::click_and_drag());
+
+    // Content coordinates to minimap coordinates, and back
+    let to_minimap = TSTransform::new(
+        rect.min.to_vec2() - content_rect.min.to_vec2() * scale,
+        scale,
+    );
+    let to_content = to_minimap.inverse();
+
+    let grab_offset_id = id.with("grab_offset");
+    if let Some(pointer) = response.interact_pointer_pos() {
+        let pointer = to_content * pointer;
+        if response.drag_started() {
+            // Grabbing the outline keeps it under the same spot of the pointer, anywhere else jumps
+            let offset = if viewport.contains(pointer) {
+                viewport.center() - pointer
+            } else {
+                egui::Vec2::ZERO
+            };
+            ui.data_mut(|data| data.insert_temp(grab_offset_id, offset));
+        }
+        if response.dragged() || response.clicked() {
+            let offset = ui
+                .data(|data| data.get_temp::<egui::Vec2>(grab_offset_id))
+                .filter(|_| response.dragged())
+                .unwrap_or_default();
+            let center = pointer + offset;
+            let moved = keep_inside(
+                egui::Rect::from_center_size(center, viewport.size()),
+                content_rect,
+            );
+            if moved != *viewport {
+                *viewport = moved;
+                response.mark_changed();
+            }
+        }
+    }
+    if response.drag_stopped() {
+        ui.data_mut(|data| data.remove::<egui::Vec2>(grab_offset_id));
+    }
+    if response.hovered() || response.dragged() {
+        ui.ctx().set_cursor_icon(if response.dragged() {
+            egui::CursorIcon::Grabbing
+        } else {
+            egui::CursorIcon::Grab
+        });
+    }
+
+    if !ui.is_rect_visible(rect) {
+        return response;
+    }
+
+    ui.painter().rect_filled(
+        rect,
+        tokens.small_corner_radius(),
+        ui.visuals().extreme_bg_color,
+    );
+
+    // Draw the thumbnail on its own layer, scaled down by egui when painting
+    let layer_id = egui::LayerId::new(ui.layer_id().order, id.with("thumbnail"));
+    ui.ctx().set_sublayer(ui.layer_id(), layer_id);
+    ui.ctx().set_transform_layer(layer_id, to_minimap);
+    let mut thumbnail = ui.new_child(
+        egui::UiBuilder::new()
+            .layer_id(layer_id)
+            .max_rect(content_rect)
+            .sense(egui::Sense::hover()),
+    );
+    thumbnail.set_clip_rect(to_content * ui.clip_rect().intersect(rect));
+    render_thumbnail(&mut thumbnail);
+
+    // The outline goes on the thumbnail's layer, to be drawn over it. Sizes are in content
+    // coordinates there.
+    thumbnail.painter().rect(
+        *viewport,
+        0.0,
+        tokens.tile_drag_preview_color,
+        egui::Stroke::new(
+            tokens.tile_drag_preview_stroke.width / scale,
+            tokens.tile_drag_preview_stroke.color,
+        ),
+        egui::StrokeKind::Inside,
+    );
+    ui.painter().rect_stroke(
+        rect,
+        tokens.small_corner_radius(),
+        egui::Stroke::new(1.0, tokens.widget_noninteractive_bg_stroke),
+        egui::StrokeKind::Outside,
+    );
+
+    response
+}
+
+/// Move `rect` the least it takes to stay inside `bounds`, or to line up with its top left corner
+/// when it is larger.
+fn keep_inside(rect: egui::Rect, bounds: egui::Rect) -> egui::Rect {
+    let mut offset = egui::Vec2::ZERO;
+    for axis in 0..2 {
+        if rect.max[axis] > bounds.max[axis] {
+            offset[axis] = bounds.max[axis] - rect.max[axis];
+        }
+        if rect.min[axis] + offset[axis] < bounds.min[axis] {
+            offset[axis] = bounds.min[axis] - rect.min[axis];
+        }
+    }
+    rect.translate(offset)
+}
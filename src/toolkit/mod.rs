@@ -1,15 +1,53 @@
-mod color_table;
+pub mod animation;
+pub mod color_table;
+pub mod colormap;
 mod design_tokens;
 
+pub mod breadcrumbs;
 pub mod button;
+pub mod chip;
+pub mod console;
+pub mod dialog;
+pub mod drop_overlay;
+pub mod error;
+mod fonts;
 pub mod icons;
+pub mod inspector;
+pub mod markdown;
 pub mod menu;
+mod minimap;
+pub mod plot;
+pub mod property_grid;
+mod reorderable_list;
+pub mod settings;
+pub mod shortcuts;
+mod smooth_scroll;
+pub mod split;
+pub mod status_bar;
+pub mod table;
+pub mod tabs;
+mod text_measure;
+pub mod tooltip;
 mod ui_ext;
+pub mod vector_edit;
+mod virtual_list;
 
+pub use animation::{AnimatedValue, Easing};
+pub use design_tokens::{SceneColors, TableStyle, linear_rgba};
+pub use fonts::add_font;
+pub use menu::format_shortcut;
+pub use tooltip::TooltipExt;
 pub use ui_ext::UiExt;
 
 use design_tokens::{DesignTokens, design_tokens_of};
 
+/// Below this width, in points, only a single narrow column fits, like a floating palette.
+pub const BREAKPOINT_COMPACT: f32 = 480.0;
+/// Below this width there is room for content, but not for a sidebar next to it.
+pub const BREAKPOINT_MEDIUM: f32 = 768.0;
+/// From this width on, sidebars and content fit side by side comfortably.
+pub const BREAKPOINT_WIDE: f32 = 1024.0;
+
 /// Apply the Rerun design tokens to the given egui context and install image loaders.
 pub fn apply_style_and_install_loaders(egui_ctx: &egui::Context) {
     egui_extras::install_image_loaders(egui_ctx);
@@ -19,6 +57,66 @@ pub fn apply_style_and_install_loaders(egui_ctx: &egui::Context) {
     });
 
     set_themes(egui_ctx);
+    set_feathering(egui_ctx, Some(default_feathering()));
+}
+
+/// Anti-alias the edges of shapes by fading them out over `size_in_pixels` physical pixels, or
+/// turn that off with `None` for hard edges.
+///
+/// Text is unaffected. Larger sizes look blurry, see [`default_feathering`] for the toolkit's.
+pub fn set_feathering(egui_ctx: &egui::Context, size_in_pixels: Option<f32>) {
+    egui_ctx.tessellation_options_mut(|options| {
+        options.feathering = size_in_pixels.is_some();
+        if let Some(size_in_pixels) = size_in_pixels {
+            options.feathering_size_in_pixels = size_in_pixels.max(0.0);
+        }
+    });
+}
+
+/// See [`set_feathering`].
+pub fn feathering(egui_ctx: &egui::Context) -> Option<f32> {
+    egui_ctx.tessellation_options(|options| {
+        options
+            .feathering
+            .then_some(options.feathering_size_in_pixels)
+    })
+}
+
+/// The feathering that keeps token dividers crisp, see [`DesignTokens::feathering_size_in_pixels`].
+pub fn default_feathering() -> f32 {
+    DesignTokens::feathering_size_in_pixels()
+}
+
+/// Follow `preference`, e.g. [`egui::ThemePreference::System`] to track the OS light/dark setting.
+///
+/// Also call this when the system theme changes, so the styles are re-applied.
+pub fn set_theme_preference(egui_ctx: &egui::Context, preference: egui::ThemePreference) {
+    egui_ctx.set_theme(preference);
+    set_themes(egui_ctx);
+}
+
+/// Lay out the toolkit's rows right to left, for Arabic, Hebrew and other RTL languages.
+///
+/// This mirrors the order of widgets in [`UiExt::horizontal_directed`] and the helpers built on
+/// it, like labelled rows and the status bar. egui itself has no bidirectional text support: the
+/// glyphs of a single label are always placed left to right and are not reordered or shaped, so
+/// numbers formatted with [`crate::misc::format_f64`] keep reading left to right either way.
+pub fn set_right_to_left(egui_ctx: &egui::Context, right_to_left: bool) {
+    egui_ctx.data_mut(|data| data.insert_temp(right_to_left_id(), right_to_left));
+}
+
+/// See [`set_right_to_left`].
+pub fn is_right_to_left(egui_ctx: &egui::Context) -> bool {
+    egui_ctx.data(|data| data.get_temp(right_to_left_id()).unwrap_or(false))
+}
+
+fn right_to_left_id() -> egui::Id {
+    egui::Id::new("toolkit_right_to_left")
+}
+
+/// The [`SceneColors`] of `theme`, for the renderer to match the ui with.
+pub fn scene_colors(theme: egui::Theme) -> SceneColors {
+    design_tokens_of(theme).scene_colors()
 }
 
 pub trait HasDesignTokens {
@@ -61,3 +159,61 @@ fn set_themes(egui_ctx: &egui::Context) {
         egui_ctx.set_style_of(theme, style);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Vertices of a one point high divider, as `egui_ctx` tessellates it.
+    fn divider_vertices(egui_ctx: &egui::Context) -> Vec<egui::epaint::Vertex> {
+        let rect = egui::Rect::from_min_size(egui::pos2(10.0, 10.0), egui::vec2(100.0, 1.0));
+        let shapes = vec![egui::epaint::ClippedShape {
+            clip_rect: egui::Rect::EVERYTHING,
+            shape: egui::Shape::rect_filled(rect, 0.0, egui::Color32::WHITE),
+        }];
+        egui_ctx
+            .tessellate(shapes, 2.0)
+            .into_iter()
+            .flat_map(|clipped| match clipped.primitive {
+                egui::epaint::Primitive::Mesh(mesh) => mesh.vertices,
+                egui::epaint::Primitive::Callback(_) => Vec::new(),
+            })
+            .collect()
+    }
+
+    /// Feathering happens entirely in egui's tessellator, which fades the edges out through
+    /// transparent vertices around the shape. The gpu only rasterizes the mesh it is given, so
+    /// the mesh shows the before and after without needing an adapter.
+    #[test]
+    fn feathering_can_be_tuned_and_turned_off() {
+        let egui_ctx = egui::Context::default();
+        apply_style_and_install_loaders(&egui_ctx);
+        // Tessellating needs the fonts, which the first frame loads
+        let _ = egui_ctx.run_ui(egui::RawInput::default(), |_| {});
+        assert_eq!(feathering(&egui_ctx), Some(default_feathering()));
+        let feathered = divider_vertices(&egui_ctx);
+        assert!(
+            feathered.iter().any(|vertex| vertex.color.a() == 0),
+            "No faded outline with feathering"
+        );
+
+        set_feathering(&egui_ctx, None);
+        assert_eq!(feathering(&egui_ctx), None);
+        // Without feathering there is no fading outline around the edges
+        let crisp = divider_vertices(&egui_ctx);
+        assert!(
+            crisp.len() < feathered.len(),
+            "{} vertices, {} with feathering",
+            crisp.len(),
+            feathered.len()
+        );
+        assert!(
+            crisp
+                .iter()
+                .all(|vertex| vertex.color == egui::Color32::WHITE)
+        );
+
+        set_feathering(&egui_ctx, Some(-1.0));
+        assert_eq!(feathering(&egui_ctx), Some(0.0));
+    }
+}
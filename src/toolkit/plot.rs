@@ -0,0 +1,232 @@
+//! Line, scatter and bar charts with [`egui_plot`], styled and formatted like the rest of the ui.
+//!
+//! ```ignore
+//! plot::Chart::new("frame_times")
+//!     .y_unit("s")
+//!     .line("CPU", cpu_times)
+//!     .line("GPU", gpu_times)
+//!     .show(ui);
+//! ```
+
+use std::ops::RangeInclusive;
+
+use super::HasDesignTokens as _;
+use crate::misc::{FloatFormatOptions, format_with_decimals_in_range, format_with_unit};
+
+/// How a [`Series`] is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeriesKind {
+    Line,
+    Scatter,
+    Bar,
+}
+
+/// Named data in a [`Chart`].
+#[derive(Clone, Debug)]
+pub struct Series {
+    pub name: String,
+    pub kind: SeriesKind,
+    /// `[x, y]` pairs. Bars stand on zero at each `x`.
+    pub points: Vec<[f64; 2]>,
+    /// Picked from the name with [`super::DesignTokens::color_for_hash`] when `None`, so a series
+    /// keeps its color across charts.
+    pub color: Option<egui::Color32>,
+}
+
+/// A chart of one or more [`Series`], with token colors, a legend, and a readout of the point
+/// under the pointer.
+pub struct Chart {
+    id_salt: egui::Id,
+    series: Vec<Series>,
+    x_unit: Option<String>,
+    y_unit: Option<String>,
+    legend: bool,
+    height: Option<f32>,
+}
+
+impl Chart {
+    pub fn new(id_salt: impl std::hash::Hash) -> Self {
+        Self {
+            id_salt: egui::Id::new(id_salt),
+            series: Vec::new(),
+            x_unit: None,
+            y_unit: None,
+            legend: true,
+            height: None,
+        }
+    }
+
+    /// Points joined by a line, in order.
+    pub fn line(self, name: impl Into<String>, points: impl Into<Vec<[f64; 2]>>) -> Self {
+        self.series(SeriesKind::Line, name, points)
+    }
+
+    /// Points on their own.
+    pub fn scatter(self, name: impl Into<String>, points: impl Into<Vec<[f64; 2]>>) -> Self {
+        self.series(SeriesKind::Scatter, name, points)
+    }
+
+    /// A bar for each point, as wide as allows for the closest two.
+    pub fn bar(self, name: impl Into<String>, points: impl Into<Vec<[f64; 2]>>) -> Self {
+        self.series(SeriesKind::Bar, name, points)
+    }
+
+    /// Add a [`Series`] with its own color, or any other settings.
+    pub fn with_series(mut self, series: Series) -> Self {
+        self.series.push(series);
+        self
+    }
+
+    /// Label x values in `unit`, with an SI prefix, like `1.5 kHz` for `1500` in `"Hz"`.
+    pub fn x_unit(mut self, unit: impl Into<String>) -> Self {
+        self.x_unit = Some(unit.into());
+        self
+    }
+
+    /// Label y values in `unit`, see [`Self::x_unit`].
+    pub fn y_unit(mut self, unit: impl Into<String>) -> Self {
+        self.y_unit = Some(unit.into());
+        self
+    }
+
+    /// Name the series in a corner of the chart, where they can be hidden. On by default.
+    pub fn legend(mut self, legend: bool) -> Self {
+        self.legend = legend;
+        self
+    }
+
+    /// Height of the chart in points, rather than all of the available height.
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn show(self, ui: &mut egui::Ui) -> egui::Response {
+        let tokens = ui.tokens();
+        let Self {
+            id_salt,
+            series,
+            x_unit,
+            y_unit,
+            legend,
+            height,
+        } = self;
+
+        let x_ticks = x_unit.clone();
+        let y_ticks = y_unit.clone();
+        let mut plot = egui_plot::Plot::new(id_salt)
+            .x_axis_formatter(move |mark, _range| {
+                format_tick(mark.value, mark.step_size, x_ticks.as_deref())
+            })
+            .y_axis_formatter(move |mark, _range| {
+                format_tick(mark.value, mark.step_size, y_ticks.as_deref())
+            })
+            .label_formatter(move |name, point| {
+                let x = format_readout(point.x, x_unit.as_deref());
+                let y = format_readout(point.y, y_unit.as_deref());
+                if name.is_empty() {
+                    format!("x: {x}\ny: {y}")
+                } else {
+                    format!("{name}\nx: {x}\ny: {y}")
+                }
+            });
+        if legend {
+            plot = plot.legend(egui_plot::Legend::default());
+        }
+        if let Some(height) = height {
+            plot = plot.height(height);
+        }
+
+        ui.scope(|ui| {
+            // egui_plot takes its background and frame from these, and its grid from the text color
+            let visuals = ui.visuals_mut();
+            visuals.extreme_bg_color = tokens.extreme_bg_color;
+            visuals.widgets.noninteractive.bg_stroke =
+                egui::Stroke::new(1.0, tokens.widget_noninteractive_bg_stroke);
+
+            plot.show(ui, |plot_ui| {
+                for series in series {
+                    let color = series.color.unwrap_or_else(|| {
+                        tokens.color_for_hash(egui::Id::new(&series.name).value())
+                    });
+                    match series.kind {
+                        SeriesKind::Line => plot_ui
+                            .line(egui_plot::Line::new(series.name, series.points).color(color)),
+                        SeriesKind::Scatter => plot_ui.points(
+                            egui_plot::Points::new(series.name, series.points)
+                                .color(color)
+                                .radius(3.0),
+                        ),
+                        SeriesKind::Bar => {
+                            let width = 0.8 * closest_spacing(&series.points);
+                            let bars = series
+                                .points
+                                .iter()
+                                .map(|&[x, y]| egui_plot::Bar::new(x, y).width(width))
+                                .collect();
+                            plot_ui.bar_chart(
+                                egui_plot::BarChart::new(series.name, bars).color(color),
+                            );
+                        }
+                    }
+                }
+            })
+            .response
+        })
+        .inner
+    }
+
+    fn series(
+        mut self,
+        kind: SeriesKind,
+        name: impl Into<String>,
+        points: impl Into<Vec<[f64; 2]>>,
+    ) -> Self {
+        self.series.push(Series {
+            name: name.into(),
+            kind,
+            points: points.into(),
+            color: None,
+        });
+        self
+    }
+}
+
+/// An axis label at `value`, with just enough decimals to tell it from its neighbors `step` away.
+fn format_tick(value: f64, step: f64, unit: Option<&str>) -> String {
+    match unit {
+        Some(unit) => format_with_unit(
+            value,
+            unit,
+            FloatFormatOptions::DEFAULT_f64.with_decimals(2),
+        ),
+        None => {
+            let decimals = (-step.log10()).ceil().clamp(0.0, 15.0) as usize;
+            format_with_decimals_in_range(value, 0..=decimals)
+        }
+    }
+}
+
+/// A value under the pointer, more precisely than the axis labels.
+fn format_readout(value: f64, unit: Option<&str>) -> String {
+    const DECIMALS: RangeInclusive<usize> = 0..=3;
+    match unit {
+        Some(unit) => format_with_unit(
+            value,
+            unit,
+            FloatFormatOptions::DEFAULT_f64.with_decimals(*DECIMALS.end()),
+        ),
+        None => format_with_decimals_in_range(value, DECIMALS),
+    }
+}
+
+/// The smallest distance between two `x`s, or one when there aren't two.
+fn closest_spacing(points: &[[f64; 2]]) -> f64 {
+    let mut xs: Vec<f64> = points.iter().map(|[x, _]| *x).collect();
+    xs.sort_by(f64::total_cmp);
+    xs.windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .filter(|spacing| *spacing > 0.0)
+        .min_by(f64::total_cmp)
+        .unwrap_or(1.0)
+}
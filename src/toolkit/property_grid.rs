@@ -0,0 +1,150 @@
+//! Labelled rows with their values lined up, see [`super::UiExt::property_grid`].
+
+use super::HasDesignTokens as _;
+use super::UiExt as _;
+
+/// Rows of a property grid, each a label on the left and a value on the right.
+///
+/// The label column is as wide as the widest label. That is only known once every row was added,
+/// so the width from the previous frame is used, and the pass is discarded when a label turns
+/// out to be wider.
+pub struct PropertyGrid<'a> {
+    ui: &'a mut egui::Ui,
+    id: egui::Id,
+    /// Width of the label column, as measured last frame.
+    label_width: f32,
+    /// Width of the widest label so far this frame.
+    max_label_width: f32,
+    separators: bool,
+    num_rows: usize,
+}
+
+impl<'a> PropertyGrid<'a> {
+    pub(super) fn new(ui: &'a mut egui::Ui, id: egui::Id) -> Self {
+        let label_width = ui.data(|data| data.get_temp::<f32>(id).unwrap_or(0.0));
+        Self {
+            ui,
+            id,
+            label_width,
+            max_label_width: 0.0,
+            separators: false,
+            num_rows: 0,
+        }
+    }
+
+    /// Draw a line between rows.
+    pub fn separators(&mut self, separators: bool) -> &mut Self {
+        self.separators = separators;
+        self
+    }
+
+    /// Add a row with `label` in the left column, and whatever `add_value` adds in the right one.
+    pub fn row<R>(
+        &mut self,
+        label: impl Into<egui::WidgetText>,
+        add_value: impl FnOnce(&mut egui::Ui) -> R,
+    ) -> R {
+        if self.separators && self.num_rows > 0 {
+            self.ui.separator();
+        }
+        self.num_rows += 1;
+
+        let tokens = self.ui.tokens();
+        let label_width = &mut self.label_width;
+        let max_label_width = &mut self.max_label_width;
+        self.ui
+            .horizontal_directed(|ui| {
+                let galley = label.into().into_galley(
+                    ui,
+                    Some(egui::TextWrapMode::Extend),
+                    f32::INFINITY,
+                    egui::TextStyle::Body,
+                );
+                *max_label_width = max_label_width.max(galley.size().x);
+                *label_width = label_width.max(galley.size().x);
+
+                let size = egui::vec2(*label_width, galley.size().y);
+                let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                // Labels line up along the edge the row starts from
+                let pos = if ui.is_right_to_left() {
+                    rect.right_top() - egui::vec2(galley.size().x, 0.0)
+                } else {
+                    rect.left_top()
+                };
+                let text_color = ui.visuals().text_color();
+                ui.painter().galley(pos, galley, text_color);
+
+                ui.add_space(tokens.view_padding() as f32);
+                add_value(ui)
+            })
+            .inner
+    }
+
+    pub(super) fn finish(self) {
+        let remembered = self.ui.data(|data| data.get_temp::<f32>(self.id));
+        if remembered == Some(self.max_label_width) {
+            return;
+        }
+        self.ui
+            .data_mut(|data| data.insert_temp(self.id, self.max_label_width));
+        if remembered.is_none_or(|width| width < self.max_label_width) {
+            // Rows above the widest label were laid out too narrow
+            self.ui
+                .ctx()
+                .request_discard("property grid label column grew");
+        } else {
+            self.ui.ctx().request_repaint();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::toolkit::UiExt as _;
+
+    const WIDTH: f32 = 400.0;
+
+    /// Lay out a property grid with a single row, and return the rect of its value.
+    fn value_rect(right_to_left: bool) -> egui::Rect {
+        let ctx = egui::Context::default();
+        crate::toolkit::set_right_to_left(&ctx, right_to_left);
+        let mut value_rect = egui::Rect::NOTHING;
+        // The label column is measured in the first frame
+        for _ in 0..2 {
+            let input = egui::RawInput {
+                screen_rect: Some(egui::Rect::from_min_size(
+                    egui::Pos2::ZERO,
+                    egui::vec2(WIDTH, 200.0),
+                )),
+                ..Default::default()
+            };
+            let _ = ctx.run_ui(input, |ui| {
+                ui.property_grid("grid", |grid| {
+                    value_rect = grid.row("Temperature", |ui| ui.label("5 778 K")).rect;
+                });
+            });
+        }
+        value_rect
+    }
+
+    #[test]
+    fn row_mirrors_right_to_left() {
+        let left_to_right = value_rect(false);
+        let right_to_left = value_rect(true);
+
+        // The value comes after the label, so right of it, or left of it when right to left
+        assert!(
+            left_to_right.left() > 40.0,
+            "value at {left_to_right:?} overlaps the label"
+        );
+        assert!(left_to_right.right() < WIDTH / 2.0);
+        assert!(
+            right_to_left.right() < WIDTH - 40.0,
+            "value at {right_to_left:?} overlaps the label"
+        );
+        assert!(right_to_left.left() > WIDTH / 2.0);
+
+        // Mirroring moves the number, but lays out its text the same
+        assert!((left_to_right.width() - right_to_left.width()).abs() < 0.5);
+    }
+}
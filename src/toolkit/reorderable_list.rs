@@ -0,0 +1,182 @@
+//! A list whose rows can be dragged into a different order, see
+//! [`super::UiExt::reorderable_list`].
+
+use super::HasDesignTokens as _;
+use super::UiExt as _;
+use super::animation::AnimatedValue;
+use super::icons;
+
+/// What is carried while a row is dragged, through egui's drag-and-drop payload.
+#[derive(Clone, Copy, Debug)]
+struct Payload {
+    list: egui::Id,
+    index: usize,
+}
+
+/// Layout remembered between frames, in temp data.
+#[derive(Clone, Debug, Default)]
+struct State {
+    /// Where each row was drawn last frame, to work out where a dragged row would drop.
+    rows: Vec<egui::Rect>,
+    /// The gap opening up above each row, plus one below the last.
+    gaps: Vec<AnimatedValue<f32>>,
+    /// Where in its row the dragged one was grabbed.
+    grab_offset: egui::Vec2,
+}
+
+/// How long the gap takes to open and close, in seconds.
+const GAP_DURATION: f32 = 0.12;
+
+pub(super) fn show<T>(
+    ui: &mut egui::Ui,
+    items: &mut Vec<T>,
+    render_item: impl Fn(&mut egui::Ui, &T),
+) -> egui::Response {
+    let tokens = ui.tokens();
+    let id = ui.auto_id_with("reorderable_list");
+    let mut state = ui
+        .data(|data| data.get_temp::<State>(id))
+        .unwrap_or_default();
+    state.rows.resize(items.len(), egui::Rect::NOTHING);
+    state.gaps.resize_with(items.len() + 1, || {
+        AnimatedValue::new(0.0).with_duration(GAP_DURATION)
+    });
+
+    // Escape clears the payload, which cancels the drag without reordering
+    let dragged = egui::DragAndDrop::payload::<Payload>(ui.ctx())
+        .filter(|payload| payload.list == id && payload.index < items.len())
+        .map(|payload| payload.index);
+    let pointer = ui.ctx().pointer_interact_pos();
+
+    // Count the other rows above the pointer, so dropping above the first or below the last
+    // row lands at either end
+    let drop_index = dragged.zip(pointer).map(|(from, pointer)| {
+        state
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|&(index, rect)| index != from && rect.center().y < pointer.y)
+            .count()
+    });
+    let gap_height = dragged.map_or(0.0, |from| {
+        state.rows[from].height() + ui.spacing().item_spacing.y
+    });
+
+    let handle_size = tokens.small_icon_size;
+    let mut started = None;
+    let mut response = ui
+        .vertical(|ui| {
+            let mut slot = 0;
+            for (index, item) in items.iter().enumerate() {
+                if dragged == Some(index) {
+                    continue;
+                }
+                gap(
+                    ui,
+                    &mut state.gaps[slot],
+                    drop_index == Some(slot),
+                    gap_height,
+                );
+                slot += 1;
+
+                let row = ui.horizontal_directed(|ui| {
+                    let (rect, _) = ui.allocate_exact_size(handle_size, egui::Sense::hover());
+                    let handle = ui
+                        .interact(rect, id.with(index), egui::Sense::drag())
+                        .on_hover_cursor(egui::CursorIcon::Grab);
+                    let tint = if handle.hovered() {
+                        tokens.text_default
+                    } else {
+                        tokens.text_subdued
+                    };
+                    icons::DND_HANDLE.as_image().tint(tint).paint_at(ui, rect);
+                    render_item(ui, item);
+                    handle
+                });
+                state.rows[index] = row.response.rect;
+
+                let handle = row.inner;
+                handle.dnd_set_drag_payload(Payload { list: id, index });
+                if handle.drag_started()
+                    && let Some(pointer) = pointer
+                {
+                    started = Some(pointer - row.response.rect.min);
+                }
+            }
+            gap(
+                ui,
+                &mut state.gaps[slot],
+                drop_index == Some(slot),
+                gap_height,
+            );
+        })
+        .response;
+
+    if let Some(grab_offset) = started {
+        state.grab_offset = grab_offset;
+    }
+
+    if let (Some(from), Some(to), Some(pointer)) = (dragged, drop_index, pointer) {
+        if ui.input(|input| input.pointer.any_released()) {
+            egui::DragAndDrop::clear_payload(ui.ctx());
+            if from != to {
+                let item = items.remove(from);
+                items.insert(to, item);
+                response.mark_changed();
+            }
+            // The rows moved, so nothing remembered about them still holds
+            state = State::default();
+        } else {
+            // The dragged row follows the pointer, above everything else
+            let rect = state.rows[from];
+            egui::Area::new(id.with("dragged"))
+                .order(egui::Order::Tooltip)
+                .fixed_pos(pointer - state.grab_offset)
+                .interactable(false)
+                .show(ui.ctx(), |ui| {
+                    let frame = tokens.popup_frame(ui.style());
+                    frame.show(ui, |ui| {
+                        // As wide as the row it was lifted from
+                        ui.set_width(rect.width() - frame.total_margin().sum().x);
+                        ui.horizontal_directed(|ui| {
+                            let (handle_rect, _) =
+                                ui.allocate_exact_size(handle_size, egui::Sense::hover());
+                            icons::DND_HANDLE
+                                .as_image()
+                                .tint(tokens.text_default)
+                                .paint_at(ui, handle_rect);
+                            render_item(ui, &items[from]);
+                        });
+                    });
+                });
+        }
+    }
+
+    ui.data_mut(|data| data.insert_temp(id, state));
+    response
+}
+
+/// Space where a dragged row would drop, opening while `open` and closing otherwise.
+fn gap(ui: &mut egui::Ui, height: &mut AnimatedValue<f32>, open: bool, open_height: f32) {
+    height.set_target(if open { open_height } else { 0.0 });
+    let height = height.update_with_ctx(ui.ctx());
+    if height <= 0.0 {
+        return;
+    }
+
+    let tokens = ui.tokens();
+    let (rect, _) = ui.allocate_exact_size(
+        egui::vec2(
+            ui.available_width(),
+            (height - ui.spacing().item_spacing.y).max(0.0),
+        ),
+        egui::Sense::hover(),
+    );
+    ui.painter().rect(
+        rect,
+        tokens.small_corner_radius(),
+        tokens.tile_drag_preview_color,
+        tokens.tile_drag_preview_stroke,
+        egui::StrokeKind::Inside,
+    );
+}
@@ -0,0 +1,187 @@
+//! A preferences window: categories on the left, their settings on the right, and a search
+//! field that finds settings across all categories.
+
+use super::property_grid::PropertyGrid;
+use super::{HasDesignTokens as _, UiExt as _, split};
+
+/// Something with settings to show in a [`settings_window`].
+pub trait Settings {
+    /// The categories listed on the left, in order.
+    fn categories(&self) -> Vec<&'static str>;
+
+    /// Add the settings in `category`, each with [`SettingsPage::setting`].
+    fn category_ui(&mut self, category: &str, page: &mut SettingsPage<'_, '_>);
+}
+
+/// The settings of one category, as labelled rows.
+pub struct SettingsPage<'a, 'ui> {
+    /// `None` while only counting the settings that match the search.
+    grid: Option<&'a mut PropertyGrid<'ui>>,
+    /// Lowercase search query, empty to show everything.
+    query: &'a str,
+    matches: usize,
+    changed: bool,
+}
+
+impl SettingsPage<'_, '_> {
+    /// A setting named `label`, edited by the widget `add_value` adds.
+    ///
+    /// Skipped while searching for something else.
+    pub fn setting(
+        &mut self,
+        label: &str,
+        add_value: impl FnOnce(&mut egui::Ui) -> egui::Response,
+    ) {
+        if !self.query.is_empty() && !label.to_lowercase().contains(self.query) {
+            return;
+        }
+        self.matches += 1;
+        if let Some(grid) = &mut self.grid {
+            self.changed |= grid.row(label, add_value).changed();
+        }
+    }
+}
+
+/// What the window remembers between frames.
+#[derive(Clone, Debug)]
+struct WindowState {
+    category: usize,
+    query: String,
+    /// Share of the width taken by the category list.
+    ratio: f32,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            category: 0,
+            query: String::new(),
+            ratio: 0.3,
+        }
+    }
+}
+
+/// A window with `settings`, while `open`.
+///
+/// Pick a category on the left to see its settings on the right. Typing in the search field
+/// shows the matching settings of every category instead. Returns whether a setting changed, e.g.
+/// to save them.
+pub fn settings_window(ctx: &egui::Context, open: &mut bool, settings: &mut dyn Settings) -> bool {
+    let id = egui::Id::new("settings_window");
+    let mut state = ctx
+        .data(|data| data.get_temp::<WindowState>(id))
+        .unwrap_or_default();
+    let categories = settings.categories();
+    state.category = state.category.min(categories.len().saturating_sub(1));
+
+    let mut changed = false;
+    egui::Window::new("Settings")
+        .id(id)
+        .open(open)
+        .collapsible(false)
+        .default_size([560.0, 360.0])
+        .show(ctx, |ui| {
+            let tokens = ui.tokens();
+            ui.search_field(&mut state.query);
+            ui.separator();
+
+            let query = state.query.trim().to_lowercase();
+            let current = state.category;
+            let mut picked = None;
+            split::horizontal(
+                ui,
+                &mut state.ratio,
+                |ui| {
+                    egui::ScrollArea::vertical()
+                        .id_salt("categories")
+                        .show(ui, |ui| {
+                            for (index, category) in categories.iter().enumerate() {
+                                // Searching shows every category at once
+                                let selected = query.is_empty() && index == current;
+                                let response = ui.add(
+                                    egui::Button::selectable(selected, *category)
+                                        .frame_when_inactive(false),
+                                );
+                                if ui.focusable(response).clicked() {
+                                    picked = Some(index);
+                                }
+                            }
+                        });
+                },
+                |ui| {
+                    ui.add_space(tokens.view_padding() as f32);
+                    egui::ScrollArea::vertical()
+                        .id_salt("settings")
+                        .show(ui, |ui| {
+                            if query.is_empty() {
+                                if let Some(category) = categories.get(current) {
+                                    changed |= category_ui(ui, settings, category, "");
+                                }
+                                return;
+                            }
+
+                            let mut any = false;
+                            for category in &categories {
+                                // Searching for a category's name finds all of its settings
+                                let category_query = if category.to_lowercase().contains(&query) {
+                                    ""
+                                } else {
+                                    query.as_str()
+                                };
+                                if count_matches(settings, category, category_query) == 0 {
+                                    continue;
+                                }
+                                any = true;
+                                ui.label(egui::RichText::new(*category).color(tokens.text_strong));
+                                changed |= category_ui(ui, settings, category, category_query);
+                                ui.add_space(tokens.view_padding() as f32);
+                            }
+                            if !any {
+                                ui.label(
+                                    egui::RichText::new("No matching settings")
+                                        .color(tokens.text_subdued),
+                                );
+                            }
+                        });
+                },
+            );
+
+            if let Some(index) = picked {
+                state.category = index;
+                state.query.clear();
+            }
+        });
+
+    ctx.data_mut(|data| data.insert_temp(id, state));
+    changed
+}
+
+/// Show the settings of `category` matching `query`, and return whether any changed.
+fn category_ui(
+    ui: &mut egui::Ui,
+    settings: &mut dyn Settings,
+    category: &str,
+    query: &str,
+) -> bool {
+    ui.property_grid(category, |grid| {
+        let mut page = SettingsPage {
+            grid: Some(grid),
+            query,
+            matches: 0,
+            changed: false,
+        };
+        settings.category_ui(category, &mut page);
+        page.changed
+    })
+}
+
+fn count_matches(settings: &mut dyn Settings, category: &str, query: &str) -> usize {
+    let mut page = SettingsPage {
+        grid: None,
+        query,
+        matches: 0,
+        changed: false,
+    };
+    settings.category_ui(category, &mut page);
+    page.matches
+}
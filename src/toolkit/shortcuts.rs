@@ -0,0 +1,205 @@
+//! Every keyboard shortcut the app responds to, in one place, and a cheat sheet listing them.
+
+use super::UiExt as _;
+use super::menu::format_shortcut;
+
+/// Opens and closes the [`cheat_sheet`].
+pub const CHEAT_SHEET_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::Questionmark);
+
+/// A shortcut, and what it does.
+#[derive(Clone, Debug)]
+pub struct Shortcut {
+    /// Heading the shortcut is listed under, like `"Edit"`.
+    pub category: &'static str,
+    pub description: &'static str,
+    pub shortcut: egui::KeyboardShortcut,
+}
+
+/// The keyboard shortcuts to list in the [`cheat_sheet`].
+///
+/// Registering a shortcut doesn't bind it, whatever handles the key still has to check for it,
+/// either with [`Self::pressed`] or by consuming it from egui's input.
+#[derive(Clone, Debug, Default)]
+pub struct Shortcuts {
+    shortcuts: Vec<Shortcut>,
+}
+
+impl Shortcuts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// List `shortcut` under `category`. Categories are shown in the order they were first used.
+    pub fn register(
+        &mut self,
+        category: &'static str,
+        description: &'static str,
+        shortcut: egui::KeyboardShortcut,
+    ) -> &mut Self {
+        self.shortcuts.push(Shortcut {
+            category,
+            description,
+            shortcut,
+        });
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Shortcut> {
+        self.shortcuts.iter()
+    }
+
+    /// The shortcut registered for `description`, e.g. for a menu item's shortcut text.
+    pub fn find(&self, description: &str) -> Option<&Shortcut> {
+        self.shortcuts
+            .iter()
+            .find(|shortcut| shortcut.description == description)
+    }
+
+    /// Which registered shortcut a press of `key` with `modifiers` held is, if any.
+    ///
+    /// A shortcut whose modifiers match exactly wins, so `Ctrl+Shift+Z` is redo even with
+    /// `Ctrl+Z` registered. Otherwise extra modifiers are ignored, like in
+    /// [`egui::InputState::consume_shortcut`].
+    pub fn pressed(
+        &self,
+        modifiers: egui::Modifiers,
+        key: egui::Key,
+    ) -> Option<egui::KeyboardShortcut> {
+        let with_key = || {
+            self.shortcuts
+                .iter()
+                .map(|shortcut| shortcut.shortcut)
+                .filter(move |shortcut| shortcut.logical_key == key)
+        };
+        with_key()
+            .find(|shortcut| modifiers.matches_exact(shortcut.modifiers))
+            .or_else(|| with_key().find(|shortcut| modifiers.matches_logically(shortcut.modifiers)))
+    }
+
+    /// Shortcuts grouped by category, in the order the categories were first used.
+    pub fn by_category(&self) -> Vec<(&'static str, Vec<&Shortcut>)> {
+        let mut categories: Vec<(&'static str, Vec<&Shortcut>)> = Vec::new();
+        for shortcut in &self.shortcuts {
+            match categories
+                .iter_mut()
+                .find(|(category, _)| *category == shortcut.category)
+            {
+                Some((_, shortcuts)) => shortcuts.push(shortcut),
+                None => categories.push((shortcut.category, vec![shortcut])),
+            }
+        }
+        categories
+    }
+}
+
+/// A modal listing `shortcuts` by category, while `open`, with a search field to filter them.
+///
+/// Closes on [`CHEAT_SHEET_SHORTCUT`], Escape, or a click outside of it.
+pub fn cheat_sheet(ctx: &egui::Context, open: &mut bool, shortcuts: &Shortcuts) {
+    if !*open {
+        return;
+    }
+    let id = egui::Id::new("shortcut_cheat_sheet");
+    let modal = egui::Modal::new(id).show(ctx, |ui| {
+        let tokens = ui.tokens();
+        ui.set_width(tokens.default_modal_width);
+        ui.heading("Keyboard Shortcuts");
+        ui.add_space(tokens.view_padding() as f32 / 2.0);
+
+        let query_id = id.with("query");
+        let saved_query = ui.data(|data| data.get_temp::<String>(query_id));
+        let just_opened = saved_query.is_none();
+        let mut query = saved_query.unwrap_or_default();
+        let search = ui.search_field(&mut query);
+        // Ready to type into as soon as it opens. After that, Escape leaves the field and the next
+        // one closes the cheat sheet.
+        if just_opened {
+            search.request_focus();
+        }
+        let needle = query.to_lowercase();
+
+        egui::ScrollArea::vertical()
+            .max_height(ui.ctx().content_rect().height() * 0.6)
+            .show(ui, |ui| {
+                let mut any = false;
+                for (category, shortcuts) in shortcuts.by_category() {
+                    let matching: Vec<(&Shortcut, String)> = shortcuts
+                        .into_iter()
+                        .map(|shortcut| (shortcut, format_shortcut(&shortcut.shortcut)))
+                        .filter(|(shortcut, keys)| {
+                            needle.is_empty()
+                                || [category, shortcut.description, keys.as_str()]
+                                    .iter()
+                                    .any(|text| text.to_lowercase().contains(&needle))
+                        })
+                        .collect();
+                    if matching.is_empty() {
+                        continue;
+                    }
+                    any = true;
+
+                    ui.add_space(tokens.view_padding() as f32 / 2.0);
+                    ui.label(
+                        egui::RichText::new(category)
+                            .strong()
+                            .color(tokens.text_strong),
+                    );
+                    ui.property_grid(category, |grid| {
+                        for (shortcut, keys) in matching {
+                            grid.row(
+                                egui::RichText::new(keys)
+                                    .monospace()
+                                    .color(tokens.text_default),
+                                |ui| ui.label(shortcut.description),
+                            );
+                        }
+                    });
+                }
+                if !any {
+                    ui.label(
+                        egui::RichText::new("No matching shortcuts").color(tokens.text_subdued),
+                    );
+                }
+            });
+
+        ui.data_mut(|data| data.insert_temp(query_id, query));
+    });
+
+    // While typing in the search field, `?` is part of the query
+    let toggled = !ctx.wants_keyboard_input()
+        && ctx.input_mut(|input| input.consume_shortcut(&CHEAT_SHEET_SHORTCUT));
+    if modal.should_close() || toggled {
+        *open = false;
+        ctx.data_mut(|data| data.remove::<String>(id.with("query")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{Key, KeyboardShortcut, Modifiers};
+
+    use super::*;
+
+    #[test]
+    fn pressed_prefers_exact_modifiers() {
+        let undo = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+        let redo = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z);
+        let pause = KeyboardShortcut::new(Modifiers::NONE, Key::F8);
+        let mut shortcuts = Shortcuts::new();
+        shortcuts
+            .register("Edit", "Undo", undo)
+            .register("Edit", "Redo", redo)
+            .register("Simulation", "Pause", pause);
+
+        assert_eq!(shortcuts.pressed(Modifiers::COMMAND, Key::Z), Some(undo));
+        assert_eq!(
+            shortcuts.pressed(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z),
+            Some(redo)
+        );
+        // Extra modifiers are ignored when nothing matches exactly
+        assert_eq!(shortcuts.pressed(Modifiers::SHIFT, Key::F8), Some(pause));
+        assert_eq!(shortcuts.pressed(Modifiers::NONE, Key::Z), None);
+        assert_eq!(shortcuts.pressed(Modifiers::COMMAND, Key::Y), None);
+    }
+}
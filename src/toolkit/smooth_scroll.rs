@@ -0,0 +1,64 @@
+//! Easing scroll areas toward an offset, see [`super::UiExt::smooth_scroll_to`].
+
+/// How long a smooth scroll takes, in seconds.
+const DURATION: f32 = 0.25;
+
+/// A scroll area moving toward `to`, stored in temp data next to the area's own state.
+#[derive(Clone, Copy, Debug)]
+struct Animation {
+    from: f32,
+    to: f32,
+    elapsed: f32,
+    /// The offset set last frame, to notice the user scrolling in the meantime.
+    last: f32,
+}
+
+fn animation_id(scroll_area: egui::Id) -> egui::Id {
+    scroll_area.with("smooth_scroll")
+}
+
+fn current_offset(ctx: &egui::Context, scroll_area: egui::Id) -> f32 {
+    egui::scroll_area::State::load(ctx, scroll_area).map_or(0.0, |state| state.offset.y)
+}
+
+/// Start moving the scroll area with `scroll_area` id toward `offset`.
+pub(super) fn scroll_to(ctx: &egui::Context, scroll_area: egui::Id, offset: f32) {
+    let from = current_offset(ctx, scroll_area);
+    let animation = Animation {
+        from,
+        to: offset.max(0.0),
+        elapsed: 0.0,
+        last: from,
+    };
+    ctx.data_mut(|data| data.insert_temp(animation_id(scroll_area), animation));
+    ctx.request_repaint();
+}
+
+/// Advance the animation by `delta_time` seconds, and return the offset to show this frame.
+///
+/// `None` once the target is reached, or when the user scrolled while it was running.
+pub(super) fn advance(ctx: &egui::Context, scroll_area: egui::Id, delta_time: f32) -> Option<f32> {
+    let id = animation_id(scroll_area);
+    let mut animation = ctx.data(|data| data.get_temp::<Animation>(id))?;
+
+    // The wheel or scroll bar takes over from the animation
+    if (current_offset(ctx, scroll_area) - animation.last).abs() > 0.5 {
+        ctx.data_mut(|data| data.remove::<Animation>(id));
+        return None;
+    }
+
+    animation.elapsed += delta_time;
+    let t = (animation.elapsed / DURATION).min(1.0);
+    let offset = egui::lerp(
+        animation.from..=animation.to,
+        egui::emath::ease_in_ease_out(t),
+    );
+    if t < 1.0 {
+        animation.last = offset;
+        ctx.data_mut(|data| data.insert_temp(id, animation));
+        ctx.request_repaint();
+    } else {
+        ctx.data_mut(|data| data.remove::<Animation>(id));
+    }
+    Some(offset)
+}
@@ -0,0 +1,136 @@
+//! Regions split in two by a draggable divider, anywhere in a ui rather than only at the edge of
+//! the window like [`egui::Panel`].
+//!
+//! Splits nest, so a split pane can be split again to build up any layout:
+//!
+//! ```ignore
+//! split::horizontal(ui, &mut self.sidebar_ratio, sidebar_ui, |ui| {
+//!     split::vertical(ui, &mut self.console_ratio, viewport_ui, console_ui);
+//! });
+//! ```
+
+use super::HasDesignTokens as _;
+
+/// Smallest a pane can be dragged to, in points, unless there isn't room for two of them.
+pub const MIN_PANE_SIZE: f32 = 48.0;
+
+/// Split the available space into a `left` and a `right` pane, with a divider between them.
+///
+/// `ratio` is the share of the width given to `left`, and is updated as the divider is dragged.
+/// Keep it somewhere that outlives the frame, like the app's state, to remember the split.
+pub fn horizontal<L, R>(
+    ui: &mut egui::Ui,
+    ratio: &mut f32,
+    left: impl FnOnce(&mut egui::Ui) -> L,
+    right: impl FnOnce(&mut egui::Ui) -> R,
+) -> (L, R) {
+    split(ui, ratio, egui::Direction::LeftToRight, left, right)
+}
+
+/// Split the available space into a `top` and a `bottom` pane, with a divider between them.
+///
+/// `ratio` is the share of the height given to `top`, see [`horizontal`].
+pub fn vertical<T, B>(
+    ui: &mut egui::Ui,
+    ratio: &mut f32,
+    top: impl FnOnce(&mut egui::Ui) -> T,
+    bottom: impl FnOnce(&mut egui::Ui) -> B,
+) -> (T, B) {
+    split(ui, ratio, egui::Direction::TopDown, top, bottom)
+}
+
+fn split<A, B>(
+    ui: &mut egui::Ui,
+    ratio: &mut f32,
+    direction: egui::Direction,
+    first: impl FnOnce(&mut egui::Ui) -> A,
+    second: impl FnOnce(&mut egui::Ui) -> B,
+) -> (A, B) {
+    let tokens = ui.tokens();
+    let id = ui.auto_id_with("split");
+    let rect = ui.available_rect_before_wrap();
+    let is_horizontal = direction == egui::Direction::LeftToRight;
+    let axis = usize::from(!is_horizontal);
+
+    let divider_width = 1.0;
+    let extent = (rect.size()[axis] - divider_width).max(0.0);
+    // Each pane keeps its minimum size, unless both can't
+    let min_ratio = if extent > 0.0 {
+        (MIN_PANE_SIZE / extent).min(0.5)
+    } else {
+        0.5
+    };
+    *ratio = ratio.clamp(min_ratio, 1.0 - min_ratio);
+
+    let divider_at = |ratio: f32| (rect.min[axis] + ratio * extent).round();
+    let divider_rect = |at: f32, half_width: f32| {
+        if is_horizontal {
+            egui::Rect::from_x_y_ranges(at - half_width..=at + half_width, rect.y_range())
+        } else {
+            egui::Rect::from_x_y_ranges(rect.x_range(), at - half_width..=at + half_width)
+        }
+    };
+
+    // Grabbed within a few points of the line, like a panel's edge
+    let grab_radius = ui.style().interaction.resize_grab_radius_side;
+    let response = ui.interact(
+        divider_rect(divider_at(*ratio) + 0.5 * divider_width, grab_radius),
+        id,
+        egui::Sense::drag(),
+    );
+    if response.dragged()
+        && let Some(pointer) = response.interact_pointer_pos()
+        && extent > 0.0
+    {
+        *ratio = ((pointer[axis] - rect.min[axis]) / extent).clamp(min_ratio, 1.0 - min_ratio);
+    }
+    if response.hovered() || response.dragged() {
+        ui.ctx().set_cursor_icon(if is_horizontal {
+            egui::CursorIcon::ResizeHorizontal
+        } else {
+            egui::CursorIcon::ResizeVertical
+        });
+    }
+
+    let at = divider_at(*ratio);
+    let (first_rect, second_rect) = if is_horizontal {
+        (
+            egui::Rect::from_x_y_ranges(rect.left()..=at, rect.y_range()),
+            egui::Rect::from_x_y_ranges(at + divider_width..=rect.right(), rect.y_range()),
+        )
+    } else {
+        (
+            egui::Rect::from_x_y_ranges(rect.x_range(), rect.top()..=at),
+            egui::Rect::from_x_y_ranges(rect.x_range(), at + divider_width..=rect.bottom()),
+        )
+    };
+
+    let mut pane = |pane_rect: egui::Rect, salt: &str| {
+        let mut child = ui.new_child(
+            egui::UiBuilder::new()
+                .id_salt(id.with(salt))
+                .max_rect(pane_rect)
+                .layout(*ui.layout()),
+        );
+        child.shrink_clip_rect(pane_rect);
+        child
+    };
+    let first = first(&mut pane(first_rect, "first"));
+    let second = second(&mut pane(second_rect, "second"));
+
+    let color = if response.dragged() {
+        tokens.widget_active_bg_fill
+    } else if response.hovered() {
+        tokens.widget_hovered_bg_fill
+    } else {
+        tokens.widget_noninteractive_bg_stroke
+    };
+    ui.painter().rect_filled(
+        divider_rect(at + 0.5 * divider_width, 0.5 * divider_width),
+        0.0,
+        color,
+    );
+
+    ui.advance_cursor_after_rect(rect);
+    (first, second)
+}
@@ -0,0 +1,58 @@
+//! A thin bar along the bottom of the window, see [`super::UiExt::status_bar`].
+
+/// The contents of a status bar, split into left, center and right aligned segments.
+pub struct StatusBar<'a> {
+    ui: &'a mut egui::Ui,
+    rect: egui::Rect,
+}
+
+impl<'a> StatusBar<'a> {
+    pub(super) fn new(ui: &'a mut egui::Ui) -> Self {
+        let rect = ui.max_rect();
+        Self { ui, rect }
+    }
+
+    /// Add widgets from the left edge, left to right. Mirrored when right to left, see
+    /// [`super::set_right_to_left`].
+    pub fn left<R>(&mut self, add_contents: impl FnOnce(&mut egui::Ui) -> R) -> R {
+        let layout = if super::is_right_to_left(self.ui.ctx()) {
+            egui::Layout::right_to_left(egui::Align::Center)
+        } else {
+            egui::Layout::left_to_right(egui::Align::Center)
+        };
+        self.segment(layout, add_contents)
+    }
+
+    /// Add widgets around the middle of the bar.
+    pub fn center<R>(&mut self, add_contents: impl FnOnce(&mut egui::Ui) -> R) -> R {
+        let layout = if super::is_right_to_left(self.ui.ctx()) {
+            egui::Layout::right_to_left(egui::Align::Center)
+        } else {
+            egui::Layout::left_to_right(egui::Align::Center)
+        };
+        self.segment(layout.with_main_align(egui::Align::Center), add_contents)
+    }
+
+    /// Add widgets from the right edge, right to left. Mirrored like [`Self::left`].
+    pub fn right<R>(&mut self, add_contents: impl FnOnce(&mut egui::Ui) -> R) -> R {
+        let layout = if super::is_right_to_left(self.ui.ctx()) {
+            egui::Layout::left_to_right(egui::Align::Center)
+        } else {
+            egui::Layout::right_to_left(egui::Align::Center)
+        };
+        self.segment(layout, add_contents)
+    }
+
+    fn segment<R>(
+        &mut self,
+        layout: egui::Layout,
+        add_contents: impl FnOnce(&mut egui::Ui) -> R,
+    ) -> R {
+        self.ui
+            .scope_builder(
+                egui::UiBuilder::new().max_rect(self.rect).layout(layout),
+                add_contents,
+            )
+            .inner
+    }
+}
@@ -0,0 +1,287 @@
+//! A thin wrapper around [`egui_extras::TableBuilder`] that applies the toolkit styling.
+//!
+//! Rows are only laid out when visible, so tables with thousands of rows are fine.
+//!
+//! ```ignore
+//! let rows: Vec<(hecs::Entity, Transform)> = world
+//!     .query::<(hecs::Entity, &Transform)>()
+//!     .iter()
+//!     .map(|(entity, transform)| (entity, *transform))
+//!     .collect();
+//!
+//! Table::new("entities")
+//!     .sortable_column(
+//!         "Entity",
+//!         |ui, (entity, _)| {
+//!             ui.label(format!("{entity:?}"));
+//!         },
+//!         |(a, _), (b, _)| a.id().cmp(&b.id()),
+//!     )
+//!     .numeric_column("x", |(_, t)| t.translation.x as f64)
+//!     .numeric_column("y", |(_, t)| t.translation.y as f64)
+//!     .numeric_column("z", |(_, t)| t.translation.z as f64)
+//!     .show(ui, &rows);
+//! ```
+
+use std::cmp::Ordering;
+
+use egui_extras::{Column, TableBuilder};
+
+use super::{DesignTokens, TableStyle, UiExt as _};
+use crate::misc::FloatFormatOptions;
+
+type CellFn<'a, Row> = Box<dyn Fn(&mut egui::Ui, &Row) + 'a>;
+type CompareFn<'a, Row> = Box<dyn Fn(&Row, &Row) -> Ordering + 'a>;
+
+struct TableColumn<'a, Row> {
+    title: String,
+    width: Column,
+    cell: CellFn<'a, Row>,
+    compare: Option<CompareFn<'a, Row>>,
+}
+
+/// Which column the table is currently sorted by.
+///
+/// Stored in egui memory, keyed by the table id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct SortState {
+    column: usize,
+    ascending: bool,
+}
+
+/// A styled, optionally sortable table.
+pub struct Table<'a, Row> {
+    id_salt: egui::Id,
+    style: TableStyle,
+    columns: Vec<TableColumn<'a, Row>>,
+}
+
+impl<'a, Row> Table<'a, Row> {
+    pub fn new(id_salt: impl std::hash::Hash) -> Self {
+        Self {
+            id_salt: egui::Id::new(id_salt),
+            style: TableStyle::Dense,
+            columns: Vec::new(),
+        }
+    }
+
+    /// Use [`TableStyle::Spacious`] to fit buttons in the cells.
+    #[inline]
+    pub fn style(mut self, style: TableStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Add a column that can not be sorted.
+    pub fn column(
+        mut self,
+        title: impl Into<String>,
+        cell: impl Fn(&mut egui::Ui, &Row) + 'a,
+    ) -> Self {
+        self.columns.push(TableColumn {
+            title: title.into(),
+            width: Column::auto().resizable(true),
+            cell: Box::new(cell),
+            compare: None,
+        });
+        self
+    }
+
+    /// Add a column that is sorted with `compare` when its header is clicked.
+    pub fn sortable_column(
+        mut self,
+        title: impl Into<String>,
+        cell: impl Fn(&mut egui::Ui, &Row) + 'a,
+        compare: impl Fn(&Row, &Row) -> Ordering + 'a,
+    ) -> Self {
+        self.columns.push(TableColumn {
+            title: title.into(),
+            width: Column::auto().resizable(true),
+            cell: Box::new(cell),
+            compare: Some(Box::new(compare)),
+        });
+        self
+    }
+
+    /// Add a sortable, right-aligned column of numbers, formatted with [`FloatFormatOptions::DEFAULT_f64`].
+    pub fn numeric_column(
+        self,
+        title: impl Into<String>,
+        value: impl Fn(&Row) -> f64 + 'a,
+    ) -> Self {
+        self.numeric_column_with_options(title, value, FloatFormatOptions::DEFAULT_f64)
+    }
+
+    /// Add a sortable, right-aligned column of numbers, formatted with the given options.
+    pub fn numeric_column_with_options(
+        mut self,
+        title: impl Into<String>,
+        value: impl Fn(&Row) -> f64 + 'a,
+        options: FloatFormatOptions,
+    ) -> Self {
+        let value = std::rc::Rc::new(value);
+        let cell_value = value.clone();
+        self.columns.push(TableColumn {
+            title: title.into(),
+            width: Column::auto().resizable(true),
+            cell: Box::new(move |ui, row| {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(options.format(cell_value(row)));
+                });
+            }),
+            compare: Some(Box::new(move |a, b| value(a).total_cmp(&value(b)))),
+        });
+        self
+    }
+
+    /// Override the sizing of the most recently added column.
+    #[inline]
+    pub fn width(mut self, width: Column) -> Self {
+        if let Some(column) = self.columns.last_mut() {
+            column.width = width;
+        }
+        self
+    }
+
+    /// Show the table, sorting `rows` according to the column the user picked.
+    pub fn show(self, ui: &mut egui::Ui, rows: &[Row]) {
+        let Self {
+            id_salt,
+            style,
+            columns,
+        } = self;
+
+        let tokens = ui.tokens();
+        let id = ui.make_persistent_id(id_salt);
+        let mut sort: Option<SortState> = ui.data(|data| data.get_temp(id));
+
+        // Sort indices rather than the rows themselves, so the caller keeps ownership:
+        let mut order: Vec<usize> = (0..rows.len()).collect();
+        if let Some(SortState { column, ascending }) = sort
+            && let Some(compare) = columns.get(column).and_then(|c| c.compare.as_ref())
+        {
+            order.sort_by(|&a, &b| {
+                let ordering = compare(&rows[a], &rows[b]);
+                if ascending {
+                    ordering
+                } else {
+                    ordering.reverse()
+                }
+            });
+        }
+
+        ui.scope(|ui| {
+            apply_table_style(ui.visuals_mut(), tokens);
+
+            let mut builder = TableBuilder::new(ui)
+                .id_salt(id_salt)
+                .striped(true)
+                .sense(egui::Sense::hover())
+                .resizable(true)
+                .auto_shrink([false, true])
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center));
+            for column in &columns {
+                builder = builder.column(column.width);
+            }
+
+            builder
+                .header(tokens.table_header_height(), |mut header| {
+                    for (index, column) in columns.iter().enumerate() {
+                        let mut clicked = false;
+                        header.col(|ui| {
+                            clicked = header_cell_ui(ui, tokens, style, column, index, sort);
+                        });
+
+                        if clicked {
+                            sort = Some(match sort {
+                                Some(s) if s.column == index => SortState {
+                                    column: index,
+                                    ascending: !s.ascending,
+                                },
+                                _ => SortState {
+                                    column: index,
+                                    ascending: true,
+                                },
+                            });
+                        }
+                    }
+                })
+                .body(|mut body| {
+                    tokens.setup_table_body(&mut body, style);
+                    let margin = tokens.table_cell_margin(style);
+                    body.rows(tokens.table_row_height(style), order.len(), |mut row| {
+                        let data = &rows[order[row.index()]];
+                        for column in &columns {
+                            row.col(|ui| {
+                                egui::Frame::new().inner_margin(margin).show(ui, |ui| {
+                                    (column.cell)(ui, data);
+                                });
+                            });
+                        }
+                    });
+                });
+        });
+
+        ui.data_mut(|data| match sort {
+            Some(sort) => data.insert_temp(id, sort),
+            None => data.remove::<SortState>(id),
+        });
+    }
+}
+
+fn apply_table_style(visuals: &mut egui::Visuals, tokens: &DesignTokens) {
+    visuals.striped = true;
+    visuals.widgets.hovered.bg_fill = tokens.table_interaction_hovered_bg_stroke;
+    visuals.widgets.active.bg_fill = tokens.table_interaction_active_bg_stroke;
+    visuals.widgets.noninteractive.bg_stroke.color =
+        tokens.table_interaction_noninteractive_bg_stroke;
+    visuals.selection.bg_fill = tokens.table_interaction_row_selection_fill;
+}
+
+fn header_cell_ui<Row>(
+    ui: &mut egui::Ui,
+    tokens: &DesignTokens,
+    style: TableStyle,
+    column: &TableColumn<'_, Row>,
+    index: usize,
+    sort: Option<SortState>,
+) -> bool {
+    let rect = ui.max_rect();
+    ui.painter()
+        .rect_filled(rect, 0.0, tokens.table_header_bg_fill);
+    ui.painter().hline(
+        rect.x_range(),
+        rect.bottom(),
+        egui::Stroke::new(1.0, tokens.table_header_stroke_color),
+    );
+
+    egui::Frame::new()
+        .inner_margin(tokens.header_cell_margin(style))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(&column.title).strong());
+
+                if let Some(sort) = sort
+                    && sort.column == index
+                {
+                    let icon = if sort.ascending {
+                        super::icons::ARROW_UP
+                    } else {
+                        super::icons::ARROW_DOWN
+                    };
+                    ui.add(
+                        icon.as_image()
+                            .fit_to_exact_size(tokens.small_icon_size)
+                            .tint(tokens.table_sort_icon_color),
+                    );
+                }
+            });
+        });
+
+    // The table only senses hover, so sortable headers need their own click sense:
+    column.compare.is_some()
+        && ui
+            .interact(rect, ui.id().with("sort"), egui::Sense::click())
+            .on_hover_cursor(egui::CursorIcon::PointingHand)
+            .clicked()
+}
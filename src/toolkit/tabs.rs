@@ -0,0 +1,224 @@
+//! A tab strip, for switching between a few views without pulling in a docking crate.
+//!
+//! The caller owns the tab bodies, and describes them with a slice of [`TabSpec`]s.
+//! [`Tabs`] only remembers which of them are open, in what order, and which one is active.
+
+use super::{TooltipExt as _, UiExt as _, icons};
+
+/// Describes one tab that [`tabs_ui`] can show.
+#[derive(Clone, Copy, Debug)]
+pub struct TabSpec<'a> {
+    pub title: &'a str,
+    /// Show a close button, and allow middle-click to close.
+    pub closable: bool,
+}
+
+impl<'a> TabSpec<'a> {
+    pub fn new(title: &'a str) -> Self {
+        Self {
+            title,
+            closable: true,
+        }
+    }
+
+    pub fn closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+}
+
+/// Which tabs are open, and in what order.
+///
+/// Tabs are referred to by their index into the `&[TabSpec]` passed to [`tabs_ui`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Tabs {
+    open: Vec<usize>,
+    active: Option<usize>,
+}
+
+impl Tabs {
+    /// Open the given tabs in order, with the first one active.
+    pub fn new(open: impl IntoIterator<Item = usize>) -> Self {
+        let open: Vec<usize> = open.into_iter().collect();
+        Self {
+            active: open.first().copied(),
+            open,
+        }
+    }
+
+    /// The tab whose body should be drawn, if any are open.
+    pub fn active(&self) -> Option<usize> {
+        self.active
+    }
+
+    /// The open tabs, in the order they are shown.
+    pub fn open_tabs(&self) -> &[usize] {
+        &self.open
+    }
+
+    pub fn is_open(&self, tab: usize) -> bool {
+        self.open.contains(&tab)
+    }
+
+    /// Open `tab` at the end of the strip if it isn't already, and make it active.
+    pub fn open(&mut self, tab: usize) {
+        if !self.is_open(tab) {
+            self.open.push(tab);
+        }
+        self.active = Some(tab);
+    }
+
+    /// Close `tab`. If it was active, its neighbor becomes active instead.
+    pub fn close(&mut self, tab: usize) {
+        let Some(position) = self.open.iter().position(|&open| open == tab) else {
+            return;
+        };
+        self.open.remove(position);
+        if self.active == Some(tab) {
+            self.active = self.open.get(position).or(self.open.last()).copied();
+        }
+    }
+}
+
+/// What happened in the tab strip this frame.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TabsResponse {
+    /// The tab whose body to draw, if any.
+    pub active: Option<usize>,
+    /// The tab that was closed this frame.
+    pub closed: Option<usize>,
+    /// The `+` button was clicked. It's up to the caller to [`Tabs::open`] something.
+    pub add_clicked: bool,
+}
+
+/// Show a tab strip for `tabs`.
+///
+/// Click a tab to activate it, drag it to reorder, and middle-click or use its close button to
+/// close it.
+pub fn tabs_ui(ui: &mut egui::Ui, tabs: &mut Tabs, specs: &[TabSpec<'_>]) -> TabsResponse {
+    let tokens = ui.tokens();
+
+    // The specs may have shrunk since the state was saved:
+    tabs.open.retain(|&tab| tab < specs.len());
+    if tabs
+        .active
+        .is_none_or(|active| !tabs.open.contains(&active))
+    {
+        tabs.active = tabs.open.first().copied();
+    }
+
+    let mut response = TabsResponse::default();
+    let mut dragged = None;
+    let mut tab_rects = Vec::with_capacity(tabs.open.len());
+
+    egui::Frame::new()
+        .fill(tokens.tab_bar_color)
+        .inner_margin(tokens.top_bar_margin())
+        .show(ui, |ui| {
+            ui.set_height(tokens.title_bar_height());
+            ui.horizontal_centered(|ui| {
+                ui.spacing_mut().item_spacing.x = 2.0;
+
+                for (position, &tab) in tabs.open.iter().enumerate() {
+                    let spec = &specs[tab];
+                    let is_active = tabs.active == Some(tab);
+
+                    let galley = egui::WidgetText::from(spec.title).into_galley(
+                        ui,
+                        Some(egui::TextWrapMode::Extend),
+                        f32::INFINITY,
+                        egui::TextStyle::Button,
+                    );
+                    let padding = ui.spacing().button_padding.x.max(6.0);
+                    let close_size = if spec.closable {
+                        tokens.small_icon_size.x + tokens.text_to_icon_padding()
+                    } else {
+                        0.0
+                    };
+                    let size = egui::vec2(
+                        galley.size().x + close_size + 2.0 * padding,
+                        tokens.title_bar_height(),
+                    );
+
+                    let (rect, tab_response) =
+                        ui.allocate_exact_size(size, egui::Sense::click_and_drag());
+                    tab_rects.push(rect);
+
+                    let (bg_color, text_color) = if is_active {
+                        (tokens.list_item_active_bg, tokens.list_item_active_text)
+                    } else if tab_response.hovered() {
+                        (tokens.list_item_hovered_bg, tokens.list_item_hovered_text)
+                    } else {
+                        (egui::Color32::TRANSPARENT, tokens.list_item_default_text)
+                    };
+
+                    if ui.is_rect_visible(rect) {
+                        ui.painter().rect_filled(rect, 4.0, bg_color);
+                        let text_pos = egui::pos2(
+                            rect.left() + padding,
+                            rect.center().y - 0.5 * galley.size().y,
+                        );
+                        ui.painter().galley(text_pos, galley, text_color);
+                    }
+                    let tab_response = ui.focusable(tab_response);
+
+                    if spec.closable {
+                        let close_rect = egui::Rect::from_center_size(
+                            egui::pos2(
+                                rect.right() - padding - 0.5 * tokens.small_icon_size.x,
+                                rect.center().y,
+                            ),
+                            tokens.small_icon_size,
+                        );
+                        let close = ui
+                            .put(
+                                close_rect,
+                                egui::Button::image(
+                                    icons::CLOSE_SMALL
+                                        .as_image()
+                                        .fit_to_exact_size(tokens.small_icon_size),
+                                )
+                                .image_tint_follows_text_color(true)
+                                .frame(false),
+                            )
+                            .on_tooltip_text("Close tab");
+                        if close.clicked() || tab_response.middle_clicked() {
+                            response.closed = Some(tab);
+                        }
+                    }
+
+                    if tab_response.clicked() || tab_response.drag_started() {
+                        tabs.active = Some(tab);
+                    }
+                    if tab_response.dragged() {
+                        ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+                        dragged = Some(position);
+                    }
+                }
+
+                response.add_clicked = ui
+                    .add(icons::ADD.as_button())
+                    .on_tooltip_text("New tab")
+                    .clicked();
+            });
+        });
+
+    // Move a dragged tab to wherever the pointer is over the strip:
+    if let Some(from) = dragged
+        && let Some(pointer) = ui.ctx().pointer_interact_pos()
+        && let Some(to) = tab_rects
+            .iter()
+            .position(|rect| rect.x_range().contains(pointer.x))
+        && from != to
+    {
+        let tab = tabs.open.remove(from);
+        tabs.open.insert(to, tab);
+    }
+
+    if let Some(closed) = response.closed {
+        tabs.close(closed);
+    }
+
+    response.active = tabs.active;
+    response
+}
@@ -0,0 +1,55 @@
+//! Laying out text ahead of drawing it, see [`super::UiExt::measure_text`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Galleys laid out this frame, by text, font and wrap width.
+#[derive(Clone, Default)]
+struct GalleyCache {
+    frame: u64,
+    galleys: HashMap<egui::Id, Arc<egui::Galley>>,
+}
+
+/// `text` in `font`, wrapped at `wrap_width`, laid out once per frame however often it is asked
+/// for.
+///
+/// The galley is laid out with [`egui::Color32::PLACEHOLDER`], so it takes on the color it is
+/// painted with.
+pub(super) fn galley(
+    ui: &egui::Ui,
+    text: &str,
+    font: egui::FontId,
+    wrap_width: f32,
+) -> Arc<egui::Galley> {
+    let frame = ui.ctx().cumulative_frame_nr();
+    let cache_id = egui::Id::new("galley_cache");
+    let key = egui::Id::new((text, &font, wrap_width.to_bits()));
+    let cached = ui.data_mut(|data| {
+        let cache = data.get_temp_mut_or_default::<GalleyCache>(cache_id);
+        // Anything from earlier frames may have been laid out with other fonts or scaling
+        if cache.frame != frame {
+            cache.frame = frame;
+            cache.galleys.clear();
+        }
+        cache.galleys.get(&key).cloned()
+    });
+    if let Some(galley) = cached {
+        return galley;
+    }
+
+    // Lay out outside of `data_mut`, which would deadlock with the fonts' lock
+    let galley = ui.fonts_mut(|fonts| {
+        fonts.layout(
+            text.to_owned(),
+            font,
+            egui::Color32::PLACEHOLDER,
+            wrap_width,
+        )
+    });
+    ui.data_mut(|data| {
+        data.get_temp_mut_or_default::<GalleyCache>(cache_id)
+            .galleys
+            .insert(key, galley.clone());
+    });
+    galley
+}
@@ -0,0 +1,114 @@
+//! Tooltips in the toolkit's style, shown on keyboard focus as well as on hover.
+//!
+//! egui only shows tooltips to the mouse, so anything explained in one is out of reach when
+//! tabbing through widgets. These also show once a widget has kept focus for
+//! [`egui::style::Interaction::tooltip_delay`], which [`super::DesignTokens`] sets for the whole
+//! app. Change it in a scope's style to use a different delay for some widgets.
+
+use super::HasDesignTokens as _;
+
+/// Which widget has keyboard focus, and since when.
+#[derive(Clone, Copy, Debug)]
+struct FocusStart {
+    widget: egui::Id,
+    time: f64,
+}
+
+/// Tooltips for any widget's [`egui::Response`], like [`egui::Response::on_hover_text`].
+pub trait TooltipExt {
+    /// Show `text` when hovering the widget, or after it kept keyboard focus for a while.
+    fn on_tooltip_text(self, text: impl Into<egui::WidgetText>) -> Self;
+
+    /// Like [`Self::on_tooltip_text`], with any contents.
+    fn on_tooltip_ui(self, add_contents: impl FnOnce(&mut egui::Ui)) -> Self;
+}
+
+impl TooltipExt for egui::Response {
+    fn on_tooltip_text(self, text: impl Into<egui::WidgetText>) -> Self {
+        self.on_tooltip_ui(|ui| {
+            ui.label(text);
+        })
+    }
+
+    fn on_tooltip_ui(self, add_contents: impl FnOnce(&mut egui::Ui)) -> Self {
+        let open = self.enabled()
+            && (egui::Tooltip::should_show_tooltip(&self, true) || focused_long_enough(&self));
+
+        let mut tooltip = egui::Tooltip::for_widget(&self);
+        let style = self.ctx.style();
+        tooltip.popup = tooltip
+            .popup
+            .open(open)
+            .frame(style.tokens().tooltip_frame(&style));
+        tooltip.show(add_contents);
+        self
+    }
+}
+
+/// Whether `response` has had keyboard focus for at least the tooltip delay.
+///
+/// Asks for a repaint when the delay runs out, since nothing else might happen until then.
+fn focused_long_enough(response: &egui::Response) -> bool {
+    if !response.has_focus() {
+        return false;
+    }
+
+    let id = egui::Id::new("tooltip_focus_start");
+    let now = response.ctx.input(|i| i.time);
+    let start = response
+        .ctx
+        .data(|data| data.get_temp::<FocusStart>(id))
+        .filter(|start| start.widget == response.id)
+        .unwrap_or_else(|| {
+            let start = FocusStart {
+                widget: response.id,
+                time: now,
+            };
+            response.ctx.data_mut(|data| data.insert_temp(id, start));
+            start
+        });
+
+    let delay = f64::from(response.ctx.style().interaction.tooltip_delay);
+    let remaining = start.time + delay - now;
+    if remaining > 0.0 {
+        response.ctx.request_repaint_after_secs(remaining as f32);
+        false
+    } else {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TooltipExt as _;
+
+    /// Run a frame at `time` with a focused button, and return whether its tooltip showed.
+    fn run_frame(ctx: &egui::Context, time: f64) -> bool {
+        let input = egui::RawInput {
+            time: Some(time),
+            ..Default::default()
+        };
+        let mut shown = false;
+        let _ = ctx.run_ui(input, |ui| {
+            let response = ui.button("Recenter");
+            if time == 0.0 {
+                response.request_focus();
+            }
+            let _ = response.on_tooltip_ui(|_| shown = true);
+        });
+        shown
+    }
+
+    #[test]
+    fn opens_after_focus_delay_without_pointer() {
+        let ctx = egui::Context::default();
+        crate::toolkit::apply_style_and_install_loaders(&ctx);
+        let delay = f64::from(ctx.style().interaction.tooltip_delay);
+
+        // Focus applies from the next frame, which starts the delay
+        assert!(!run_frame(&ctx, 0.0));
+        assert!(!run_frame(&ctx, 0.1));
+        assert!(!run_frame(&ctx, 0.1 + delay / 2.0));
+        assert!(run_frame(&ctx, 0.2 + delay));
+    }
+}
@@ -5,11 +5,31 @@
 //     CollapsingResponse, Color32, IntoAtoms, NumExt as _, Rangef, Rect, StrokeKind, Widget as _,
 //     WidgetInfo, WidgetText, pos2,
 // };
+use std::ops::RangeInclusive;
+
 use egui::IntoAtoms;
 
 // use crate::alert::Alert;
+use super::animation::AnimatedValue;
 use super::button::Button;
+use super::chip::{self, ChipResponse};
+use super::color_table::lerp_oklab;
 use super::design_tokens::DesignTokens;
+use super::icons::{self, Icon};
+use super::minimap;
+use super::property_grid::PropertyGrid;
+use super::reorderable_list;
+use super::smooth_scroll;
+use super::status_bar::StatusBar;
+use super::text_measure;
+use super::tooltip::TooltipExt as _;
+use super::vector_edit;
+use super::virtual_list;
+use crate::misc::{
+    LatLon, format_hex_color, format_lat_lon_with_hemisphere, format_percent,
+    format_with_decimals_in_range, nudge_step, parse_f64, parse_hex_color, parse_lat_lon_on_axis,
+    sanitize_f64, strip_whitespace_and_normalize, wrap_longitude,
+};
 // use crate::list_item::{self, LabelContent};
 // use crate::{ContextExt as _, DesignTokens, Icon, LabelStyle, icons};
 
@@ -226,6 +246,29 @@ pub trait UiExt {
     //     response
     // }
 
+    /// Whether rows should flow right to left, see [`super::set_right_to_left`].
+    fn is_right_to_left(&self) -> bool {
+        super::is_right_to_left(self.ui().ctx())
+    }
+
+    /// Like [`egui::Ui::horizontal`], but right to left when [`Self::is_right_to_left`].
+    ///
+    /// Right to left rows start at the right edge, so they fill the available width.
+    fn horizontal_directed<R>(
+        &mut self,
+        add_contents: impl FnOnce(&mut egui::Ui) -> R,
+    ) -> egui::InnerResponse<R> {
+        let ui = self.ui_mut();
+        if ui.is_right_to_left() {
+            ui.with_layout(
+                egui::Layout::right_to_left(egui::Align::Center),
+                add_contents,
+            )
+        } else {
+            ui.horizontal(add_contents)
+        }
+    }
+
     fn primary_button<'a>(&mut self, atoms: impl IntoAtoms<'a>) -> egui::Response {
         self.ui_mut().add(Button::new(atoms).primary())
     }
@@ -234,6 +277,1028 @@ pub trait UiExt {
         self.ui_mut().add(Button::new(atoms).secondary())
     }
 
+    /// A slider whose readout is formatted with [`format_with_decimals_in_range`].
+    ///
+    /// Typed values are parsed with [`parse_f64`], so thousands separators and `−` are accepted,
+    /// and then kept in `range` by [`sanitize_f64`]. While focused, the up and down arrow keys
+    /// nudge the value by [`nudge_step`].
+    fn formatted_slider(
+        &mut self,
+        value: &mut f64,
+        range: RangeInclusive<f64>,
+        decimals: RangeInclusive<usize>,
+    ) -> egui::Response {
+        let ui = self.ui_mut();
+        let tokens = ui.tokens();
+        let focus_id = ui.auto_id_with("formatted_slider_focus");
+        let nudged = nudge_with_arrow_keys(ui, focus_id, value, range.clone(), *decimals.end());
+        let mut response = ui
+            .scope(|ui| {
+                ui.visuals_mut().extreme_bg_color = tokens.text_edit_bg_color;
+                let parse_range = range.clone();
+                ui.add(
+                    egui::Slider::new(value, range)
+                        .min_decimals(*decimals.start())
+                        .max_decimals(*decimals.end())
+                        .custom_formatter(move |value, _| {
+                            format_with_decimals_in_range(value, decimals.clone())
+                        })
+                        .custom_parser(move |text| {
+                            parse_f64(text).map(|value| sanitize_f64(value, parse_range.clone()))
+                        }),
+                )
+            })
+            .inner;
+        remember_focus(ui, focus_id, &response);
+        if nudged {
+            response.mark_changed();
+        }
+        ui.focusable(response)
+    }
+
+    /// Like [`Self::formatted_slider`], but a [`egui::DragValue`] without the slider.
+    fn drag_value_formatted(
+        &mut self,
+        value: &mut f64,
+        range: RangeInclusive<f64>,
+        decimals: RangeInclusive<usize>,
+    ) -> egui::Response {
+        drag_value_formatted(self.ui_mut(), value, range, decimals, 1.0)
+    }
+
+    /// Edit an interval inside `bounds`, with a handle for each end that can't pass the other.
+    ///
+    /// Both ends are also shown as numbers beside the track, which can be typed into like
+    /// [`Self::drag_value_formatted`].
+    fn range_slider(
+        &mut self,
+        range: &mut RangeInclusive<f64>,
+        bounds: RangeInclusive<f64>,
+    ) -> egui::Response {
+        let ui = self.ui_mut();
+        let tokens = ui.tokens();
+        let (min_bound, max_bound) = bounds.clone().into_inner();
+        let (mut start, mut end) = range.clone().into_inner();
+        start = sanitize_f64(start, bounds.clone());
+        end = sanitize_f64(end, start..=max_bound);
+
+        // Enough decimals to tell apart a hundredth of the bounds
+        let span = (max_bound - min_bound).abs();
+        let decimals = if span >= 100.0 {
+            0..=0
+        } else {
+            0..=(2.0 - span.max(f64::MIN_POSITIVE).log10().floor()).clamp(0.0, 6.0) as usize
+        };
+
+        let mut response = ui
+            .horizontal_directed(|ui| {
+                let start_response =
+                    ui.drag_value_formatted(&mut start, min_bound..=end, decimals.clone());
+
+                let end_width = ui.spacing().interact_size.x + ui.spacing().item_spacing.x;
+                let track_width = (ui.available_width() - end_width)
+                    .max(ui.spacing().slider_width.min(ui.available_width()));
+                let size = egui::vec2(track_width, ui.spacing().interact_size.y);
+                let (rect, mut track_response) = ui.allocate_exact_size(size, egui::Sense::click());
+
+                // Handles are kept inside the track, so the ends line up with the bounds
+                let radius = 0.5
+                    * ui.spacing()
+                        .slider_rail_height
+                        .max(tokens.small_icon_size.y);
+                let x_range = if ui.is_right_to_left() {
+                    egui::Rangef::new(rect.right() - radius, rect.left() + radius)
+                } else {
+                    egui::Rangef::new(rect.left() + radius, rect.right() - radius)
+                };
+                let to_x = |value: f64| {
+                    let t = egui::emath::inverse_lerp(min_bound..=max_bound, value).unwrap_or(0.0);
+                    egui::lerp(x_range.min..=x_range.max, t as f32)
+                };
+                let to_value = |x: f32| {
+                    let t = egui::emath::inverse_lerp(x_range.min..=x_range.max, x).unwrap_or(0.0);
+                    egui::lerp(min_bound..=max_bound, f64::from(t.clamp(0.0, 1.0)))
+                };
+
+                // Clicking the track moves the closer handle there
+                if track_response.clicked()
+                    && let Some(pointer) = track_response.interact_pointer_pos()
+                {
+                    let value = to_value(pointer.x);
+                    if (value - start).abs() <= (value - end).abs() {
+                        start = value.min(end);
+                    } else {
+                        end = value.max(start);
+                    }
+                    track_response.mark_changed();
+                }
+
+                let center_y = rect.center().y;
+                let handle_rect = |value: f64| {
+                    egui::Rect::from_center_size(
+                        egui::pos2(to_x(value), center_y),
+                        egui::Vec2::splat(2.0 * radius),
+                    )
+                };
+                let (start_rect, end_rect) = (handle_rect(start), handle_rect(end));
+                let start_handle = ui.interact(
+                    start_rect,
+                    track_response.id.with("start"),
+                    egui::Sense::drag(),
+                );
+                let end_handle =
+                    ui.interact(end_rect, track_response.id.with("end"), egui::Sense::drag());
+
+                // Which handle a drag moves is settled when it starts. Handles that overlap
+                // can't be told apart by the pointer, so the drag waits until the pointer leaves
+                // them: towards the lower bound moves the start, the other way the end.
+                let moving_id = track_response.id.with("moving_start");
+                let pointer_value = start_handle
+                    .interact_pointer_pos()
+                    .or(end_handle.interact_pointer_pos())
+                    .map(|pointer| to_value(pointer.x));
+                if start_handle.drag_started() || end_handle.drag_started() {
+                    ui.data_mut(|data| {
+                        if start_rect.intersects(end_rect) {
+                            data.remove::<bool>(moving_id);
+                        } else {
+                            data.insert_temp(moving_id, start_handle.drag_started());
+                        }
+                    });
+                }
+                let mut moving_start = None;
+                if start_handle.dragged() || end_handle.dragged() {
+                    moving_start = ui.data(|data| data.get_temp::<bool>(moving_id));
+                    if moving_start.is_none()
+                        && let Some(value) = pointer_value
+                        && (value < start || value > end)
+                    {
+                        moving_start = Some(value < start);
+                        ui.data_mut(|data| data.insert_temp(moving_id, value < start));
+                    }
+                }
+                if let (Some(moving_start), Some(value)) = (moving_start, pointer_value) {
+                    if moving_start {
+                        start = sanitize_f64(value, min_bound..=end);
+                    } else {
+                        end = sanitize_f64(value, start..=max_bound);
+                    }
+                }
+
+                if ui.is_rect_visible(rect) {
+                    let rail_height = ui.spacing().slider_rail_height;
+                    let rail = egui::Rect::from_x_y_ranges(
+                        rect.x_range(),
+                        egui::Rangef::point(center_y).expand(0.5 * rail_height),
+                    );
+                    let corner_radius = 0.5 * rail_height;
+                    ui.painter()
+                        .rect_filled(rail, corner_radius, tokens.widget_inactive_bg_fill);
+                    let (start_x, end_x) = (to_x(start), to_x(end));
+                    let selected = egui::Rect::from_x_y_ranges(
+                        egui::Rangef::new(start_x.min(end_x), start_x.max(end_x)),
+                        rail.y_range(),
+                    );
+                    ui.painter()
+                        .rect_filled(selected, corner_radius, tokens.selection_bg_fill);
+
+                    for (x, handle, is_start) in
+                        [(start_x, &start_handle, true), (end_x, &end_handle, false)]
+                    {
+                        let fill = if moving_start == Some(is_start)
+                            || (moving_start.is_none() && handle.hovered())
+                        {
+                            tokens.strong_fg_color
+                        } else {
+                            tokens.text_default
+                        };
+                        ui.painter()
+                            .circle_filled(egui::pos2(x, center_y), radius, fill);
+                    }
+                }
+
+                let end_response =
+                    ui.drag_value_formatted(&mut end, start..=max_bound, decimals.clone());
+                start_response | track_response | start_handle | end_handle | end_response
+            })
+            .inner;
+
+        let new_range = start..=end;
+        if new_range != *range {
+            *range = new_range;
+            response.mark_changed();
+        }
+        response
+    }
+
+    /// Edit a latitude and longitude in degrees, shown with [`format_lat_lon_with_hemisphere`],
+    /// like `40.446111°N`.
+    ///
+    /// Typed values are parsed with [`parse_lat_lon_on_axis`], so a sign, a hemisphere letter, or
+    /// degrees, minutes and seconds all work. Latitude is clamped to ±90°, and longitude wrapped to
+    /// (−180°, 180°]. That includes values set from elsewhere, which marks the response as changed.
+    fn lat_lon_edit(&mut self, lat: &mut f64, lon: &mut f64) -> egui::Response {
+        let ui = self.ui_mut();
+
+        let before = [lat.to_bits(), lon.to_bits()];
+        *lat = lat.clamp(-90.0, 90.0);
+        *lon = wrap_longitude(*lon);
+        let normalized = before != [lat.to_bits(), lon.to_bits()];
+
+        ui.horizontal(|ui| {
+            let lat_response = ui.add(
+                egui::DragValue::new(lat)
+                    .speed(0.01)
+                    .range(-90.0..=90.0)
+                    .custom_formatter(|value, _| {
+                        format_lat_lon_with_hemisphere(value, LatLon::Latitude)
+                    })
+                    .custom_parser(|text| parse_lat_lon_on_axis(text, LatLon::Latitude)),
+            );
+            let lon_response = ui.add(
+                egui::DragValue::new(lon)
+                    .speed(0.01)
+                    .custom_formatter(|value, _| {
+                        format_lat_lon_with_hemisphere(value, LatLon::Longitude)
+                    })
+                    .custom_parser(|text| parse_lat_lon_on_axis(text, LatLon::Longitude)),
+            );
+            // Dragged or typed past the antimeridian
+            *lon = wrap_longitude(*lon);
+
+            let mut response = lat_response | lon_response;
+            if normalized {
+                response.mark_changed();
+            }
+            response
+        })
+        .inner
+    }
+
+    /// Edit a vector with a drag value per component, labeled X, Y and Z in the axis colors, and a
+    /// button resetting it to zero.
+    ///
+    /// Fits in a [`Self::property_grid`] row. Use [`vector_edit::Vec3Edit`] for another default,
+    /// drag speed, or to link the components, like for a uniform scale.
+    fn vec3_edit(&mut self, value: &mut [f32; 3]) -> egui::Response {
+        vector_edit::vec3(self.ui_mut(), value)
+    }
+
+    /// Edit a rotation as euler angles in degrees, like [`Self::vec3_edit`], resetting to the
+    /// identity.
+    fn quat_edit(&mut self, rotation: &mut glam::Quat) -> egui::Response {
+        vector_edit::quat(self.ui_mut(), rotation)
+    }
+
+    /// Edit a matrix a column at a time, like [`Self::vec3_edit`], each resetting to the identity.
+    fn mat4_edit(&mut self, matrix: &mut glam::Mat4) -> egui::Response {
+        vector_edit::mat4(self.ui_mut(), matrix)
+    }
+
+    /// A color swatch with a picker popup, next to an editable `#RRGGBBAA` hex field.
+    ///
+    /// Invalid hex is shown as an error while typing, and reverts when the field loses focus.
+    fn color_edit(&mut self, color: &mut egui::Color32) -> egui::Response {
+        let ui = self.ui_mut();
+        let tokens = ui.tokens();
+
+        ui.horizontal_directed(|ui| {
+            let mut response = ui
+                .scope(|ui| {
+                    let widgets = &mut ui.visuals_mut().widgets;
+                    widgets.inactive.bg_stroke = tokens.color_swatch_interactive_stroke;
+                    widgets.hovered.bg_stroke = tokens.color_swatch_interactive_stroke;
+                    widgets.active.bg_stroke = tokens.color_swatch_interactive_stroke;
+                    egui::color_picker::color_edit_button_srgba(
+                        ui,
+                        color,
+                        egui::color_picker::Alpha::OnlyBlend,
+                    )
+                })
+                .inner;
+
+            // Keep the text around while editing, so half-typed colors aren't overwritten:
+            let id = response.id.with("hex");
+            let mut text = ui
+                .data(|data| data.get_temp::<String>(id))
+                .unwrap_or_else(|| format_hex_color(*color));
+            let parsed = parse_hex_color(&text);
+
+            let hex_response = ui
+                .scope(|ui| {
+                    if parsed.is_none() {
+                        ui.style_invalid_field();
+                    }
+                    ui.add(
+                        egui::TextEdit::singleline(&mut text)
+                            .font(egui::TextStyle::Monospace)
+                            .desired_width(80.0),
+                    )
+                })
+                .inner;
+
+            if hex_response.changed()
+                && let Some(parsed) = parse_hex_color(&text)
+            {
+                *color = parsed;
+                response.mark_changed();
+            }
+
+            ui.data_mut(|data| {
+                if hex_response.has_focus() {
+                    data.insert_temp(id, text);
+                } else {
+                    data.remove::<String>(id);
+                }
+            });
+
+            response
+        })
+        .inner
+    }
+
+    /// Show `display`, followed by a button that copies `raw` to the clipboard.
+    ///
+    /// Use this for formatted numbers, so what ends up in the clipboard can be parsed again.
+    fn copyable_value(&mut self, display: &str, raw: &str) -> egui::Response {
+        /// For how long the button says "Copied!" after being clicked.
+        const COPIED_FEEDBACK_SECONDS: f64 = 1.5;
+
+        let ui = self.ui_mut();
+        let tokens = ui.tokens();
+        ui.horizontal_directed(|ui| {
+            ui.spacing_mut().item_spacing.x = tokens.text_to_icon_padding();
+            let label = ui.label(display);
+
+            let button = ui.add(
+                egui::Button::image(
+                    icons::COPY
+                        .as_image()
+                        .fit_to_exact_size(tokens.small_icon_size),
+                )
+                .image_tint_follows_text_color(true)
+                .frame(false),
+            );
+            let id = button.id.with("copied_at");
+            if button.clicked() {
+                ui.ctx().copy_text(raw.to_owned());
+                ui.data_mut(|data| data.insert_temp(id, ui.time()));
+            }
+
+            let copied_at = ui.data(|data| data.get_temp::<f64>(id));
+            let button = match copied_at {
+                Some(copied_at) if ui.time() - copied_at < COPIED_FEEDBACK_SECONDS => {
+                    ui.ctx()
+                        .request_repaint_after_secs(COPIED_FEEDBACK_SECONDS as f32);
+                    button.on_hover_text("Copied!")
+                }
+                _ => button.on_hover_text(format!("Copy {raw}")),
+            };
+
+            ui.focusable(button) | label
+        })
+        .inner
+    }
+
+    /// Like [`Self::copyable_value`], but copies `display` without thousands separators and
+    /// with a plain `-`, as made by [`strip_whitespace_and_normalize`].
+    fn copyable_number(&mut self, display: &str) -> egui::Response {
+        let raw = strip_whitespace_and_normalize(display);
+        self.copyable_value(display, &raw)
+    }
+
+    /// Show a fixed height status bar along the bottom of this [`egui::Ui`].
+    ///
+    /// Call this before adding the central contents, so they get the space that is left over.
+    fn status_bar<R>(&mut self, add_contents: impl FnOnce(&mut StatusBar<'_>) -> R) -> R {
+        let ui = self.ui_mut();
+        let tokens = ui.tokens();
+        egui::Panel::bottom("status_bar")
+            .resizable(false)
+            .exact_size(tokens.status_bar_height())
+            .frame(tokens.bottom_panel_frame())
+            .show_inside(ui, |ui| add_contents(&mut StatusBar::new(ui)))
+            .inner
+    }
+
+    /// Lay out with `wide` when at least `breakpoint` points are available, and `narrow` otherwise.
+    ///
+    /// The width is what's left in this ui, so the same panel switches layouts whether it is
+    /// docked in a large window or floating on its own. See [`super::BREAKPOINT_MEDIUM`] and its
+    /// neighbors for standard breakpoints.
+    fn responsive<R>(
+        &mut self,
+        breakpoint: f32,
+        wide: impl FnOnce(&mut egui::Ui) -> R,
+        narrow: impl FnOnce(&mut egui::Ui) -> R,
+    ) -> R {
+        let ui = self.ui_mut();
+        if ui.available_width() >= breakpoint {
+            wide(ui)
+        } else {
+            narrow(ui)
+        }
+    }
+
+    /// An on/off switch, with a knob that slides along a rounded track.
+    ///
+    /// Toggled by clicking, or with Space or Enter while focused. The track takes the selection
+    /// color when on, and the response is marked changed when toggled.
+    fn toggle_switch(&mut self, on: &mut bool) -> egui::Response {
+        let ui = self.ui_mut();
+        let tokens = ui.tokens();
+        let height = (0.8 * ui.spacing().interact_size.y).round();
+        let size = egui::vec2((1.8 * height).round(), height);
+
+        let (rect, mut response) = ui.allocate_exact_size(size, egui::Sense::click());
+        if response.clicked() {
+            *on = !*on;
+            response.mark_changed();
+        }
+        response.widget_info(|| {
+            egui::WidgetInfo::selected(egui::WidgetType::Checkbox, ui.is_enabled(), *on, "")
+        });
+
+        let target = if *on { 1.0 } else { 0.0 };
+        let mut how_on = ui
+            .data(|data| data.get_temp::<AnimatedValue<f32>>(response.id))
+            .unwrap_or_else(|| AnimatedValue::new(target).with_duration(0.15));
+        how_on.set_target(target);
+        let t = how_on.update_with_ctx(ui.ctx());
+        ui.data_mut(|data| data.insert_temp(response.id, how_on));
+
+        if ui.is_rect_visible(rect) {
+            let off_fill = if response.hovered() {
+                tokens.widget_hovered_bg_fill
+            } else {
+                tokens.widget_inactive_bg_fill
+            };
+            let track_fill = lerp_oklab(off_fill, tokens.selection_bg_fill, t);
+            let knob_fill = lerp_oklab(tokens.text_default, tokens.text_color_on_primary, t);
+
+            let radius = 0.5 * rect.height();
+            ui.painter().rect_filled(rect, radius, track_fill);
+            let knob_x = egui::lerp((rect.left() + radius)..=(rect.right() - radius), t);
+            ui.painter().circle_filled(
+                egui::pos2(knob_x, rect.center().y),
+                radius - 2.0,
+                knob_fill,
+            );
+        }
+
+        ui.focusable(response)
+    }
+
+    /// Mutually exclusive `options` as a row of connected buttons, with the `current` one
+    /// highlighted in the selection color.
+    ///
+    /// While focused, the left and right arrow keys move the selection. Returns whether it changed.
+    fn segmented<T: PartialEq + Clone>(&mut self, current: &mut T, options: &[(T, &str)]) -> bool {
+        let ui = self.ui_mut();
+        let tokens = ui.tokens();
+        let padding = ui.spacing().button_padding;
+        let galleys: Vec<_> = options
+            .iter()
+            .map(|(_, label)| {
+                egui::WidgetText::from(*label).into_galley(
+                    ui,
+                    Some(egui::TextWrapMode::Extend),
+                    f32::INFINITY,
+                    egui::TextStyle::Button,
+                )
+            })
+            .collect();
+        let widths: Vec<f32> = galleys
+            .iter()
+            .map(|galley| galley.size().x + 2.0 * padding.x)
+            .collect();
+        let height = galleys
+            .iter()
+            .map(|galley| galley.size().y + 2.0 * padding.y)
+            .fold(ui.spacing().interact_size.y, f32::max);
+
+        let size = egui::vec2(widths.iter().sum(), height);
+        let (rect, response) =
+            ui.allocate_exact_size(size, egui::Sense::focusable_noninteractive());
+        let selected = options.iter().position(|(value, _)| value == current);
+        let mut next = None;
+
+        // Segments run in the same direction as the rest of the row
+        let right_to_left = ui.is_right_to_left();
+        let mut x = if right_to_left {
+            rect.right()
+        } else {
+            rect.left()
+        };
+        let segment_rects: Vec<egui::Rect> = widths
+            .iter()
+            .map(|&width| {
+                let x_range = if right_to_left {
+                    x -= width;
+                    x..=x + width
+                } else {
+                    x += width;
+                    x - width..=x
+                };
+                egui::Rect::from_x_y_ranges(x_range, rect.y_range())
+            })
+            .collect();
+
+        if response.has_focus() && !options.is_empty() {
+            // Keep egui from moving focus to the next widget on left and right
+            ui.memory_mut(|memory| {
+                memory.set_focus_lock_filter(
+                    response.id,
+                    egui::EventFilter {
+                        horizontal_arrows: true,
+                        ..Default::default()
+                    },
+                );
+            });
+            let (back, forward) = if right_to_left {
+                (egui::Key::ArrowRight, egui::Key::ArrowLeft)
+            } else {
+                (egui::Key::ArrowLeft, egui::Key::ArrowRight)
+            };
+            let last = options.len() - 1;
+            ui.input_mut(|input| {
+                if input.consume_key(egui::Modifiers::NONE, back) {
+                    next = Some(selected.map_or(0, |index| index.saturating_sub(1)));
+                } else if input.consume_key(egui::Modifiers::NONE, forward) {
+                    next = Some(selected.map_or(0, |index| (index + 1).min(last)));
+                }
+            });
+        }
+
+        let corner_radius = tokens.small_corner_radius();
+        let divider = ui.visuals().widgets.noninteractive.bg_stroke;
+        ui.painter()
+            .rect_filled(rect, corner_radius, tokens.widget_inactive_bg_fill);
+        for (index, (segment_rect, galley)) in segment_rects.iter().zip(galleys).enumerate() {
+            let segment = ui.interact(*segment_rect, response.id.with(index), egui::Sense::click());
+            if segment.clicked() {
+                next = Some(index);
+                ui.memory_mut(|memory| memory.request_focus(response.id));
+            }
+
+            let is_selected = selected == Some(index);
+            let text_color = if is_selected {
+                ui.painter()
+                    .rect_filled(*segment_rect, corner_radius, tokens.selection_bg_fill);
+                tokens.text_color_on_primary
+            } else {
+                if segment.hovered() {
+                    ui.painter().rect_filled(
+                        *segment_rect,
+                        corner_radius,
+                        tokens.widget_hovered_bg_fill,
+                    );
+                }
+                tokens.text_default
+            };
+            let text_pos = segment_rect.center() - 0.5 * galley.size();
+            ui.painter().galley(text_pos, galley, text_color);
+
+            // Dividers between unselected neighbors
+            if index > 0 && !is_selected && selected != Some(index - 1) {
+                let x = if right_to_left {
+                    segment_rect.right()
+                } else {
+                    segment_rect.left()
+                };
+                ui.painter()
+                    .vline(x, segment_rect.y_range().shrink(padding.y), divider);
+            }
+        }
+        ui.focusable(response);
+
+        match next {
+            Some(index) if selected != Some(index) => {
+                *current = options[index].0.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// A single line of text, cut short with `…` when it doesn't fit the available width.
+    ///
+    /// Hovering a shortened label shows the full text.
+    fn truncated_label(&mut self, text: &str) -> egui::Response {
+        let ui = self.ui_mut();
+        let color = ui.tokens().text_default;
+        let (pos, galley, response) = egui::Label::new(text)
+            .truncate()
+            .show_tooltip_when_elided(false)
+            .layout_in_ui(ui);
+        if ui.is_rect_visible(response.rect) {
+            ui.painter().galley(pos, galley.clone(), color);
+        }
+        if galley.elided {
+            response.on_tooltip_text(text)
+        } else {
+            response
+        }
+    }
+
+    /// Size of `text` on a single line in `font`, without adding anything to the ui.
+    ///
+    /// Measuring the same text again in the same frame is free, see [`Self::text_galley`].
+    fn measure_text(&self, text: &str, font: egui::FontId) -> egui::Vec2 {
+        self.text_galley(text, font, f32::INFINITY).size()
+    }
+
+    /// `text` laid out in `font`, wrapping at `wrap_width`, to measure now and paint later.
+    ///
+    /// Galleys are cached by text, font and wrap width for the rest of the frame. They take on
+    /// the color they are painted with.
+    fn text_galley(
+        &self,
+        text: &str,
+        font: egui::FontId,
+        wrap_width: f32,
+    ) -> std::sync::Arc<egui::Galley> {
+        text_measure::galley(self.ui(), text, font, wrap_width)
+    }
+
+    /// A small rounded label for a tag or filter, with a close button when `closable`.
+    ///
+    /// `color` outlines and tints the chip. Without one, a color is picked from the text, so the
+    /// same tag looks the same everywhere. Lay out several with [`Self::chips_row`].
+    fn chip(&mut self, text: &str, color: Option<egui::Color32>, closable: bool) -> ChipResponse {
+        chip::show(self.ui_mut(), text, color, closable)
+    }
+
+    /// Chips side by side, wrapping onto more lines when they don't fit.
+    fn chips_row<R>(
+        &mut self,
+        add_chips: impl FnOnce(&mut egui::Ui) -> R,
+    ) -> egui::InnerResponse<R> {
+        self.ui_mut().horizontal_wrapped(|ui| {
+            let spacing = ui.tokens().text_to_icon_padding();
+            ui.spacing_mut().item_spacing = egui::Vec2::splat(spacing);
+            add_chips(ui)
+        })
+    }
+
+    /// A small frameless button showing `icon`.
+    ///
+    /// `alt_text` is read by screen readers, and shown as a tooltip on hover and keyboard focus.
+    fn icon_button(&mut self, icon: &Icon, alt_text: &str) -> egui::Response {
+        let ui = self.ui_mut();
+        let image = icon
+            .as_image()
+            .fit_to_exact_size(ui.tokens().small_icon_size)
+            .alt_text(alt_text);
+        let response = ui.add(
+            egui::Button::image(image)
+                .image_tint_follows_text_color(true)
+                .frame(false),
+        );
+        ui.focusable(response).on_tooltip_text(alt_text)
+    }
+
+    /// A placeholder for a panel with nothing to show, like an empty list.
+    ///
+    /// Fills the available space, with a dimmed `icon`, `title` and `subtitle` centered in it. The
+    /// text wraps to fit, and the icon is left out when there isn't room for it.
+    fn empty_state(&mut self, icon: &Icon, title: &str, subtitle: Option<&str>) -> egui::Response {
+        let ui = self.ui_mut();
+        let tokens = ui.tokens();
+        let rect = ui.available_rect_before_wrap();
+        let wrap_width = (rect.width() - 2.0 * tokens.view_padding() as f32).max(0.0);
+        let spacing = ui.spacing().item_spacing.y;
+
+        let layout = |text: egui::RichText| {
+            egui::WidgetText::from(text).into_galley(
+                ui,
+                Some(egui::TextWrapMode::Wrap),
+                wrap_width,
+                egui::TextStyle::Body,
+            )
+        };
+        let title = layout(
+            egui::RichText::new(title)
+                .strong()
+                .color(tokens.text_subdued),
+        );
+        let subtitle = subtitle
+            .map(|subtitle| layout(egui::RichText::new(subtitle).color(tokens.text_subdued)));
+
+        let icon_size = 2.0 * tokens.large_button_icon_size;
+        let text_height = title.size().y + subtitle.as_ref().map_or(0.0, |s| spacing + s.size().y);
+        let show_icon = text_height + spacing + icon_size.y <= rect.height();
+        let content_height = if show_icon {
+            icon_size.y + spacing + text_height
+        } else {
+            text_height
+        };
+
+        let response = ui.allocate_rect(rect, egui::Sense::hover());
+        let mut content_ui = ui.new_child(
+            egui::UiBuilder::new()
+                .max_rect(rect)
+                .layout(egui::Layout::top_down(egui::Align::Center)),
+        );
+        content_ui.add_space(((rect.height() - content_height) / 2.0).max(0.0));
+        if show_icon {
+            content_ui.add(
+                icon.as_image()
+                    .tint(tokens.text_subdued)
+                    .fit_to_exact_size(icon_size),
+            );
+        }
+        content_ui.add(egui::Label::new(title));
+        if let Some(subtitle) = subtitle {
+            content_ui.add(egui::Label::new(subtitle));
+        }
+        response
+    }
+
+    /// Rows of labels and values, with every value starting at the same column.
+    ///
+    /// ```ignore
+    /// ui.property_grid("camera", |grid| {
+    ///     grid.row("Near", |ui| ui.add(egui::DragValue::new(&mut near)));
+    ///     grid.row("Far", |ui| ui.add(egui::DragValue::new(&mut far)));
+    /// });
+    /// ```
+    fn property_grid<R>(
+        &mut self,
+        id_salt: impl std::hash::Hash,
+        add_rows: impl FnOnce(&mut PropertyGrid<'_>) -> R,
+    ) -> R {
+        let ui = self.ui_mut();
+        let id = ui.make_persistent_id(id_salt);
+        ui.vertical(|ui| {
+            let mut grid = PropertyGrid::new(ui, id);
+            let inner = add_rows(&mut grid);
+            grid.finish();
+            inner
+        })
+        .inner
+    }
+
+    /// A `key: value` label for a status bar, with the value formatted by
+    /// [`format_with_decimals_in_range`].
+    fn status_value(
+        &mut self,
+        key: &str,
+        value: f64,
+        decimals: RangeInclusive<usize>,
+    ) -> egui::Response {
+        let ui = self.ui_mut();
+        let tokens = ui.tokens();
+        let mut job = egui::text::LayoutJob::default();
+        egui::RichText::new(format!("{key}: "))
+            .color(tokens.text_subdued)
+            .append_to(
+                &mut job,
+                ui.style(),
+                egui::FontSelection::Default,
+                egui::Align::Center,
+            );
+        egui::RichText::new(format_with_decimals_in_range(value, decimals))
+            .monospace()
+            .append_to(
+                &mut job,
+                ui.style(),
+                egui::FontSelection::Default,
+                egui::Align::Center,
+            );
+        ui.label(job)
+    }
+
+    /// Show a texture that can be zoomed with the scroll wheel and panned by dragging.
+    ///
+    /// Starts out fitted to the available space. Double-click to fit it again. The current zoom
+    /// is shown in the bottom right corner, with [`format_percent`].
+    fn image_viewer(&mut self, texture: &egui::TextureHandle) -> egui::Response {
+        /// Zoom is in physical pixels per texel, so 1.0 is 1:1.
+        const ZOOM_RANGE: RangeInclusive<f32> = 0.01..=64.0;
+
+        #[derive(Clone, Copy)]
+        struct ViewerState {
+            /// `None` means fit to the available space.
+            zoom: Option<f32>,
+            /// Offset of the image center from the canvas center, in points.
+            pan: egui::Vec2,
+        }
+
+        let ui = self.ui_mut();
+        let id = ui.make_persistent_id(texture.id());
+        let mut state = ui
+            .data(|data| data.get_temp::<ViewerState>(id))
+            .unwrap_or(ViewerState {
+                zoom: None,
+                pan: egui::Vec2::ZERO,
+            });
+
+        let pixels_per_point = ui.pixels_per_point();
+        let texture_size = texture.size_vec2();
+
+        let (fit, one_to_one) = ui
+            .horizontal(|ui| {
+                let fit = ui.button("Fit").on_hover_text("Fit the image to the view");
+                let one_to_one = ui.button("1:1").on_hover_text("One texel per screen pixel");
+                (fit, one_to_one)
+            })
+            .inner;
+
+        let (rect, mut response) =
+            ui.allocate_exact_size(ui.available_size(), egui::Sense::click_and_drag());
+
+        let fit_zoom = (rect.size() * pixels_per_point / texture_size).min_elem();
+        let fit_zoom = fit_zoom.clamp(*ZOOM_RANGE.start(), *ZOOM_RANGE.end());
+
+        if fit.clicked() || response.double_clicked() {
+            state = ViewerState {
+                zoom: None,
+                pan: egui::Vec2::ZERO,
+            };
+            response.mark_changed();
+        }
+        if one_to_one.clicked() {
+            state.zoom = Some(1.0);
+            state.pan = egui::Vec2::ZERO;
+            response.mark_changed();
+        }
+
+        let mut zoom = state.zoom.unwrap_or(fit_zoom);
+
+        if response.dragged() {
+            state.pan += response.drag_delta();
+            response.mark_changed();
+        }
+
+        if let Some(pointer) = response.hover_pos() {
+            let (scroll, pinch) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+            let factor = (scroll / 200.0).exp() * pinch;
+            if factor != 1.0 {
+                let new_zoom = (zoom * factor).clamp(*ZOOM_RANGE.start(), *ZOOM_RANGE.end());
+                // Keep the texel under the cursor in place:
+                let cursor = pointer - rect.center();
+                state.pan = cursor - (cursor - state.pan) * (new_zoom / zoom);
+                zoom = new_zoom;
+                state.zoom = Some(zoom);
+                response.mark_changed();
+            }
+        }
+
+        let image_rect = egui::Rect::from_center_size(
+            rect.center() + state.pan,
+            texture_size * zoom / pixels_per_point,
+        );
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+        painter.image(
+            texture.id(),
+            image_rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+        painter.text(
+            rect.right_bottom() - egui::vec2(4.0, 4.0),
+            egui::Align2::RIGHT_BOTTOM,
+            format_percent(zoom as f64),
+            egui::TextStyle::Small.resolve(ui.style()),
+            ui.tokens().text_subdued,
+        );
+
+        ui.data_mut(|data| data.insert_temp(id, state));
+
+        response
+    }
+
+    /// A single line search box, with a magnifier icon and a button to clear it.
+    ///
+    /// [`egui::Response::changed`] is set whenever `query` changed this frame, so callers can
+    /// re-filter. Pressing Escape while typing clears the query.
+    fn search_field(&mut self, query: &mut String) -> egui::Response {
+        let ui = self.ui_mut();
+        let tokens = ui.tokens();
+        ui.horizontal_directed(|ui| {
+            ui.spacing_mut().item_spacing.x = tokens.text_to_icon_padding();
+            ui.add(
+                icons::SEARCH
+                    .as_image()
+                    .tint(tokens.label_button_icon_color)
+                    .fit_to_exact_size(tokens.small_icon_size),
+            );
+
+            // Leave room for the clear button
+            let clear_width = if query.is_empty() {
+                0.0
+            } else {
+                tokens.small_icon_size.x + ui.spacing().item_spacing.x
+            };
+            let mut response = ui.add(
+                egui::TextEdit::singleline(query)
+                    .hint_text("Search…")
+                    .desired_width(ui.available_width() - clear_width),
+            );
+
+            // The text edit gives up focus on Escape, swallowing the key so it doesn't reach the app
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+                if !query.is_empty() {
+                    query.clear();
+                    response.mark_changed();
+                }
+            }
+
+            if !query.is_empty() && ui.icon_button(&icons::CLOSE_SMALL, "Clear").clicked() {
+                query.clear();
+                response.mark_changed();
+                response.request_focus();
+            }
+
+            response
+        })
+        .inner
+    }
+
+    /// Ease the scroll area with id `scroll_area` toward `offset` points from its top.
+    ///
+    /// The id is [`egui::scroll_area::ScrollAreaOutput::id`]. The animation runs over the next few
+    /// frames while the area is shown through [`Self::smooth_scroll_area`], and stops as soon as
+    /// the user scrolls it themselves.
+    fn smooth_scroll_to(&self, scroll_area: egui::Id, offset: f32) {
+        smooth_scroll::scroll_to(self.ui().ctx(), scroll_area, offset);
+    }
+
+    /// Give `scroll_area` an id from `id_salt`, and move it along any animation started with
+    /// [`Self::smooth_scroll_to`].
+    fn smooth_scroll_area(
+        &self,
+        scroll_area: egui::ScrollArea,
+        id_salt: impl std::hash::Hash,
+    ) -> egui::ScrollArea {
+        let ui = self.ui();
+        let id_salt = egui::Id::new(id_salt);
+        let delta_time = ui.input(|input| input.stable_dt);
+        let scroll_area = scroll_area.id_salt(id_salt);
+        match smooth_scroll::advance(ui.ctx(), ui.make_persistent_id(id_salt), delta_time) {
+            Some(offset) => scroll_area.vertical_scroll_offset(offset),
+            None => scroll_area,
+        }
+    }
+
+    /// A scrolling list of `total` rows, each `row_height` tall, that only calls `render_row` for
+    /// the rows in view. Cheap enough for hundreds of thousands of rows.
+    ///
+    /// Rows are striped and highlight on hover. For anything with columns, use a table instead.
+    fn virtual_list(
+        &mut self,
+        total: usize,
+        row_height: f32,
+        render_row: impl FnMut(&mut egui::Ui, usize),
+    ) -> egui::Response {
+        virtual_list::show(self.ui_mut(), total, row_height, None, render_row)
+    }
+
+    /// A [`Self::virtual_list`] where clicking a row selects it.
+    ///
+    /// While the list has keyboard focus, the arrow keys, Page Up/Down, Home and End move the
+    /// selection, scrolling to keep it in view. [`egui::Response::changed`] is set when the
+    /// selection changed.
+    fn selectable_virtual_list(
+        &mut self,
+        total: usize,
+        row_height: f32,
+        selected: &mut Option<usize>,
+        render_row: impl FnMut(&mut egui::Ui, usize),
+    ) -> egui::Response {
+        virtual_list::show(self.ui_mut(), total, row_height, Some(selected), render_row)
+    }
+
+    /// `items` as rows with a drag handle each, which drag the row to a different place in the list.
+    ///
+    /// A gap opens where the row would drop, and `items` is reordered on release. Escape cancels
+    /// the drag. [`egui::Response::changed`] is set when the order changed.
+    fn reorderable_list<T>(
+        &mut self,
+        items: &mut Vec<T>,
+        render_item: impl Fn(&mut egui::Ui, &T),
+    ) -> egui::Response {
+        reorderable_list::show(self.ui_mut(), items, render_item)
+    }
+
+    /// An overview of a canvas spanning `content_rect`, with the part in view, `viewport`,
+    /// outlined on top. Both are in the canvas' own coordinates.
+    ///
+    /// `render_thumbnail` paints the canvas as usual, and egui scales it down to fit. Dragging the
+    /// outline, or clicking elsewhere, moves `viewport` and sets [`egui::Response::changed`], for
+    /// the caller to scroll its canvas to match.
+    fn minimap(
+        &mut self,
+        content_rect: egui::Rect,
+        viewport: &mut egui::Rect,
+        render_thumbnail: impl Fn(&mut egui::Ui),
+    ) -> egui::Response {
+        minimap::show(self.ui_mut(), content_rect, viewport, render_thumbnail)
+    }
+
     // fn re_checkbox<'a>(
     //     &mut self,
     //     checked: &'a mut bool,
@@ -1050,56 +2115,6 @@ pub trait UiExt {
     //     .inner
     // }
 
-    // /// Binary toggle switch.
-    // ///
-    // /// Adapted from `egui_demo_lib/src/demo/toggle_switch.rs`
-    // fn toggle_switch(&mut self, height: f32, on: &mut bool) -> egui::Response {
-    //     let ui = self.ui_mut();
-    //     let width = (height / 2. * 3.).ceil();
-    //     let size = egui::vec2(width, height); // 12x7 in figma, but 12x8 looks _much_ better in epaint
-
-    //     let (interact_rect, mut response) = ui.allocate_exact_size(size, egui::Sense::click());
-
-    //     let visual_rect = egui::Align2::CENTER_CENTER.align_size_within_rect(size, interact_rect);
-
-    //     if response.clicked() {
-    //         *on = !*on;
-    //         response.mark_changed();
-    //     }
-    //     response.widget_info(|| {
-    //         egui::WidgetInfo::selected(egui::WidgetType::Checkbox, ui.is_enabled(), *on, "")
-    //     });
-
-    //     if ui.is_rect_visible(visual_rect) {
-    //         let how_on = ui.animate_bool(response.id, *on);
-    //         let visuals = ui.style().interact(&response);
-    //         let expanded_rect = visual_rect.expand(visuals.expansion);
-    //         let fg_fill_off = visuals.bg_fill;
-    //         let fg_fill_on = ui.visuals().selection.bg_fill;
-    //         let fg_fill = fg_fill_off.lerp_to_gamma(fg_fill_on, how_on);
-    //         let bg_fill_off = visuals.text_color();
-
-    //         let corner_radius = 0.5 * expanded_rect.height();
-    //         ui.painter()
-    //             .rect_filled(expanded_rect, corner_radius, bg_fill_off);
-    //         let circle_x = egui::lerp(
-    //             (expanded_rect.left() + corner_radius)..=(expanded_rect.right() - corner_radius),
-    //             how_on,
-    //         );
-
-    //         let circle_center = egui::pos2(circle_x, expanded_rect.center().y);
-    //         let circle_radius_off = 0.3 * expanded_rect.height();
-    //         let circle_radius_on = 0.35 * expanded_rect.height();
-    //         ui.painter().circle_filled(
-    //             circle_center,
-    //             egui::lerp(circle_radius_off..=circle_radius_on, how_on),
-    //             fg_fill,
-    //         );
-    //     }
-
-    //     response
-    // }
-
     // /// Helper for adding a list-item hyperlink.
     // ///
     // /// By default, the url is open in the same tab or a new tab based on the mouse button and
@@ -1450,6 +2465,24 @@ pub trait UiExt {
     //     ))
     // }
 
+    /// Draw the focus ring around `response` if it has keyboard focus.
+    ///
+    /// Custom widgets should pass their response through this, so that Tab and Shift-Tab
+    /// navigation looks the same everywhere.
+    fn focusable(&mut self, response: egui::Response) -> egui::Response {
+        let ui = self.ui_mut();
+        if response.has_focus() && ui.is_rect_visible(response.rect) {
+            let tokens = ui.tokens();
+            ui.painter().rect_stroke(
+                response.rect.expand(1.0),
+                ui.visuals().widgets.active.corner_radius,
+                tokens.focus_outline_stroke,
+                egui::StrokeKind::Outside,
+            );
+        }
+        response
+    }
+
     /// Set the current style for a text field that has invalid content.
     fn style_invalid_field(&mut self) {
         let ui = self.ui_mut();
@@ -1474,3 +2507,100 @@ impl UiExt for egui::Ui {
         self
     }
 }
+
+/// [`UiExt::drag_value_formatted`], changing by `speed` per point dragged.
+pub(super) fn drag_value_formatted(
+    ui: &mut egui::Ui,
+    value: &mut f64,
+    range: RangeInclusive<f64>,
+    decimals: RangeInclusive<usize>,
+    speed: f64,
+) -> egui::Response {
+    let tokens = ui.tokens();
+    let focus_id = ui.auto_id_with("drag_value_formatted_focus");
+    let nudged = nudge_with_arrow_keys(ui, focus_id, value, range.clone(), *decimals.end());
+    let mut response = ui
+        .scope(|ui| {
+            ui.visuals_mut().extreme_bg_color = tokens.text_edit_bg_color;
+            let parse_range = range.clone();
+            ui.add(
+                egui::DragValue::new(value)
+                    .speed(speed)
+                    .range(range)
+                    .min_decimals(*decimals.start())
+                    .max_decimals(*decimals.end())
+                    .custom_formatter(move |value, _| {
+                        format_with_decimals_in_range(value, decimals.clone())
+                    })
+                    .custom_parser(move |text| {
+                        parse_f64(text).map(|value| sanitize_f64(value, parse_range.clone()))
+                    }),
+            )
+        })
+        .inner;
+    remember_focus(ui, focus_id, &response);
+    if nudged {
+        response.mark_changed();
+    }
+    ui.focusable(response)
+}
+
+/// Nudge `value` with the up and down arrow keys, if the widget remembered under `focus_id` had
+/// keyboard focus last frame.
+///
+/// The step is [`nudge_step`] for the most `decimals` the widget edits to, so it doesn't change
+/// with how many the current value shows. The keys are consumed, so the widget itself doesn't
+/// also step. Returns whether `value` changed.
+fn nudge_with_arrow_keys(
+    ui: &mut egui::Ui,
+    focus_id: egui::Id,
+    value: &mut f64,
+    range: RangeInclusive<f64>,
+    decimals: usize,
+) -> bool {
+    let had_focus = ui.data(|data| data.get_temp::<bool>(focus_id).unwrap_or(false));
+    if !had_focus {
+        return false;
+    }
+
+    let (presses, modifiers) = ui.input_mut(|input| {
+        let mut presses = 0i32;
+        input.events.retain(|event| match event {
+            egui::Event::Key {
+                key: egui::Key::ArrowUp,
+                pressed: true,
+                ..
+            } => {
+                presses += 1;
+                false
+            }
+            egui::Event::Key {
+                key: egui::Key::ArrowDown,
+                pressed: true,
+                ..
+            } => {
+                presses -= 1;
+                false
+            }
+            _ => true,
+        });
+        (presses, input.modifiers)
+    });
+    if presses == 0 {
+        return false;
+    }
+
+    let step = nudge_step(decimals, modifiers);
+    // Round to the step's own decimals, so repeated nudges don't pile up float noise
+    let scale = 10f64.powi((-step.log10().round()).max(0.0) as i32);
+    let nudged = ((*value + presses as f64 * step) * scale).round() / scale;
+    let nudged = sanitize_f64(nudged, range);
+    let changed = nudged != *value;
+    *value = nudged;
+    changed
+}
+
+fn remember_focus(ui: &egui::Ui, focus_id: egui::Id, response: &egui::Response) {
+    let has_focus = response.has_focus();
+    ui.data_mut(|data| data.insert_temp(focus_id, has_focus));
+}
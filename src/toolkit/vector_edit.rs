@@ -0,0 +1,243 @@
+//! Editors for vectors, rotations and matrices, with a labeled field per component, see
+//! [`super::UiExt::vec3_edit`].
+
+use std::ops::RangeInclusive;
+
+use super::icons;
+use super::ui_ext::drag_value_formatted;
+use super::{DesignTokens, TooltipExt as _, UiExt as _};
+
+/// Labels of the components, in order.
+const AXES: [&str; 4] = ["X", "Y", "Z", "W"];
+
+/// Edit a vec3, see [`super::UiExt::vec3_edit`] for the defaults.
+///
+/// ```ignore
+/// let mut scale = transform.scale.to_array();
+/// let response = Vec3Edit::new(&mut scale)
+///     .default([1.0; 3])
+///     .speed(0.01)
+///     .linked(&mut uniform_scale)
+///     .show(ui);
+/// ```
+pub struct Vec3Edit<'a> {
+    value: &'a mut [f32; 3],
+    default: [f32; 3],
+    speed: f64,
+    decimals: RangeInclusive<usize>,
+    linked: Option<&'a mut bool>,
+}
+
+impl<'a> Vec3Edit<'a> {
+    pub fn new(value: &'a mut [f32; 3]) -> Self {
+        Self {
+            value,
+            default: [0.0; 3],
+            speed: 0.1,
+            decimals: 0..=3,
+            linked: None,
+        }
+    }
+
+    /// What the reset button sets the vector back to.
+    pub fn default(mut self, default: [f32; 3]) -> Self {
+        self.default = default;
+        self
+    }
+
+    /// How much a component changes per point dragged.
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn decimals(mut self, decimals: RangeInclusive<usize>) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Add a toggle that, while on, keeps the ratios between the components when one is edited,
+    /// like a uniform scale.
+    pub fn linked(mut self, linked: &'a mut bool) -> Self {
+        self.linked = Some(linked);
+        self
+    }
+
+    pub fn show(self, ui: &mut egui::Ui) -> egui::Response {
+        components_ui(
+            ui,
+            self.value,
+            &self.default,
+            self.speed,
+            self.decimals,
+            self.linked,
+        )
+    }
+}
+
+/// A row of X/Y/Z/W fields for `values`, in the axis colors, with a button resetting them to
+/// `default`.
+fn components_ui(
+    ui: &mut egui::Ui,
+    values: &mut [f32],
+    default: &[f32],
+    speed: f64,
+    decimals: RangeInclusive<usize>,
+    linked: Option<&mut bool>,
+) -> egui::Response {
+    debug_assert_eq!(values.len(), default.len());
+    debug_assert!(values.len() <= AXES.len());
+    let tokens = ui.tokens();
+
+    ui.horizontal(|ui| {
+        let before = values.to_vec();
+        let mut response = ui.response();
+        let mut edited = None;
+        for (index, value) in values.iter_mut().enumerate() {
+            ui.label(
+                egui::RichText::new(AXES[index])
+                    .strong()
+                    .color(axis_color(tokens, index)),
+            );
+            let mut edit = f64::from(*value);
+            let field = drag_value_formatted(
+                ui,
+                &mut edit,
+                f64::NEG_INFINITY..=f64::INFINITY,
+                decimals.clone(),
+                speed,
+            );
+            if field.changed() {
+                *value = edit as f32;
+                edited = Some(index);
+            }
+            response |= field;
+        }
+
+        if let Some(linked) = linked {
+            let alt_text = if *linked {
+                "Unlink components"
+            } else {
+                "Link components"
+            };
+            let image = icons::CHAIN
+                .as_image()
+                .fit_to_exact_size(tokens.small_icon_size)
+                .alt_text(alt_text);
+            let toggle = ui.add(
+                egui::Button::image(image)
+                    .image_tint_follows_text_color(true)
+                    .selected(*linked)
+                    .frame_when_inactive(false),
+            );
+            if toggle.clicked() {
+                *linked = !*linked;
+            }
+            response |= ui.focusable(toggle).on_tooltip_text(alt_text);
+
+            if *linked && let Some(index) = edited {
+                link_components(values, &before, index);
+            }
+        }
+
+        let is_default = values == default;
+        let reset = ui
+            .add_enabled_ui(!is_default, |ui| ui.icon_button(&icons::RESET, "Reset"))
+            .inner;
+        if reset.clicked() {
+            values.copy_from_slice(default);
+            response.mark_changed();
+        }
+        response | reset
+    })
+    .inner
+}
+
+/// Scale every component by as much as the one at `edited` changed from `before`. From zero there
+/// is no ratio to keep, so they all take its new value.
+fn link_components(values: &mut [f32], before: &[f32], edited: usize) {
+    let new = values[edited];
+    let old = before[edited];
+    for (index, (value, before)) in values.iter_mut().zip(before).enumerate() {
+        if index == edited {
+            continue;
+        }
+        *value = if old == 0.0 {
+            new
+        } else {
+            before * (new / old)
+        };
+    }
+}
+
+fn axis_color(tokens: &DesignTokens, index: usize) -> egui::Color32 {
+    match index {
+        0 => tokens.axis_color_x,
+        1 => tokens.axis_color_y,
+        2 => tokens.axis_color_z,
+        _ => tokens.text_subdued,
+    }
+}
+
+pub(super) fn vec3(ui: &mut egui::Ui, value: &mut [f32; 3]) -> egui::Response {
+    Vec3Edit::new(value).show(ui)
+}
+
+/// `rotation` as euler angles in degrees, which is far easier to reason about.
+pub(super) fn quat(ui: &mut egui::Ui, rotation: &mut glam::Quat) -> egui::Response {
+    let (y, x, z) = rotation.to_euler(glam::EulerRot::YXZ);
+    let mut euler = [x.to_degrees(), y.to_degrees(), z.to_degrees()];
+    let response = Vec3Edit::new(&mut euler)
+        .speed(1.0)
+        .decimals(0..=1)
+        .show(ui);
+    if response.changed() {
+        let [x, y, z] = euler;
+        *rotation = glam::Quat::from_euler(
+            glam::EulerRot::YXZ,
+            y.to_radians(),
+            x.to_radians(),
+            z.to_radians(),
+        );
+    }
+    response
+}
+
+/// One row per column of `matrix`, so the basis vectors and then the translation, each reset to
+/// that of the identity on its own.
+pub(super) fn mat4(ui: &mut egui::Ui, matrix: &mut glam::Mat4) -> egui::Response {
+    let mut columns = matrix.to_cols_array_2d();
+    let identity = glam::Mat4::IDENTITY.to_cols_array_2d();
+    let response = ui
+        .vertical(|ui| {
+            let mut response = ui.response();
+            for (column, default) in columns.iter_mut().zip(&identity) {
+                response |= components_ui(ui, column, default, 0.01, 0..=3, None);
+            }
+            response
+        })
+        .inner;
+    if response.changed() {
+        *matrix = glam::Mat4::from_cols_array_2d(&columns);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::link_components;
+
+    #[test]
+    fn linked_components_keep_their_ratios() {
+        let before = [1.0, 2.0, -4.0];
+        let mut values = [1.0, 3.0, -4.0];
+        link_components(&mut values, &before, 1);
+        assert_eq!(values, [1.5, 3.0, -6.0]);
+
+        // Nothing to scale from zero, so the others follow the edited one
+        let before = [0.0, 2.0, 5.0];
+        let mut values = [0.5, 2.0, 5.0];
+        link_components(&mut values, &before, 0);
+        assert_eq!(values, [0.5; 3]);
+    }
+}
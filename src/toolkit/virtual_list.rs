@@ -0,0 +1,143 @@
+//! A scrolling list that only lays out the rows in view, see [`super::UiExt::virtual_list`].
+
+use super::HasDesignTokens as _;
+use super::UiExt as _;
+
+/// Show `total` rows of `row_height`, calling `render_row` for the visible ones.
+///
+/// With `selected`, clicking a row selects it, and the arrow keys move the selection while the
+/// list has keyboard focus, scrolling to keep it in view.
+pub(super) fn show(
+    ui: &mut egui::Ui,
+    total: usize,
+    row_height: f32,
+    mut selected: Option<&mut Option<usize>>,
+    mut render_row: impl FnMut(&mut egui::Ui, usize),
+) -> egui::Response {
+    let tokens = ui.tokens();
+    let id = ui.auto_id_with("virtual_list");
+    let has_focus = ui.memory(|memory| memory.has_focus(id));
+
+    // Keyboard navigation
+    let mut changed = false;
+    let mut scroll_to_selection = false;
+    if let Some(selected) = selected.as_deref_mut()
+        && has_focus
+        && total > 0
+    {
+        // Keep egui from moving focus to the next widget on up and down
+        ui.memory_mut(|memory| {
+            memory.set_focus_lock_filter(
+                id,
+                egui::EventFilter {
+                    vertical_arrows: true,
+                    ..Default::default()
+                },
+            );
+        });
+
+        let page = ((ui.available_height() / row_height).floor() as usize).max(1);
+        let last = total - 1;
+        let next = ui.input_mut(|input| {
+            let current = *selected;
+            if input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown) {
+                Some(current.map_or(0, |index| (index + 1).min(last)))
+            } else if input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp) {
+                Some(current.map_or(last, |index| index.saturating_sub(1)))
+            } else if input.consume_key(egui::Modifiers::NONE, egui::Key::PageDown) {
+                Some(current.map_or(0, |index| (index + page).min(last)))
+            } else if input.consume_key(egui::Modifiers::NONE, egui::Key::PageUp) {
+                Some(current.map_or(0, |index| index.saturating_sub(page)))
+            } else if input.consume_key(egui::Modifiers::NONE, egui::Key::Home) {
+                Some(0)
+            } else if input.consume_key(egui::Modifiers::NONE, egui::Key::End) {
+                Some(last)
+            } else {
+                None
+            }
+        });
+        if let Some(next) = next {
+            changed = *selected != Some(next);
+            *selected = Some(next);
+            scroll_to_selection = true;
+        }
+    }
+
+    let scroll_id = ui.make_persistent_id(egui::Id::new(id));
+    let output = ui
+        .smooth_scroll_area(egui::ScrollArea::vertical(), id)
+        .auto_shrink([false, true])
+        .show_rows(ui, row_height, total, |ui, rows| {
+            let row_height_with_spacing = row_height + ui.spacing().item_spacing.y;
+            let top = ui.max_rect().top() - rows.start as f32 * row_height_with_spacing;
+            let left = ui.max_rect().left();
+            let width = ui.max_rect().width();
+            let row_rect = |index: usize| {
+                egui::Rect::from_min_size(
+                    egui::pos2(left, top + index as f32 * row_height_with_spacing),
+                    egui::vec2(width, row_height),
+                )
+            };
+
+            // The selected row may be out of view, and so never laid out
+            if scroll_to_selection && let Some(Some(index)) = selected.as_deref() {
+                let row = row_rect(*index);
+                let view = ui.clip_rect();
+                let offset = egui::scroll_area::State::load(ui.ctx(), scroll_id)
+                    .map_or(0.0, |state| state.offset.y);
+                if row.top() < view.top() {
+                    ui.smooth_scroll_to(scroll_id, offset + row.top() - view.top());
+                } else if row.bottom() > view.bottom() {
+                    ui.smooth_scroll_to(scroll_id, offset + row.bottom() - view.bottom());
+                }
+            }
+
+            for index in rows {
+                let rect = row_rect(index);
+                let response = ui.interact(rect, id.with(index), egui::Sense::click());
+                let is_selected = selected.as_deref() == Some(&Some(index));
+
+                let fill = if is_selected {
+                    Some(tokens.table_interaction_row_selection_fill)
+                } else if response.hovered() {
+                    Some(tokens.list_item_hovered_bg)
+                } else if index % 2 == 1 {
+                    Some(tokens.faint_bg_color)
+                } else {
+                    None
+                };
+                if let Some(fill) = fill {
+                    ui.painter().rect_filled(rect, 0.0, fill);
+                }
+
+                if response.clicked()
+                    && let Some(selected) = selected.as_deref_mut()
+                {
+                    changed |= *selected != Some(index);
+                    *selected = Some(index);
+                    ui.memory_mut(|memory| memory.request_focus(id));
+                }
+
+                let layout = if ui.is_right_to_left() {
+                    egui::Layout::right_to_left(egui::Align::Center)
+                } else {
+                    egui::Layout::left_to_right(egui::Align::Center)
+                };
+                let mut row_ui = ui.new_child(egui::UiBuilder::new().max_rect(rect).layout(layout));
+                row_ui.set_clip_rect(rect.intersect(ui.clip_rect()));
+                render_row(&mut row_ui, index);
+                ui.advance_cursor_after_rect(rect);
+            }
+        });
+
+    let sense = if selected.is_some() {
+        egui::Sense::focusable_noninteractive()
+    } else {
+        egui::Sense::hover()
+    };
+    let mut response = ui.interact(output.inner_rect, id, sense);
+    if changed {
+        response.mark_changed();
+    }
+    ui.focusable(response)
+}